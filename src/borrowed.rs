@@ -0,0 +1,1873 @@
+// This file is part of rss.
+//
+// Copyright © 2015-2021 The rust-syndication Developers
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the MIT License and/or Apache 2.0 License.
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::BufRead;
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::Range;
+use std::str;
+
+use quick_xml::events::attributes::{Attribute, Attributes};
+use quick_xml::events::{BytesCData, BytesStart, BytesText, Event};
+use quick_xml::Reader;
+
+use crate::category::Category;
+use crate::channel::Channel;
+use crate::cloud::Cloud;
+use crate::enclosure::Enclosure;
+use crate::error::{Error, LimitKind, ParseError};
+use crate::extension::syndication;
+use crate::extension::{Extension, ExtensionMap};
+use crate::guid::Guid;
+use crate::image::Image;
+use crate::input::{BufSource, SliceSource, XmlSource};
+use crate::item::Item;
+use crate::markup::{Markup, TextKind};
+use crate::parser::{Context, ParseOptions, ParseWarning};
+use crate::source::Source;
+use crate::textinput::TextInput;
+use crate::unknown::UnknownElement;
+use crate::util::{
+    is_namespace_declaration, is_rdf_attribute, sort_items, QName, CONTENT_NAMESPACE, RDF_NAMESPACE,
+};
+
+/// A map of borrowed extension namespace prefixes to local names to elements.
+pub type ExtensionMapRef<'a> =
+    BTreeMap<Cow<'a, str>, BTreeMap<Cow<'a, str>, Vec<ExtensionRef<'a>>>>;
+
+/// A channel that borrows its text from the input it was read from.
+///
+/// Text that contains entities is unescaped into an owned string, all other text is borrowed.
+/// The iTunes, Dublin Core, Syndication and Atom extensions are kept in `extensions` and are
+/// only parsed by `into_owned`.
+///
+/// # Examples
+///
+/// ```
+/// use std::borrow::Cow;
+/// use rss::ChannelRef;
+///
+/// let input = r#"
+/// <rss version="2.0">
+///   <channel>
+///     <title>Title</title>
+///     <item><title>Fish &amp; Chips</title></item>
+///   </channel>
+/// </rss>
+/// "#;
+///
+/// let channel = ChannelRef::parse(input).unwrap();
+/// assert!(matches!(channel.title, Cow::Borrowed("Title")));
+/// assert!(matches!(channel.items[0].title, Some(Cow::Owned(_))));
+///
+/// let channel = channel.into_owned();
+/// assert_eq!(channel.items()[0].title(), Some("Fish & Chips"));
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ChannelRef<'a> {
+    /// The name of the channel.
+    pub title: Cow<'a, str>,
+    /// The URL for the website corresponding to the channel.
+    pub link: Cow<'a, str>,
+    /// A description of the channel.
+    pub description: Cow<'a, str>,
+    /// The kind of text in the description.
+    pub description_kind: TextKind,
+    /// The language of the channel.
+    pub language: Option<Cow<'a, str>>,
+    /// The copyright notice for the channel.
+    pub copyright: Option<Cow<'a, str>>,
+    /// The email address for the managing editor.
+    pub managing_editor: Option<Cow<'a, str>>,
+    /// The email address for the webmaster.
+    pub webmaster: Option<Cow<'a, str>>,
+    /// The publication date for the content of the channel as an RFC822 timestamp.
+    pub pub_date: Option<Cow<'a, str>>,
+    /// The date that the contents of the channel last changed as an RFC822 timestamp.
+    pub last_build_date: Option<Cow<'a, str>>,
+    /// The categories the channel belongs to.
+    pub categories: Vec<CategoryRef<'a>>,
+    /// A string indicating the program used to generate the channel.
+    pub generator: Option<Cow<'a, str>>,
+    /// A URL that points to the documentation for the RSS format.
+    pub docs: Option<Cow<'a, str>>,
+    /// The cloud to register with to be notified of updates to the channel.
+    pub cloud: Option<CloudRef<'a>>,
+    /// The PICS rating for the channel.
+    pub rating: Option<Cow<'a, str>>,
+    /// The number of minutes the channel can be cached before refreshing.
+    pub ttl: Option<Cow<'a, str>>,
+    /// An image that can be displayed with the channel.
+    pub image: Option<ImageRef<'a>>,
+    /// A text input box that can be displayed with the channel.
+    pub text_input: Option<TextInputRef<'a>>,
+    /// A hint to tell the aggregator which hours it can skip.
+    pub skip_hours: Vec<Cow<'a, str>>,
+    /// A hint to tell the aggregator which days it can skip.
+    pub skip_days: Vec<Cow<'a, str>>,
+    /// The items in the channel.
+    pub items: Vec<ItemRef<'a>>,
+    /// The extensions for the channel.
+    pub extensions: ExtensionMapRef<'a>,
+    /// The namespaces declared on the RSS and channel tags.
+    pub namespaces: BTreeMap<String, String>,
+    /// The URI that identifies the channel in an RSS 1.0 feed, from its `rdf:about` attribute.
+    pub about: Option<Cow<'a, str>>,
+    /// The elements without a namespace prefix that are not part of RSS.
+    pub unknown_elements: Vec<UnknownElementRef<'a>>,
+    /// The attributes of the channel element that are not part of RSS, keyed by qualified name.
+    pub extra_attrs: BTreeMap<Cow<'a, str>, Cow<'a, str>>,
+    /// The namespaces of the extensions, keyed by prefix.
+    extension_namespaces: BTreeMap<String, String>,
+}
+
+impl<'a> ChannelRef<'a> {
+    /// Read a channel from a string, borrowing as much of its text as possible.
+    pub fn parse(input: &'a str) -> Result<Self, ParseError> {
+        ChannelRef::parse_with_options(input, ParseOptions::default()).map(|(channel, _)| channel)
+    }
+
+    /// Read a channel from a string using the given options, returning it along with the
+    /// problems in the input that were skipped or repaired.
+    ///
+    /// The input is already UTF-8, so `ParseOptions::charset` and the encoding in the XML
+    /// declaration are ignored.
+    pub fn parse_with_options(
+        input: &'a str,
+        options: ParseOptions,
+    ) -> Result<(Self, Vec<ParseWarning>), ParseError> {
+        let mut context = Context::new(options);
+        let mut source = SliceSource::new(input, context.limits().max_input_len);
+        let result = ChannelParser::read_document(&mut Parser::new(&mut source, &mut context));
+        match result {
+            Ok(channel) => Ok((channel, context.into_warnings())),
+            Err(err) => Err(context.locate(err)),
+        }
+    }
+
+    /// Convert this channel to a `Channel` that owns its text.
+    pub fn into_owned(self) -> Channel {
+        let mut channel = Channel {
+            title: self.title.into_owned(),
+            link: self.link.into_owned(),
+            description: self.description.into_owned(),
+            description_kind: self.description_kind,
+            language: self.language.map(Cow::into_owned),
+            copyright: self.copyright.map(Cow::into_owned),
+            managing_editor: self.managing_editor.map(Cow::into_owned),
+            webmaster: self.webmaster.map(Cow::into_owned),
+            pub_date: self.pub_date.map(Cow::into_owned),
+            last_build_date: self.last_build_date.map(Cow::into_owned),
+            categories: self
+                .categories
+                .into_iter()
+                .map(CategoryRef::into_owned)
+                .collect(),
+            generator: self.generator.map(Cow::into_owned),
+            docs: self.docs.map(Cow::into_owned),
+            cloud: self.cloud.map(CloudRef::into_owned),
+            rating: self.rating.map(Cow::into_owned),
+            ttl: self.ttl.map(Cow::into_owned),
+            image: self.image.map(ImageRef::into_owned),
+            text_input: self.text_input.map(TextInputRef::into_owned),
+            skip_hours: self.skip_hours.into_iter().map(Cow::into_owned).collect(),
+            skip_days: self.skip_days.into_iter().map(Cow::into_owned).collect(),
+            items: self.items.into_iter().map(ItemRef::into_owned).collect(),
+            extensions: extensions_into_owned(self.extensions),
+            namespaces: self.namespaces,
+            about: self.about.map(Cow::into_owned),
+            unknown_elements: self
+                .unknown_elements
+                .into_iter()
+                .map(UnknownElementRef::into_owned)
+                .collect(),
+            extra_attrs: attrs_into_owned(self.extra_attrs),
+            ..Default::default()
+        };
+        // the values that are not valid were reported when the channel was read
+        channel.parse_extensions(&self.extension_namespaces);
+        channel
+    }
+}
+
+/// An item that borrows its text from the input it was read from.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ItemRef<'a> {
+    /// The title of the item.
+    pub title: Option<Cow<'a, str>>,
+    /// The URL of the item.
+    pub link: Option<Cow<'a, str>>,
+    /// The item synopsis.
+    pub description: Option<Cow<'a, str>>,
+    /// The kind of text in the description.
+    pub description_kind: TextKind,
+    /// The email address of author of the item.
+    pub author: Option<Cow<'a, str>>,
+    /// The categories the item belongs to.
+    pub categories: Vec<CategoryRef<'a>>,
+    /// The URL for the comments page of the item.
+    pub comments: Option<Cow<'a, str>>,
+    /// The description of a media object that is attached to the item.
+    pub enclosure: Option<EnclosureRef<'a>>,
+    /// A unique identifier for the item.
+    pub guid: Option<GuidRef<'a>>,
+    /// The date the item was published as an RFC 2822 timestamp.
+    pub pub_date: Option<Cow<'a, str>>,
+    /// The RSS channel the item came from.
+    pub source: Option<SourceRef<'a>>,
+    /// The HTML contents of the item.
+    pub content: Option<Cow<'a, str>>,
+    /// The kind of text in the contents.
+    pub content_kind: TextKind,
+    /// The URI that identifies the item in an RSS 1.0 feed, from its `rdf:about` attribute.
+    pub about: Option<Cow<'a, str>>,
+    /// The elements without a namespace prefix that are not part of RSS.
+    pub unknown_elements: Vec<UnknownElementRef<'a>>,
+    /// The extensions for the item.
+    pub extensions: ExtensionMapRef<'a>,
+    /// The attributes of the item element that are not part of RSS, keyed by qualified name.
+    pub extra_attrs: BTreeMap<Cow<'a, str>, Cow<'a, str>>,
+    /// The namespaces of the extensions, keyed by prefix.
+    extension_namespaces: BTreeMap<String, String>,
+}
+
+impl<'a> ItemRef<'a> {
+    /// Convert this item to an `Item` that owns its text.
+    pub fn into_owned(self) -> Item {
+        let mut item = Item {
+            title: self.title.map(Cow::into_owned),
+            link: self.link.map(Cow::into_owned),
+            description: self.description.map(Cow::into_owned),
+            description_kind: self.description_kind,
+            author: self.author.map(Cow::into_owned),
+            categories: self
+                .categories
+                .into_iter()
+                .map(CategoryRef::into_owned)
+                .collect(),
+            comments: self.comments.map(Cow::into_owned),
+            enclosure: self.enclosure.map(EnclosureRef::into_owned),
+            guid: self.guid.map(GuidRef::into_owned),
+            pub_date: self.pub_date.map(Cow::into_owned),
+            source: self.source.map(SourceRef::into_owned),
+            content: self.content.map(Cow::into_owned),
+            content_kind: self.content_kind,
+            about: self.about.map(Cow::into_owned),
+            unknown_elements: self
+                .unknown_elements
+                .into_iter()
+                .map(UnknownElementRef::into_owned)
+                .collect(),
+            extensions: extensions_into_owned(self.extensions),
+            extra_attrs: attrs_into_owned(self.extra_attrs),
+            ..Default::default()
+        };
+        item.parse_extensions(&self.extension_namespaces);
+        item
+    }
+}
+
+/// A category that borrows its text from the input it was read from.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CategoryRef<'a> {
+    /// The name of the category.
+    pub name: Cow<'a, str>,
+    /// The domain for the category.
+    pub domain: Option<Cow<'a, str>>,
+    /// The attributes of the category element that are not part of RSS, keyed by qualified name.
+    pub extra_attrs: BTreeMap<Cow<'a, str>, Cow<'a, str>>,
+}
+
+impl<'a> CategoryRef<'a> {
+    /// Convert this category to a `Category` that owns its text.
+    pub fn into_owned(self) -> Category {
+        Category {
+            name: self.name.into_owned(),
+            domain: self.domain.map(Cow::into_owned),
+            extra_attrs: attrs_into_owned(self.extra_attrs),
+        }
+    }
+}
+
+/// A cloud that borrows its text from the input it was read from.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct CloudRef<'a> {
+    /// The domain to register with.
+    pub domain: Cow<'a, str>,
+    /// The port to register with.
+    pub port: Cow<'a, str>,
+    /// The path to register with.
+    pub path: Cow<'a, str>,
+    /// The procedure to register with.
+    pub register_procedure: Cow<'a, str>,
+    /// The protocol to register with.
+    pub protocol: Cow<'a, str>,
+    /// The attributes of the cloud element that are not part of RSS, keyed by qualified name.
+    pub extra_attrs: BTreeMap<Cow<'a, str>, Cow<'a, str>>,
+}
+
+impl<'a> CloudRef<'a> {
+    /// Convert this cloud to a `Cloud` that owns its text.
+    pub fn into_owned(self) -> Cloud {
+        Cloud {
+            domain: self.domain.into_owned(),
+            port: self.port.into_owned(),
+            path: self.path.into_owned(),
+            register_procedure: self.register_procedure.into_owned(),
+            protocol: self.protocol.into_owned(),
+            extra_attrs: attrs_into_owned(self.extra_attrs),
+        }
+    }
+}
+
+/// An enclosure that borrows its text from the input it was read from.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct EnclosureRef<'a> {
+    /// The URL of the enclosure.
+    pub url: Cow<'a, str>,
+    /// The length of the enclosure in bytes.
+    pub length: Cow<'a, str>,
+    /// The MIME type of the enclosure.
+    pub mime_type: Cow<'a, str>,
+    /// The attributes of the enclosure element that are not part of RSS, keyed by qualified name.
+    pub extra_attrs: BTreeMap<Cow<'a, str>, Cow<'a, str>>,
+}
+
+impl<'a> EnclosureRef<'a> {
+    /// Convert this enclosure to an `Enclosure` that owns its text.
+    pub fn into_owned(self) -> Enclosure {
+        Enclosure {
+            url: self.url.into_owned(),
+            length: self.length.into_owned(),
+            mime_type: self.mime_type.into_owned(),
+            extra_attrs: attrs_into_owned(self.extra_attrs),
+        }
+    }
+}
+
+/// A GUID that borrows its text from the input it was read from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GuidRef<'a> {
+    /// The value of the GUID.
+    pub value: Cow<'a, str>,
+    /// Indicates if the GUID is a permalink.
+    pub permalink: bool,
+    /// The attributes of the GUID element that are not part of RSS, keyed by qualified name.
+    pub extra_attrs: BTreeMap<Cow<'a, str>, Cow<'a, str>>,
+}
+
+impl<'a> Default for GuidRef<'a> {
+    fn default() -> Self {
+        GuidRef {
+            value: Default::default(),
+            permalink: true,
+            extra_attrs: BTreeMap::new(),
+        }
+    }
+}
+
+impl<'a> GuidRef<'a> {
+    /// Convert this GUID to a `Guid` that owns its text.
+    pub fn into_owned(self) -> Guid {
+        Guid {
+            value: self.value.into_owned(),
+            permalink: self.permalink,
+            extra_attrs: attrs_into_owned(self.extra_attrs),
+        }
+    }
+}
+
+/// An image that borrows its text from the input it was read from.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ImageRef<'a> {
+    /// The URL of the image.
+    pub url: Cow<'a, str>,
+    /// A description of the image. This is used in the HTML `alt` attribute.
+    pub title: Cow<'a, str>,
+    /// The URL that the image links to.
+    pub link: Cow<'a, str>,
+    /// The width of the image.
+    pub width: Option<Cow<'a, str>>,
+    /// The height of the image.
+    pub height: Option<Cow<'a, str>>,
+    /// The text for the HTML `title` attribute.
+    pub description: Option<Cow<'a, str>>,
+    /// The URI that identifies the image in an RSS 1.0 feed, from its `rdf:about` attribute.
+    pub about: Option<Cow<'a, str>>,
+    /// The attributes of the image element that are not part of RSS, keyed by qualified name.
+    pub extra_attrs: BTreeMap<Cow<'a, str>, Cow<'a, str>>,
+}
+
+impl<'a> ImageRef<'a> {
+    /// Convert this image to an `Image` that owns its text.
+    pub fn into_owned(self) -> Image {
+        Image {
+            url: self.url.into_owned(),
+            title: self.title.into_owned(),
+            link: self.link.into_owned(),
+            width: self.width.map(Cow::into_owned),
+            height: self.height.map(Cow::into_owned),
+            description: self.description.map(Cow::into_owned),
+            about: self.about.map(Cow::into_owned),
+            extra_attrs: attrs_into_owned(self.extra_attrs),
+        }
+    }
+}
+
+/// A source that borrows its text from the input it was read from.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SourceRef<'a> {
+    /// The URL of the source.
+    pub url: Cow<'a, str>,
+    /// The title of the source.
+    pub title: Option<Cow<'a, str>>,
+    /// The attributes of the source element that are not part of RSS, keyed by qualified name.
+    pub extra_attrs: BTreeMap<Cow<'a, str>, Cow<'a, str>>,
+}
+
+impl<'a> SourceRef<'a> {
+    /// Convert this source to a `Source` that owns its text.
+    pub fn into_owned(self) -> Source {
+        Source {
+            url: self.url.into_owned(),
+            title: self.title.map(Cow::into_owned),
+            extra_attrs: attrs_into_owned(self.extra_attrs),
+        }
+    }
+}
+
+/// A text input that borrows its text from the input it was read from.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TextInputRef<'a> {
+    /// The label of the Submit button for the text input.
+    pub title: Cow<'a, str>,
+    /// A description of the text input.
+    pub description: Cow<'a, str>,
+    /// The name of the text object.
+    pub name: Cow<'a, str>,
+    /// The URL of the CGI script that processes the text input request.
+    pub link: Cow<'a, str>,
+    /// The URI that identifies the text input in an RSS 1.0 feed, from its `rdf:about`
+    /// attribute.
+    pub about: Option<Cow<'a, str>>,
+    /// The attributes of the text input element that are not part of RSS, keyed by qualified name.
+    pub extra_attrs: BTreeMap<Cow<'a, str>, Cow<'a, str>>,
+}
+
+impl<'a> TextInputRef<'a> {
+    /// Convert this text input to a `TextInput` that owns its text.
+    pub fn into_owned(self) -> TextInput {
+        TextInput {
+            title: self.title.into_owned(),
+            description: self.description.into_owned(),
+            name: self.name.into_owned(),
+            link: self.link.into_owned(),
+            about: self.about.map(Cow::into_owned),
+            extra_attrs: attrs_into_owned(self.extra_attrs),
+        }
+    }
+}
+
+/// An extension element that borrows its text from the input it was read from.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ExtensionRef<'a> {
+    /// The qualified name of the extension element.
+    pub name: Cow<'a, str>,
+    /// The content of the extension element.
+    pub value: Option<Cow<'a, str>>,
+    /// The attributes for the extension element.
+    pub attrs: BTreeMap<Cow<'a, str>, Cow<'a, str>>,
+    /// The children of the extension element. This is a map of local names to child elements.
+    pub children: BTreeMap<Cow<'a, str>, Vec<ExtensionRef<'a>>>,
+    /// The kind of text in the content.
+    pub kind: TextKind,
+}
+
+impl<'a> ExtensionRef<'a> {
+    /// Convert this extension to an `Extension` that owns its text.
+    pub fn into_owned(self) -> Extension {
+        Extension {
+            name: self.name.into_owned(),
+            value: self.value.map(Cow::into_owned),
+            attrs: attrs_into_owned(self.attrs),
+            children: self
+                .children
+                .into_iter()
+                .map(|(name, children)| {
+                    let children = children.into_iter().map(ExtensionRef::into_owned).collect();
+                    (name.into_owned(), children)
+                })
+                .collect(),
+            kind: self.kind,
+        }
+    }
+}
+
+/// An element without a namespace prefix that is not part of RSS, which borrows its text from
+/// the input it was read from.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct UnknownElementRef<'a> {
+    /// The element, along with its attributes and children.
+    pub element: ExtensionRef<'a>,
+    /// The local name of the RSS element that came before this element in its parent, or `None`
+    /// if it came before all of them.
+    pub after: Option<Cow<'a, str>>,
+}
+
+impl<'a> UnknownElementRef<'a> {
+    /// Convert this element to an `UnknownElement` that owns its text.
+    pub fn into_owned(self) -> UnknownElement {
+        UnknownElement {
+            element: self.element.into_owned(),
+            after: self.after.map(Cow::into_owned),
+        }
+    }
+}
+
+fn attrs_into_owned(attrs: BTreeMap<Cow<str>, Cow<str>>) -> BTreeMap<String, String> {
+    attrs
+        .into_iter()
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect()
+}
+
+fn extensions_into_owned(extensions: ExtensionMapRef) -> ExtensionMap {
+    extensions
+        .into_iter()
+        .map(|(prefix, map)| {
+            let map = map
+                .into_iter()
+                .map(|(name, extensions)| {
+                    let extensions = extensions
+                        .into_iter()
+                        .map(ExtensionRef::into_owned)
+                        .collect();
+                    (name.into_owned(), extensions)
+                })
+                .collect();
+            (prefix.into_owned(), map)
+        })
+        .collect()
+}
+
+/// Reads the borrowed types from a source of events.
+///
+/// This is the only parser in the crate: the owned types are read by converting the borrowed
+/// ones. Text is borrowed when the source keeps the input in memory and contains no entities,
+/// and is owned otherwise.
+pub(crate) struct Parser<'s, 'i, S> {
+    source: &'s mut S,
+    context: &'s mut Context,
+    input: PhantomData<&'i [u8]>,
+}
+
+impl<'s, 'i, S: XmlSource<'i>> Parser<'s, 'i, S> {
+    pub fn new(source: &'s mut S, context: &'s mut Context) -> Self {
+        Parser {
+            source,
+            context,
+            input: PhantomData,
+        }
+    }
+
+    fn read_event(&mut self) -> Result<Event<'i>, Error> {
+        self.context.read_event(self.source)
+    }
+
+    fn skip_element(&mut self) -> Result<(), Error> {
+        self.context.skip_element(self.source)
+    }
+
+    fn stray_text(&mut self) {
+        self.context.stray_text();
+    }
+
+    /// Borrows `bytes` from the input if they are part of it, and copies them otherwise.
+    fn slice(&self, bytes: &[u8]) -> Result<Cow<'i, str>, Error> {
+        if let Some(input) = self.source.input() {
+            let start = (bytes.as_ptr() as usize).checked_sub(input.as_ptr() as usize);
+            if let Some(slice) = start.and_then(|start| input.get(start..start + bytes.len())) {
+                return Ok(Cow::Borrowed(str::from_utf8(slice)?));
+            }
+        }
+
+        Ok(Cow::Owned(str::from_utf8(bytes)?.to_string()))
+    }
+
+    /// Unescapes the value of an attribute.
+    fn attr_value(&mut self, attr: &Attribute) -> Result<Cow<'i, str>, Error> {
+        self.context
+            .check_limit(LimitKind::TextLength, attr.value.len())?;
+        let escaped = self.slice(&attr.value)?;
+        self.context.unescape(escaped)
+    }
+
+    /// Applies the namespace declarations in `atts` to the namespaces in scope.
+    ///
+    /// The default namespace is stored with an empty prefix.
+    fn scoped_namespaces<'n>(
+        &mut self,
+        namespaces: &'n BTreeMap<String, String>,
+        mut atts: Attributes,
+    ) -> Result<Cow<'n, BTreeMap<String, String>>, Error> {
+        let mut scoped = Cow::Borrowed(namespaces);
+
+        for attr in atts.with_checks(false).flatten() {
+            let key = attr.key.into_inner();
+            let prefix = if key == b"xmlns" {
+                ""
+            } else if let Some(prefix) = key.strip_prefix(b"xmlns:") {
+                str::from_utf8(prefix)?
+            } else {
+                continue;
+            };
+
+            let namespace = self.attr_value(&attr)?;
+            scoped
+                .to_mut()
+                .insert(prefix.to_string(), namespace.into_owned());
+        }
+
+        Ok(scoped)
+    }
+
+    /// Returns the value of the RDF attribute named `local_name`, such as `rdf:about`.
+    ///
+    /// The attribute may only be unprefixed in RSS 1.0 documents.
+    fn rdf_attribute(
+        &mut self,
+        namespaces: &BTreeMap<String, String>,
+        mut atts: Attributes,
+        local_name: &str,
+    ) -> Result<Option<Cow<'i, str>>, Error> {
+        let rss1 = self.context.is_rss1();
+        for attr in atts.with_checks(false).flatten() {
+            if is_rdf_attribute(namespaces, attr.key.into_inner(), local_name, rss1) {
+                return self.attr_value(&attr).map(Some);
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Returns the attributes of an element that may have an `rdf:about` attribute, other than
+    /// that attribute and the namespace declarations, keyed by qualified name.
+    fn extra_attributes(
+        &mut self,
+        namespaces: &BTreeMap<String, String>,
+        mut atts: Attributes,
+    ) -> Result<BTreeMap<Cow<'i, str>, Cow<'i, str>>, Error> {
+        let mut extra_attrs = BTreeMap::new();
+        let rss1 = self.context.is_rss1();
+
+        for attr in atts.with_checks(false).flatten() {
+            let key = attr.key.into_inner();
+            if is_namespace_declaration(key) || is_rdf_attribute(namespaces, key, "about", rss1) {
+                continue;
+            }
+            let key = self.slice(key)?;
+            extra_attrs.insert(key, self.attr_value(&attr)?);
+        }
+
+        Ok(extra_attrs)
+    }
+
+    /// Keeps the namespace declarations among the attributes `atts` of an element that bind the
+    /// prefixes of its extra attributes `attrs`, since they are not declared on the root element
+    /// when the element is written.
+    fn keep_declarations(
+        &mut self,
+        attrs: &mut BTreeMap<Cow<'i, str>, Cow<'i, str>>,
+        atts: Attributes,
+    ) -> Result<(), Error> {
+        let document = BTreeMap::new();
+        let declared = self.scoped_namespaces(&document, atts)?;
+        declare_prefixes(attrs, attr_prefixes(attrs), &declared, &document);
+        Ok(())
+    }
+
+    /// Unescapes the contents of a text event.
+    fn text(&mut self, text: BytesText<'i>) -> Result<Cow<'i, str>, Error> {
+        let escaped = decode(text.into_inner())?;
+        self.context.unescape(escaped)
+    }
+
+    /// Returns the contents of a CDATA event.
+    fn cdata(&mut self, cdata: BytesCData<'i>) -> Result<Cow<'i, str>, Error> {
+        decode(cdata.into_inner())
+    }
+
+    fn element_text(&mut self) -> Result<Option<Cow<'i, str>>, Error> {
+        self.context.keep_whitespace(true);
+        let content = self.read_text();
+        self.context.keep_whitespace(false);
+        content
+    }
+
+    /// Reads the text of an element whose start tag was read last, skipping its child elements.
+    ///
+    /// The whitespace around the text is trimmed, but the whitespace between text nodes is kept,
+    /// so that the words on either side of a child element stay apart.
+    fn read_text(&mut self) -> Result<Option<Cow<'i, str>>, Error> {
+        let mut content = None;
+        // the whitespace after the text that was read last
+        let mut whitespace = String::new();
+
+        loop {
+            match self.read_event()? {
+                Event::Start(_) => self.skip_element()?,
+                Event::Text(text) => {
+                    let text = self.text(text)?;
+                    append_trimmed(&mut content, &mut whitespace, text);
+                }
+                Event::CData(cdata) => {
+                    let text = self.cdata(cdata)?;
+                    append_spaced(&mut content, &mut whitespace, text);
+                }
+                Event::End(_) | Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        Ok(content)
+    }
+
+    /// Reads the text of an element that may contain markup, such as a `description`, keeping
+    /// the markup if `ParseOptions::preserve_markup` is set.
+    fn element_content(&mut self) -> Result<(Option<Cow<'i, str>>, TextKind), Error> {
+        if !self.context.options().preserve_markup {
+            return Ok((self.element_text()?, TextKind::Plain));
+        }
+
+        let start = self.context.position().offset;
+        self.context.keep_markup(true);
+        let markup = self.read_markup();
+        self.context.keep_markup(false);
+        let (content, kind) = markup?.text()?;
+        let end = self.context.position().offset;
+
+        Ok((
+            content.map(|content| self.borrow(start..end, content)),
+            kind,
+        ))
+    }
+
+    /// Reads the contents of an element whose start tag was read last as markup.
+    fn read_markup(&mut self) -> Result<Markup, Error> {
+        let mut markup = Markup::default();
+        let mut depth = 0;
+
+        loop {
+            let event = self.read_event()?;
+            match event {
+                Event::End(_) if depth == 0 => break,
+                Event::Eof => break,
+                _ => markup.push_event(&event),
+            }
+
+            match event {
+                Event::Start(_) => depth += 1,
+                Event::End(_) => depth -= 1,
+                Event::Text(text) if depth == 0 => {
+                    let text = self.text(text)?;
+                    markup.push_text(&text);
+                }
+                Event::CData(cdata) if depth == 0 => {
+                    let text = self.cdata(cdata)?;
+                    markup.push_text(&text);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(markup)
+    }
+
+    /// Borrows `text` from the part of the input in `range` if it appears there.
+    fn borrow(&self, range: Range<usize>, text: String) -> Cow<'i, str> {
+        let found = self
+            .source
+            .input()
+            .and_then(|input| input.get(range))
+            .and_then(|part| str::from_utf8(part).ok())
+            .and_then(|part| part.find(text.as_str()).map(|offset| (part, offset)));
+
+        match found {
+            Some((part, offset)) => Cow::Borrowed(&part[offset..offset + text.len()]),
+            None => Cow::Owned(text),
+        }
+    }
+
+    /// Reads the start tag of the root `<rss>` or `<rdf:RDF>` element, returning the namespaces
+    /// it declares.
+    fn read_root(&mut self, element: &BytesStart) -> Result<BTreeMap<String, String>, Error> {
+        let mut namespaces = self
+            .scoped_namespaces(&BTreeMap::new(), element.attributes())?
+            .into_owned();
+        let name = QName::resolve(&namespaces, element.name().into_inner());
+        if name.matches(RDF_NAMESPACE, "rdf", "RDF") {
+            self.context.set_rss1();
+            Ok(namespaces)
+        } else if name.prefix.is_none() && name.local_name == b"rss" {
+            // the elements of the channel are in the default namespace of `<rss>`, so one that is
+            // not an RSS namespace is taken to be the namespace of the core elements
+            if !name.is_rss() {
+                namespaces.remove("");
+            }
+            Ok(namespaces)
+        } else {
+            Err(Error::InvalidStartTag)
+        }
+    }
+
+    /// Reads a single child element of `<channel>`, other than `<item>`, into `channel`.
+    fn read_channel_element(
+        &mut self,
+        namespaces: &BTreeMap<String, String>,
+        name: &QName,
+        element: &BytesStart,
+        channel: &mut ChannelRef<'i>,
+        item_order: &mut Vec<String>,
+        previous: &mut Option<Cow<'i, str>>,
+    ) -> Result<(), Error> {
+        if !name.is_rss() {
+            // the invalid values of the Syndication extension are reported at their start tag
+            let location = self.context.here();
+            let extension = self.read_extension(
+                element,
+                name,
+                &mut channel.extensions,
+                &mut channel.extension_namespaces,
+            )?;
+            if name.namespace == Some(syndication::NAMESPACE) {
+                let value = extension.and_then(|extension| extension.value.as_deref());
+                let local_name = str::from_utf8(name.local_name)?;
+                if let Some(warning) = syndication::check_value(local_name, value.unwrap_or("")) {
+                    self.context.warn_in(location, warning);
+                }
+            }
+            return Ok(());
+        }
+
+        let local_name = self.slice(name.local_name)?;
+
+        match name.local_name {
+            b"category" => {
+                let category = self.read_category(element.attributes())?;
+                channel.categories.push(category);
+            }
+            b"cloud" => channel.cloud = Some(self.read_cloud(element.attributes())?),
+            // in RSS 1.0 the channel refers to the image and text input, which are siblings of
+            // the channel
+            b"image" | b"textinput" | b"textInput"
+                if self
+                    .rdf_attribute(namespaces, element.attributes(), "resource")?
+                    .is_some() =>
+            {
+                self.skip_element()?;
+            }
+            b"image" => {
+                let image = self.read_image(namespaces, element.attributes())?;
+                channel.image = Some(image);
+            }
+            b"textInput" => {
+                let text_input = self.read_text_input(namespaces, element.attributes())?;
+                channel.text_input = Some(text_input);
+            }
+            b"items" => item_order.extend(self.read_item_order(namespaces)?),
+            b"title" => {
+                if let Some(content) = self.element_text()? {
+                    channel.title = content;
+                }
+            }
+            b"link" => {
+                if let Some(content) = self.element_text()? {
+                    channel.link = content;
+                }
+            }
+            b"description" => {
+                if let (Some(content), kind) = self.element_content()? {
+                    channel.description = content;
+                    channel.description_kind = kind;
+                }
+            }
+            b"language" => channel.language = self.element_text()?,
+            b"copyright" => channel.copyright = self.element_text()?,
+            b"managingEditor" => channel.managing_editor = self.element_text()?,
+            b"webMaster" => channel.webmaster = self.element_text()?,
+            b"pubDate" => channel.pub_date = self.element_text()?,
+            b"lastBuildDate" => channel.last_build_date = self.element_text()?,
+            b"generator" => channel.generator = self.element_text()?,
+            b"rating" => channel.rating = self.element_text()?,
+            b"docs" => channel.docs = self.element_text()?,
+            b"ttl" => channel.ttl = self.element_text()?,
+            b"skipHours" => channel.skip_hours = self.read_list(b"hour")?,
+            b"skipDays" => channel.skip_days = self.read_list(b"day")?,
+            _ => {
+                let unknown = self.read_unknown_element(element, local_name, previous)?;
+                channel.unknown_elements.push(unknown);
+                return Ok(());
+            }
+        }
+
+        *previous = Some(local_name);
+        Ok(())
+    }
+
+    /// Reads the text of the children named `child`, as in `<skipHours>`.
+    fn read_list(&mut self, child: &[u8]) -> Result<Vec<Cow<'i, str>>, Error> {
+        let mut values = Vec::new();
+
+        loop {
+            match self.read_event()? {
+                Event::Start(element) => {
+                    if element.name().into_inner() == child {
+                        if let Some(content) = self.element_text()? {
+                            values.push(content);
+                        }
+                    } else {
+                        self.skip_element()?;
+                    }
+                }
+                Event::Text(_) | Event::CData(_) => self.stray_text(),
+                Event::End(_) | Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        Ok(values)
+    }
+
+    /// Reads the `rdf:resource` of each `rdf:li` in the `<items>` element of an RSS 1.0 channel.
+    fn read_item_order(
+        &mut self,
+        namespaces: &BTreeMap<String, String>,
+    ) -> Result<Vec<String>, Error> {
+        let mut order = Vec::new();
+        let mut depth = 0;
+
+        loop {
+            match self.read_event()? {
+                Event::Start(element) => {
+                    depth += 1;
+                    let name = QName::resolve(namespaces, element.name().into_inner());
+                    if name.local_name == b"li" {
+                        let resource =
+                            self.rdf_attribute(namespaces, element.attributes(), "resource")?;
+                        order.extend(resource.map(Cow::into_owned));
+                    }
+                }
+                Event::End(_) if depth > 0 => depth -= 1,
+                Event::End(_) | Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        Ok(order)
+    }
+
+    /// Reads the contents of an `<item>` element whose start tag with the attributes `atts` was
+    /// read last, in the namespaces `parent` of its parent.
+    ///
+    /// The prefixes that the item binds to another namespace than its parent are declared on
+    /// each of the elements of the item that use them, so that they are kept when it is written.
+    pub fn read_item(
+        &mut self,
+        parent: &BTreeMap<String, String>,
+        atts: Attributes,
+    ) -> Result<ItemRef<'i>, Error> {
+        let namespaces = self.scoped_namespaces(parent, atts.clone())?;
+        let mut item = ItemRef {
+            extra_attrs: self.extra_attributes(&namespaces, atts.clone())?,
+            about: self.rdf_attribute(&namespaces, atts, "about")?,
+            ..Default::default()
+        };
+        let prefixes = attr_prefixes(&item.extra_attrs);
+        declare_prefixes(&mut item.extra_attrs, prefixes, &namespaces, parent);
+        let mut previous = None;
+
+        loop {
+            match self.read_event()? {
+                Event::Start(element) => {
+                    let scope = self.scoped_namespaces(&namespaces, element.attributes())?;
+                    let name = QName::resolve(&scope, element.name().into_inner());
+
+                    if name.matches(CONTENT_NAMESPACE, "content", "encoded") {
+                        let (content, kind) = self.element_content()?;
+                        item.content = content;
+                        item.content_kind = kind;
+                    } else if !name.is_rss() {
+                        let extension = self.read_extension(
+                            &element,
+                            &name,
+                            &mut item.extensions,
+                            &mut item.extension_namespaces,
+                        )?;
+                        if let Some(extension) = extension {
+                            let prefixes = extension_prefixes(extension);
+                            declare_prefixes(&mut extension.attrs, prefixes, &namespaces, parent);
+                        }
+                    } else {
+                        let local_name = self.slice(name.local_name)?;
+                        match name.local_name {
+                            b"category" => {
+                                let mut category = self.read_category(element.attributes())?;
+                                let attrs = &mut category.extra_attrs;
+                                declare_prefixes(attrs, attr_prefixes(attrs), &namespaces, parent);
+                                item.categories.push(category);
+                            }
+                            b"guid" => {
+                                let mut guid = self.read_guid(element.attributes())?;
+                                let attrs = &mut guid.extra_attrs;
+                                declare_prefixes(attrs, attr_prefixes(attrs), &namespaces, parent);
+                                item.guid = Some(guid);
+                            }
+                            b"enclosure" => {
+                                let mut enclosure = self.read_enclosure(element.attributes())?;
+                                let attrs = &mut enclosure.extra_attrs;
+                                declare_prefixes(attrs, attr_prefixes(attrs), &namespaces, parent);
+                                item.enclosure = Some(enclosure);
+                            }
+                            b"source" => {
+                                let mut source = self.read_source(element.attributes())?;
+                                let attrs = &mut source.extra_attrs;
+                                declare_prefixes(attrs, attr_prefixes(attrs), &namespaces, parent);
+                                item.source = Some(source);
+                            }
+                            b"title" => item.title = self.element_text()?,
+                            b"link" => item.link = self.element_text()?,
+                            b"description" => {
+                                let (description, kind) = self.element_content()?;
+                                item.description = description;
+                                item.description_kind = kind;
+                            }
+                            b"author" => item.author = self.element_text()?,
+                            b"comments" => item.comments = self.element_text()?,
+                            b"pubDate" => item.pub_date = self.element_text()?,
+                            _ => {
+                                let mut unknown =
+                                    self.read_unknown_element(&element, local_name, &previous)?;
+                                let element = &mut unknown.element;
+                                let prefixes = extension_prefixes(element);
+                                declare_prefixes(&mut element.attrs, prefixes, &namespaces, parent);
+                                item.unknown_elements.push(unknown);
+                                continue;
+                            }
+                        }
+                        previous = Some(local_name);
+                    }
+                }
+                Event::Text(_) | Event::CData(_) => self.stray_text(),
+                Event::End(_) => break,
+                Event::Eof => return Err(Error::Eof),
+                _ => {}
+            }
+        }
+
+        Ok(item)
+    }
+
+    /// Reads the contents of a `<category>` element whose start tag with the attributes `atts`
+    /// was read last.
+    pub fn read_category(&mut self, atts: Attributes) -> Result<CategoryRef<'i>, Error> {
+        let mut category = CategoryRef::default();
+
+        for attr in atts.clone().with_checks(false).flatten() {
+            let key = attr.key.into_inner();
+            if key == b"domain" {
+                category.domain = Some(self.attr_value(&attr)?);
+            } else if !is_namespace_declaration(key) {
+                let key = self.slice(key)?;
+                category.extra_attrs.insert(key, self.attr_value(&attr)?);
+            }
+        }
+
+        self.keep_declarations(&mut category.extra_attrs, atts)?;
+        category.name = self.element_text()?.unwrap_or_default();
+        Ok(category)
+    }
+
+    /// Reads a `<cloud>` element whose start tag with the attributes `atts` was read last.
+    pub fn read_cloud(&mut self, atts: Attributes) -> Result<CloudRef<'i>, Error> {
+        let mut cloud = CloudRef::default();
+
+        for attr in atts.clone().with_checks(false).flatten() {
+            match attr.key.into_inner() {
+                b"domain" => cloud.domain = self.attr_value(&attr)?,
+                b"port" => cloud.port = self.attr_value(&attr)?,
+                b"path" => cloud.path = self.attr_value(&attr)?,
+                b"registerProcedure" => cloud.register_procedure = self.attr_value(&attr)?,
+                b"protocol" => cloud.protocol = self.attr_value(&attr)?,
+                key if !is_namespace_declaration(key) => {
+                    let key = self.slice(key)?;
+                    cloud.extra_attrs.insert(key, self.attr_value(&attr)?);
+                }
+                _ => {}
+            }
+        }
+
+        self.keep_declarations(&mut cloud.extra_attrs, atts)?;
+        self.skip_element()?;
+        Ok(cloud)
+    }
+
+    /// Reads an `<enclosure>` element whose start tag with the attributes `atts` was read last.
+    pub fn read_enclosure(&mut self, atts: Attributes) -> Result<EnclosureRef<'i>, Error> {
+        let mut enclosure = EnclosureRef::default();
+
+        for attr in atts.clone().with_checks(false).flatten() {
+            match attr.key.into_inner() {
+                b"url" => enclosure.url = self.attr_value(&attr)?,
+                b"length" => enclosure.length = self.attr_value(&attr)?,
+                b"type" => enclosure.mime_type = self.attr_value(&attr)?,
+                key if !is_namespace_declaration(key) => {
+                    let key = self.slice(key)?;
+                    enclosure.extra_attrs.insert(key, self.attr_value(&attr)?);
+                }
+                _ => {}
+            }
+        }
+
+        self.keep_declarations(&mut enclosure.extra_attrs, atts)?;
+        self.skip_element()?;
+        Ok(enclosure)
+    }
+
+    /// Reads the contents of a `<guid>` element whose start tag with the attributes `atts` was
+    /// read last.
+    pub fn read_guid(&mut self, atts: Attributes) -> Result<GuidRef<'i>, Error> {
+        let mut guid = GuidRef::default();
+
+        for attr in atts.clone().with_checks(false).flatten() {
+            let key = attr.key.into_inner();
+            if key == b"isPermaLink" {
+                guid.permalink = &*attr.value != b"false";
+            } else if !is_namespace_declaration(key) {
+                let key = self.slice(key)?;
+                guid.extra_attrs.insert(key, self.attr_value(&attr)?);
+            }
+        }
+
+        self.keep_declarations(&mut guid.extra_attrs, atts)?;
+        guid.value = self.element_text()?.unwrap_or_default();
+        Ok(guid)
+    }
+
+    /// Reads the contents of a `<source>` element whose start tag with the attributes `atts` was
+    /// read last.
+    pub fn read_source(&mut self, atts: Attributes) -> Result<SourceRef<'i>, Error> {
+        let mut source = SourceRef::default();
+
+        for attr in atts.clone().with_checks(false).flatten() {
+            let key = attr.key.into_inner();
+            if key == b"url" {
+                source.url = self.attr_value(&attr)?;
+            } else if !is_namespace_declaration(key) {
+                let key = self.slice(key)?;
+                source.extra_attrs.insert(key, self.attr_value(&attr)?);
+            }
+        }
+
+        self.keep_declarations(&mut source.extra_attrs, atts)?;
+        source.title = self.element_text()?;
+        Ok(source)
+    }
+
+    /// Reads the contents of an `<image>` element whose start tag with the attributes `atts` was
+    /// read last.
+    pub fn read_image(
+        &mut self,
+        namespaces: &BTreeMap<String, String>,
+        atts: Attributes,
+    ) -> Result<ImageRef<'i>, Error> {
+        let mut image = ImageRef {
+            extra_attrs: self.extra_attributes(namespaces, atts.clone())?,
+            about: self.rdf_attribute(namespaces, atts.clone(), "about")?,
+            ..Default::default()
+        };
+        self.keep_declarations(&mut image.extra_attrs, atts)?;
+
+        loop {
+            match self.read_event()? {
+                Event::Start(element) => match element.name().into_inner() {
+                    b"url" => image.url = self.element_text()?.unwrap_or_default(),
+                    b"title" => image.title = self.element_text()?.unwrap_or_default(),
+                    b"link" => image.link = self.element_text()?.unwrap_or_default(),
+                    b"width" => image.width = self.element_text()?,
+                    b"height" => image.height = self.element_text()?,
+                    b"description" => image.description = self.element_text()?,
+                    _ => self.skip_element()?,
+                },
+                Event::Text(_) | Event::CData(_) => self.stray_text(),
+                Event::End(_) => break,
+                Event::Eof => return Err(Error::Eof),
+                _ => {}
+            }
+        }
+
+        Ok(image)
+    }
+
+    /// Reads the contents of a `<textInput>` element whose start tag with the attributes `atts`
+    /// was read last.
+    pub fn read_text_input(
+        &mut self,
+        namespaces: &BTreeMap<String, String>,
+        atts: Attributes,
+    ) -> Result<TextInputRef<'i>, Error> {
+        let mut text_input = TextInputRef {
+            extra_attrs: self.extra_attributes(namespaces, atts.clone())?,
+            about: self.rdf_attribute(namespaces, atts.clone(), "about")?,
+            ..Default::default()
+        };
+        self.keep_declarations(&mut text_input.extra_attrs, atts)?;
+
+        loop {
+            match self.read_event()? {
+                Event::Start(element) => match element.name().into_inner() {
+                    b"title" => text_input.title = self.element_text()?.unwrap_or_default(),
+                    b"description" => {
+                        text_input.description = self.element_text()?.unwrap_or_default()
+                    }
+                    b"name" => text_input.name = self.element_text()?.unwrap_or_default(),
+                    b"link" => text_input.link = self.element_text()?.unwrap_or_default(),
+                    _ => self.skip_element()?,
+                },
+                Event::Text(_) | Event::CData(_) => self.stray_text(),
+                Event::End(_) => break,
+                Event::Eof => return Err(Error::Eof),
+                _ => {}
+            }
+        }
+
+        Ok(text_input)
+    }
+
+    /// Reads an extension element, recording the namespace its prefix is bound to in
+    /// `extension_namespaces`, and returns it. Elements that cannot be stored as an extension are
+    /// skipped.
+    fn read_extension<'m>(
+        &mut self,
+        element: &BytesStart,
+        name: &QName,
+        extensions: &'m mut ExtensionMapRef<'i>,
+        extension_namespaces: &mut BTreeMap<String, String>,
+    ) -> Result<Option<&'m mut ExtensionRef<'i>>, Error> {
+        let prefix = match name.extension_prefix() {
+            Some(prefix) => prefix,
+            None => return self.skip_element().map(|_| None),
+        };
+
+        let prefix = self.slice(prefix)?;
+        let local_name = self.slice(name.local_name)?;
+
+        let mut extension = self.read_extension_element(element)?;
+        if name.prefix.is_none() {
+            extension.name = Cow::Owned(format!("{}:{}", prefix, local_name));
+        }
+
+        if let Some(namespace) = name.namespace {
+            extension_namespaces.insert(prefix.to_string(), namespace.to_string());
+        }
+
+        let extensions = extensions
+            .entry(prefix)
+            .or_default()
+            .entry(local_name)
+            .or_default();
+        extensions.push(extension);
+
+        Ok(extensions.last_mut())
+    }
+
+    /// Reads an element without a namespace prefix that is not part of RSS. `after` is the local
+    /// name of the RSS element that came before it.
+    fn read_unknown_element(
+        &mut self,
+        element: &BytesStart,
+        local_name: Cow<'i, str>,
+        after: &Option<Cow<'i, str>>,
+    ) -> Result<UnknownElementRef<'i>, Error> {
+        let mut extension = self.read_extension_element(element)?;
+        extension.name = local_name;
+        Ok(UnknownElementRef {
+            element: extension,
+            after: after.clone(),
+        })
+    }
+
+    /// Reads an extension element and its children, keeping the markup inside it if
+    /// `ParseOptions::preserve_markup` is set.
+    fn read_extension_element(&mut self, element: &BytesStart) -> Result<ExtensionRef<'i>, Error> {
+        if !self.context.options().preserve_markup {
+            self.context.keep_whitespace(true);
+            let extension = self.parse_extension_element(element, false);
+            self.context.keep_whitespace(false);
+            return extension;
+        }
+
+        self.context.keep_markup(true);
+        let extension = self.parse_extension_element(element, true);
+        self.context.keep_markup(false);
+        extension
+    }
+
+    /// Reads an extension element and its children, collecting the contents of each element as
+    /// raw XML if `keep_markup` is set.
+    ///
+    /// The elements are read without recursion, so that deeply nested extensions cannot
+    /// overflow the stack.
+    fn parse_extension_element(
+        &mut self,
+        element: &BytesStart,
+        keep_markup: bool,
+    ) -> Result<ExtensionRef<'i>, Error> {
+        // the number of elements nested in the outermost extension element that were read so far
+        let mut children = 0;
+        let mut open = vec![OpenExtension {
+            extension: ExtensionRef {
+                name: self.slice(element.name().into_inner())?,
+                attrs: self.extension_attrs(element.attributes())?,
+                ..Default::default()
+            },
+            local_name: Cow::Borrowed(""),
+            element: None,
+            markup: keep_markup.then(Markup::default),
+            start: self.context.position().offset,
+            whitespace: String::new(),
+        }];
+
+        loop {
+            let event = self.read_event()?;
+            let current = open.last_mut().ok_or(Error::Eof)?;
+            match event {
+                Event::Start(element) => {
+                    children += 1;
+                    self.context
+                        .check_limit(LimitKind::ExtensionChildren, children)?;
+                    let child = OpenExtension {
+                        extension: ExtensionRef {
+                            name: self.slice(element.name().into_inner())?,
+                            attrs: self.extension_attrs(element.attributes())?,
+                            ..Default::default()
+                        },
+                        local_name: self.slice(element.local_name().into_inner())?,
+                        markup: keep_markup.then(Markup::default),
+                        element: Some(element),
+                        start: self.context.position().offset,
+                        whitespace: String::new(),
+                    };
+                    open.push(child);
+                }
+                // only reported when markup is kept
+                Event::Empty(element) => {
+                    children += 1;
+                    self.context
+                        .check_limit(LimitKind::ExtensionChildren, children)?;
+                    let name = self.slice(element.local_name().into_inner())?;
+                    let child = ExtensionRef {
+                        name: self.slice(element.name().into_inner())?,
+                        attrs: self.extension_attrs(element.attributes())?,
+                        ..Default::default()
+                    };
+                    current
+                        .extension
+                        .children
+                        .entry(name)
+                        .or_default()
+                        .push(child);
+                    if let Some(markup) = current.markup.as_mut() {
+                        markup.push_event(&Event::Empty(element));
+                    }
+                }
+                Event::Text(text) => match current.markup.as_mut() {
+                    Some(markup) => {
+                        markup.push_event(&Event::Text(text.borrow()));
+                        markup.push_text(&self.text(text)?);
+                    }
+                    None => {
+                        let text = self.text(text)?;
+                        let value = &mut current.extension.value;
+                        append_trimmed(value, &mut current.whitespace, text);
+                    }
+                },
+                Event::CData(cdata) => match current.markup.as_mut() {
+                    Some(markup) => {
+                        markup.push_event(&Event::CData(cdata.borrow()));
+                        markup.push_text(&self.cdata(cdata)?);
+                    }
+                    None => {
+                        let text = self.cdata(cdata)?;
+                        let value = &mut current.extension.value;
+                        append_spaced(value, &mut current.whitespace, text);
+                    }
+                },
+                Event::End(_) => {
+                    let mut closed = open.pop().ok_or(Error::Eof)?;
+                    if let Some(markup) = closed.markup.as_ref() {
+                        let end = self.context.position().offset;
+                        let (value, kind) = markup.value()?;
+                        closed.extension.value =
+                            value.map(|value| self.borrow(closed.start..end, value));
+                        closed.extension.kind = kind;
+                    }
+
+                    let parent = match open.last_mut() {
+                        Some(parent) => parent,
+                        None => return Ok(closed.extension),
+                    };
+                    if let (Some(markup), Some(inner), Some(element)) = (
+                        parent.markup.as_mut(),
+                        closed.markup.as_ref(),
+                        closed.element.as_ref(),
+                    ) {
+                        markup.push_child(element, inner);
+                    }
+                    parent
+                        .extension
+                        .children
+                        .entry(closed.local_name)
+                        .or_default()
+                        .push(closed.extension);
+                }
+                Event::Eof => return Err(Error::Eof),
+                event => {
+                    if let Some(markup) = current.markup.as_mut() {
+                        markup.push_event(&event);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the attributes of an extension element.
+    fn extension_attrs(
+        &mut self,
+        mut atts: Attributes,
+    ) -> Result<BTreeMap<Cow<'i, str>, Cow<'i, str>>, Error> {
+        let mut attrs = BTreeMap::new();
+
+        for attr in atts.with_checks(false).flatten() {
+            let key = self.slice(attr.key.into_inner())?;
+            let value = self.attr_value(&attr)?;
+            attrs.insert(key, value);
+        }
+
+        Ok(attrs)
+    }
+}
+
+/// An extension element whose end tag was not read yet.
+struct OpenExtension<'i> {
+    extension: ExtensionRef<'i>,
+    /// The local name of the element, which its parent keeps it under.
+    local_name: Cow<'i, str>,
+    /// The start tag of the element, unless it is the outermost extension element.
+    element: Option<BytesStart<'i>>,
+    /// The contents of the element as raw XML, when markup is kept.
+    markup: Option<Markup>,
+    /// The offset in the input at which the contents of the element start.
+    start: usize,
+    /// The whitespace after the text of the element that was read last, when markup is not kept.
+    whitespace: String,
+}
+
+/// Where a `ChannelParser` is in the document.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+enum State {
+    /// Before the root `<rss>` or `<rdf:RDF>` element.
+    #[default]
+    Prolog,
+    /// Inside the root element.
+    Root,
+    /// Inside the `<channel>` element.
+    Channel,
+    /// The end of the document was reached.
+    Done,
+}
+
+/// Reads a channel one element at a time, so that its items can be returned as they are read.
+///
+/// Everything but the items is added to the channel header as it is read.
+#[derive(Debug, Default)]
+pub(crate) struct ChannelParser<'i> {
+    state: State,
+    /// The namespaces in scope for the children of the root element.
+    namespaces: BTreeMap<String, String>,
+    /// The namespaces in scope for the children of the channel element.
+    channel_namespaces: BTreeMap<String, String>,
+    channel: ChannelRef<'i>,
+    has_channel: bool,
+    /// The `rdf:about` URIs of the items of an RSS 1.0 channel, in order.
+    item_order: Vec<String>,
+    /// The local name of the last RSS element of the channel that was read.
+    previous: Option<Cow<'i, str>>,
+    /// Whether the channel header changed since `take_changed` was last called.
+    changed: bool,
+}
+
+impl<'i> ChannelParser<'i> {
+    /// Reads a whole document.
+    pub fn read_document<S: XmlSource<'i>>(
+        parser: &mut Parser<'_, 'i, S>,
+    ) -> Result<ChannelRef<'i>, Error> {
+        let mut channel_parser = ChannelParser::default();
+        let mut items = Vec::new();
+
+        while !channel_parser.is_done() {
+            items.extend(channel_parser.step(parser)?);
+        }
+
+        channel_parser.into_channel(items)
+    }
+
+    /// Reads the contents of a `<channel>` element whose start tag with the attributes `atts`
+    /// was read last.
+    pub fn read_channel<S: XmlSource<'i>>(
+        parser: &mut Parser<'_, 'i, S>,
+        namespaces: &BTreeMap<String, String>,
+        atts: Attributes,
+    ) -> Result<ChannelRef<'i>, Error> {
+        let mut channel_parser = ChannelParser::default();
+        let scope = parser.scoped_namespaces(namespaces, atts.clone())?;
+        channel_parser.start_channel(parser, scope.into_owned(), atts)?;
+        let mut items = Vec::new();
+
+        while channel_parser.state == State::Channel {
+            items.extend(channel_parser.step(parser)?);
+        }
+
+        channel_parser.into_channel(items)
+    }
+
+    /// Whether the end of the document was reached.
+    pub fn is_done(&self) -> bool {
+        self.state == State::Done
+    }
+
+    /// Whether the next step, if it starts with `element`, reads only the start tag rather than
+    /// the whole element, as it does for the root and channel elements.
+    #[cfg(feature = "async")]
+    pub fn reads_start_only(&self, element: &BytesStart) -> bool {
+        if self.state != State::Root {
+            return self.state == State::Prolog;
+        }
+
+        let mut scope = Cow::Borrowed(&self.namespaces);
+        for attr in element.attributes().with_checks(false).flatten() {
+            let key = attr.key.into_inner();
+            let prefix = if key == b"xmlns" {
+                &[][..]
+            } else if let Some(prefix) = key.strip_prefix(b"xmlns:") {
+                prefix
+            } else {
+                continue;
+            };
+            if let (Ok(prefix), Ok(namespace)) = (str::from_utf8(prefix), attr.unescape_value()) {
+                scope
+                    .to_mut()
+                    .insert(prefix.to_string(), namespace.into_owned());
+            }
+        }
+
+        let name = QName::resolve(&scope, element.name().into_inner());
+        name.is_rss() && name.local_name == b"channel"
+    }
+
+    /// Stops reading, as after an error.
+    pub fn stop(&mut self) {
+        self.state = State::Done;
+    }
+
+    /// Whether a channel element was found.
+    pub fn has_channel(&self) -> bool {
+        self.has_channel
+    }
+
+    /// Returns the channel header.
+    pub fn channel(&self) -> &ChannelRef<'i> {
+        &self.channel
+    }
+
+    /// Returns the order of the items given by an RSS 1.0 channel.
+    pub fn item_order(&self) -> &[String] {
+        &self.item_order
+    }
+
+    /// Consumes the parser and returns the channel header.
+    pub fn into_header(self) -> ChannelRef<'i> {
+        self.channel
+    }
+
+    /// Returns whether the channel header changed since this was last called.
+    pub fn take_changed(&mut self) -> bool {
+        mem::take(&mut self.changed)
+    }
+
+    /// Returns the channel with `items`, failing if the input never contained a channel element.
+    ///
+    /// The items of an RSS 1.0 channel are sorted in the order given by the channel.
+    pub fn into_channel(self, mut items: Vec<ItemRef<'i>>) -> Result<ChannelRef<'i>, Error> {
+        if !self.has_channel {
+            return Err(Error::Eof);
+        }
+
+        sort_items(&mut items, &self.item_order, |item| item.about.as_deref());
+        let mut channel = self.channel;
+        channel.items = items;
+        Ok(channel)
+    }
+
+    /// Reads the next event at the top level of the document or the channel, along with the
+    /// element it starts, returning the item that was read if there is one.
+    pub fn step<S: XmlSource<'i>>(
+        &mut self,
+        parser: &mut Parser<'_, 'i, S>,
+    ) -> Result<Option<ItemRef<'i>>, Error> {
+        if self.state == State::Done {
+            return Ok(None);
+        }
+
+        let element = match (self.state, parser.read_event()?) {
+            (State::Prolog, Event::Start(element)) => {
+                self.namespaces = parser.read_root(&element)?;
+                add_declared_namespaces(&mut self.channel.namespaces, &self.namespaces);
+                self.state = State::Root;
+                self.changed = true;
+                return Ok(None);
+            }
+            (State::Prolog, Event::Eof) => return Err(Error::Eof),
+            (State::Prolog, _) => return Ok(None),
+            (_, Event::Start(element)) => element,
+            (_, Event::Text(_)) | (_, Event::CData(_)) => {
+                parser.stray_text();
+                return Ok(None);
+            }
+            (State::Channel, Event::End(_)) => {
+                self.state = State::Root;
+                return Ok(None);
+            }
+            (State::Channel, Event::Eof) => return Err(Error::Eof),
+            (_, Event::End(_)) | (_, Event::Eof) => {
+                self.state = State::Done;
+                return Ok(None);
+            }
+            _ => return Ok(None),
+        };
+
+        let parent = match self.state {
+            State::Channel => &self.channel_namespaces,
+            _ => &self.namespaces,
+        };
+        let scope = parser.scoped_namespaces(parent, element.attributes())?;
+        let name = QName::resolve(&scope, element.name().into_inner());
+        let local_name = if name.is_rss() {
+            Some(name.local_name)
+        } else {
+            None
+        };
+
+        match (self.state, local_name) {
+            (_, Some(b"item")) => {
+                parser.context.count_item()?;
+                let item = parser.read_item(parent, element.attributes())?;
+                if self.state == State::Channel {
+                    self.previous = Some(Cow::Borrowed("item"));
+                }
+                return Ok(Some(item));
+            }
+            (State::Channel, _) => parser.read_channel_element(
+                &scope,
+                &name,
+                &element,
+                &mut self.channel,
+                &mut self.item_order,
+                &mut self.previous,
+            )?,
+            // for parsing RSS 0.9, 1.0 feeds the items, image and text input are siblings of the
+            // channel
+            (_, Some(b"channel")) => {
+                let scope = scope.into_owned();
+                self.start_channel(parser, scope, element.attributes())?;
+            }
+            (_, Some(b"image")) => {
+                let image = parser.read_image(&scope, element.attributes())?;
+                self.channel.image = Some(image);
+            }
+            (_, Some(b"textinput")) => {
+                let text_input = parser.read_text_input(&scope, element.attributes())?;
+                self.channel.text_input = Some(text_input);
+            }
+            _ => parser.skip_element()?,
+        }
+
+        self.changed = true;
+        Ok(None)
+    }
+
+    /// Starts reading the contents of the channel element with the attributes `atts`, in which
+    /// the namespaces in `scope` are in scope.
+    fn start_channel<S: XmlSource<'i>>(
+        &mut self,
+        parser: &mut Parser<'_, 'i, S>,
+        scope: BTreeMap<String, String>,
+        atts: Attributes,
+    ) -> Result<(), Error> {
+        self.channel.about = parser.rdf_attribute(&scope, atts.clone(), "about")?;
+        self.channel.extra_attrs = parser.extra_attributes(&scope, atts)?;
+        add_declared_namespaces(&mut self.channel.namespaces, &scope);
+        self.channel_namespaces = scope;
+        self.has_channel = true;
+        self.state = State::Channel;
+        Ok(())
+    }
+}
+
+/// Reads the rest of an element whose start tag was read from `reader` by the caller, as the
+/// `from_xml` functions of the owned types do.
+pub(crate) fn read_from_reader<R, T, F>(reader: &mut Reader<R>, read: F) -> Result<T, Error>
+where
+    R: BufRead,
+    F: FnOnce(&mut Parser<'_, 'static, BufSource<'_, R>>) -> Result<T, Error>,
+{
+    let mut context = Context::for_element();
+    let mut source = BufSource::new(reader, None);
+    read(&mut Parser::new(&mut source, &mut context))
+}
+
+/// Converts the contents of an event to a string, keeping them borrowed if they are.
+fn decode(bytes: Cow<[u8]>) -> Result<Cow<str>, Error> {
+    match bytes {
+        Cow::Borrowed(bytes) => Ok(Cow::Borrowed(str::from_utf8(bytes)?)),
+        Cow::Owned(bytes) => match String::from_utf8(bytes) {
+            Ok(text) => Ok(Cow::Owned(text)),
+            Err(err) => Err(err.utf8_error().into()),
+        },
+    }
+}
+
+/// Appends `text` to `content`, since text may be split into several events, such as a CDATA
+/// section that was split around a `]]>`.
+fn append<'a>(content: &mut Option<Cow<'a, str>>, text: Cow<'a, str>) {
+    match content {
+        Some(content) => content.to_mut().push_str(&text),
+        None => *content = Some(text),
+    }
+}
+
+/// Appends the text of a text event to `content` without the whitespace around it. The
+/// whitespace after it is kept in `whitespace` until more text follows, so that only the
+/// whitespace between text nodes ends up in `content`.
+fn append_trimmed<'a>(
+    content: &mut Option<Cow<'a, str>>,
+    whitespace: &mut String,
+    text: Cow<'a, str>,
+) {
+    let start = text.len() - text.trim_start().len();
+    if start == text.len() {
+        whitespace.push_str(&text);
+        return;
+    }
+
+    let end = text.trim_end().len();
+    whitespace.push_str(&text[..start]);
+    let after = text[end..].to_string();
+    append_spaced(content, whitespace, slice(text, start..end));
+    *whitespace = after;
+}
+
+/// Appends `text` to `content` along with the `whitespace` before it, which is left out at the
+/// start of the content.
+fn append_spaced<'a>(
+    content: &mut Option<Cow<'a, str>>,
+    whitespace: &mut String,
+    text: Cow<'a, str>,
+) {
+    if let Some(content) = content.as_mut().filter(|_| !whitespace.is_empty()) {
+        content.to_mut().push_str(whitespace);
+    }
+    whitespace.clear();
+    append(content, text);
+}
+
+/// Returns the part of `text` in `range`, keeping it borrowed if it is.
+fn slice(text: Cow<str>, range: Range<usize>) -> Cow<str> {
+    match text {
+        Cow::Borrowed(text) => Cow::Borrowed(&text[range]),
+        Cow::Owned(text) if range.len() == text.len() => Cow::Owned(text),
+        Cow::Owned(text) => Cow::Owned(text[range].to_string()),
+    }
+}
+
+/// Declares in `attrs` each of the `prefixes` that an element uses if it is bound to another
+/// namespace in `scope`, where the element was read, than in `document`, the namespaces that are
+/// declared around it when it is written. The prefixes that the element declares itself are left
+/// as they are.
+fn declare_prefixes<'i>(
+    attrs: &mut BTreeMap<Cow<'i, str>, Cow<'i, str>>,
+    prefixes: BTreeSet<String>,
+    scope: &BTreeMap<String, String>,
+    document: &BTreeMap<String, String>,
+) {
+    for prefix in prefixes {
+        let namespace = match scope.get(&prefix) {
+            Some(namespace) if document.get(&prefix) != Some(namespace) => namespace,
+            _ => continue,
+        };
+        let key = format!("xmlns:{}", prefix);
+        if !attrs.contains_key(key.as_str()) {
+            attrs.insert(Cow::Owned(key), Cow::Owned(namespace.clone()));
+        }
+    }
+}
+
+/// Returns the prefixes of the qualified names in `attrs`.
+fn attr_prefixes(attrs: &BTreeMap<Cow<str>, Cow<str>>) -> BTreeSet<String> {
+    attrs
+        .keys()
+        .filter_map(|key| prefix_of(key))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Returns the prefixes of the names of `extension` and of its descendants and their attributes.
+fn extension_prefixes(extension: &ExtensionRef) -> BTreeSet<String> {
+    let mut prefixes = BTreeSet::new();
+    let mut elements = vec![extension];
+
+    while let Some(element) = elements.pop() {
+        prefixes.extend(prefix_of(&element.name).map(str::to_string));
+        prefixes.extend(attr_prefixes(&element.attrs));
+        elements.extend(element.children.values().flatten());
+    }
+
+    prefixes
+}
+
+/// Returns the prefix of the qualified name `name`, unless it is one of the reserved `xml` and
+/// `xmlns` prefixes.
+fn prefix_of(name: &str) -> Option<&str> {
+    match name.split_once(':') {
+        Some(("xml", _)) | Some(("xmlns", _)) | None => None,
+        Some((prefix, _)) => Some(prefix),
+    }
+}
+
+/// Add the prefixed namespaces in `declared` to `namespaces`, keeping the ones that were added
+/// first.
+fn add_declared_namespaces(
+    namespaces: &mut BTreeMap<String, String>,
+    declared: &BTreeMap<String, String>,
+) {
+    for (prefix, namespace) in declared {
+        if !prefix.is_empty() && !namespaces.contains_key(prefix) {
+            namespaces.insert(prefix.clone(), namespace.clone());
+        }
+    }
+}
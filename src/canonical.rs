@@ -0,0 +1,247 @@
+// This file is part of rss.
+//
+// Copyright © 2015-2021 The rust-syndication Developers
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the MIT License and/or Apache 2.0 License.
+
+use std::io::{self, Write};
+use std::str;
+
+#[cfg(feature = "chrono")]
+use chrono::Utc;
+use quick_xml::escape::{escape, unescape};
+use quick_xml::events::{BytesStart, BytesText, Event};
+use quick_xml::{Error as XmlError, Reader, Writer};
+
+#[cfg(feature = "chrono")]
+use crate::channel::Channel;
+#[cfg(feature = "chrono")]
+use crate::date::{format_date, format_w3cdtf, parse_date, parse_w3cdtf};
+use crate::error::Error;
+#[cfg(feature = "chrono")]
+use crate::extension::dublincore::DublinCoreExtension;
+use crate::writer::{TextStyle, WriteOptions};
+
+/// The options a channel is written with before it is brought into canonical form.
+pub(crate) fn canonical_options() -> WriteOptions {
+    WriteOptions {
+        channel_description: TextStyle::Escaped,
+        item_description: TextStyle::Escaped,
+        content: TextStyle::Escaped,
+        ..Default::default()
+    }
+}
+
+/// Rewrites the dates of `channel` that can be parsed as UTC dates in a single format: RFC 2822
+/// for the RSS dates, and W3C dates for the Dublin Core and Syndication extensions. Dates that
+/// cannot be parsed are left as they are.
+#[cfg(feature = "chrono")]
+pub(crate) fn canonical_dates(channel: &mut Channel) {
+    canonical_date(&mut channel.pub_date);
+    canonical_date(&mut channel.last_build_date);
+    canonical_dublin_core_dates(&mut channel.dublin_core_ext);
+    if let Some(syndication) = channel.syndication_ext.as_mut() {
+        if let Some(base) = parse_w3cdtf(&syndication.base) {
+            syndication.base = format_w3cdtf(&base.with_timezone(&Utc));
+        }
+    }
+    for item in &mut channel.items {
+        canonical_date(&mut item.pub_date);
+        canonical_dublin_core_dates(&mut item.dublin_core_ext);
+    }
+}
+
+#[cfg(feature = "chrono")]
+fn canonical_date(date: &mut Option<String>) {
+    if let Some(parsed) = date.as_deref().and_then(parse_date) {
+        *date = Some(format_date(&parsed.with_timezone(&Utc)));
+    }
+}
+
+#[cfg(feature = "chrono")]
+fn canonical_dublin_core_dates(extension: &mut Option<DublinCoreExtension>) {
+    let dates = match extension.as_mut() {
+        Some(extension) => &mut extension.dates,
+        None => return,
+    };
+    for date in dates {
+        if let Some(parsed) = parse_w3cdtf(date) {
+            *date = format_w3cdtf(&parsed.with_timezone(&Utc));
+        }
+    }
+}
+
+/// Rewrites an unindented document in canonical form.
+///
+/// The attributes of each element are sorted by name, attribute values and text are escaped the
+/// same way, CDATA sections are written as text, line breaks are written as `\n`, whitespace at
+/// the start and end of each text is removed, as it is when the text is read, and elements
+/// without content are self-closing. Text with entities that are not known to XML is not
+/// unescaped, but is otherwise normalised in the same way.
+pub(crate) fn canonicalize(document: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut reader = Reader::from_reader(document);
+    reader.check_end_names(false);
+    let mut writer = Writer::new(Vec::with_capacity(document.len()));
+    let mut buf = Vec::new();
+
+    // a start tag that is not written until it is known whether the element has content, and
+    // the escaped text that has been read since the last tag
+    let mut start: Option<BytesStart<'static>> = None;
+    let mut text = String::new();
+
+    loop {
+        let event = reader.read_event_into(&mut buf)?;
+        match event {
+            // text with entities that are not known, such as HTML entities in XHTML markup, is
+            // kept as it is
+            Event::Text(ref content) => {
+                let escaped = str::from_utf8(content)?;
+                match unescape(escaped) {
+                    Ok(unescaped) => text.push_str(&escape(&unescaped)),
+                    Err(_) => text.push_str(escaped),
+                }
+                buf.clear();
+                continue;
+            }
+            Event::CData(ref content) => {
+                text.push_str(&escape(str::from_utf8(content)?));
+                buf.clear();
+                continue;
+            }
+            _ => {}
+        }
+
+        let text = normalize_text(&std::mem::take(&mut text));
+        if let Some(element) = start.take() {
+            match (&event, text.is_empty()) {
+                (Event::End(_), true) => {
+                    writer.write_event(Event::Empty(element))?;
+                    buf.clear();
+                    continue;
+                }
+                _ => writer.write_event(Event::Start(element))?,
+            }
+        }
+        if !text.is_empty() {
+            writer.write_event(Event::Text(BytesText::from_escaped(text)))?;
+        }
+
+        match event {
+            Event::Start(ref element) => start = Some(sort_attributes(element)?),
+            Event::Empty(ref element) => {
+                writer.write_event(Event::Empty(sort_attributes(element)?))?;
+            }
+            Event::Eof => break,
+            event => writer.write_event(event)?,
+        }
+        buf.clear();
+    }
+
+    Ok(writer.into_inner())
+}
+
+/// Removes the whitespace at the start and end of `text` and normalises its line breaks.
+fn normalize_text(text: &str) -> String {
+    normalize_line_breaks(text.trim())
+}
+
+/// Returns a copy of `element` with its attributes sorted by name and their values escaped the
+/// same way, unless they contain entities that are not known to XML.
+fn sort_attributes(element: &BytesStart) -> Result<BytesStart<'static>, Error> {
+    let mut attributes = Vec::new();
+    for attribute in element.attributes() {
+        let attribute = attribute.map_err(XmlError::InvalidAttr)?;
+        let escaped = str::from_utf8(&attribute.value)?;
+        let value = match unescape(escaped) {
+            Ok(value) => normalize_line_breaks(&escape(&value)),
+            Err(_) => normalize_line_breaks(escaped),
+        };
+        attributes.push((attribute.key.into_inner().to_vec(), value));
+    }
+    attributes.sort();
+
+    let mut sorted = BytesStart::new(str::from_utf8(element.name().into_inner())?.to_string());
+    for (key, value) in &attributes {
+        sorted.push_attribute((key.as_slice(), value.as_bytes()));
+    }
+    Ok(sorted)
+}
+
+/// Writes the line breaks of `value` as `\n`.
+fn normalize_line_breaks(value: &str) -> String {
+    value.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+/// A 64-bit FNV-1a hash of the bytes written to it.
+///
+/// Unlike the hashers of the standard library, its output is specified, so the hashes of a
+/// document are the same on every platform and with every version of Rust.
+pub(crate) struct ContentHasher(u64);
+
+impl ContentHasher {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    pub fn new() -> Self {
+        ContentHasher(Self::OFFSET_BASIS)
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Write for ContentHasher {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for byte in buf {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(Self::PRIME);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canonical(document: &str) -> String {
+        String::from_utf8(canonicalize(document.as_bytes()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_sorted_attributes() {
+        assert_eq!(
+            canonical("<a z='1' b=\"&#34;\" m=\"&lt;\"/>"),
+            "<a b=\"&quot;\" m=\"&lt;\" z=\"1\"/>"
+        );
+    }
+
+    #[test]
+    fn test_text() {
+        assert_eq!(
+            canonical("<a>\r\n  <b> x &#38; y\r\nz </b>\n  <c><![CDATA[<p>]]>&amp;</c>\n</a>"),
+            "<a><b>x &amp; y\nz</b><c>&lt;p&gt;&amp;</c></a>"
+        );
+    }
+
+    #[test]
+    fn test_empty_elements() {
+        assert_eq!(
+            canonical("<a><b></b><c>  </c><d x=\"1\"><![CDATA[]]></d></a>"),
+            "<a><b/><c/><d x=\"1\"/></a>"
+        );
+    }
+
+    #[test]
+    fn test_content_hasher() {
+        let mut hasher = ContentHasher::new();
+        assert_eq!(hasher.finish(), 0xcbf2_9ce4_8422_2325);
+        hasher.write_all(b"a").unwrap();
+        assert_eq!(hasher.finish(), 0xaf63_dc4c_8601_ec8c);
+    }
+}
@@ -5,17 +5,17 @@
 // This program is free software; you can redistribute it and/or modify
 // it under the terms of the MIT License and/or Apache 2.0 License.
 
+use std::collections::BTreeMap;
 use std::io::{BufRead, Write};
 
 use quick_xml::events::attributes::Attributes;
 use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
 use quick_xml::Error as XmlError;
 use quick_xml::Reader;
-use quick_xml::Writer;
 
+use crate::borrowed::{read_from_reader, CategoryRef};
 use crate::error::Error;
-use crate::toxml::ToXml;
-use crate::util::element_text;
+use crate::toxml::{ToXml, XmlWriter};
 
 /// Represents a category in an RSS feed.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -34,6 +34,10 @@ pub struct Category {
     pub name: String,
     /// The domain for the category.
     pub domain: Option<String>,
+    /// The attributes of the category element that are not part of RSS, such as `xml:lang`, keyed
+    /// by qualified name.
+    #[cfg_attr(feature = "builders", builder(setter(each = "extra_attr")))]
+    pub extra_attrs: BTreeMap<String, String>,
 }
 
 impl Category {
@@ -100,38 +104,67 @@ impl Category {
     {
         self.domain = domain.into();
     }
+
+    /// Return the attributes of this category that are not part of RSS.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use rss::Category;
+    ///
+    /// let mut extra_attrs = BTreeMap::new();
+    /// extra_attrs.insert("xml:lang".to_string(), "en".to_string());
+    ///
+    /// let mut category = Category::default();
+    /// category.set_extra_attrs(extra_attrs);
+    /// assert_eq!(category.extra_attrs()["xml:lang"], "en");
+    /// ```
+    pub fn extra_attrs(&self) -> &BTreeMap<String, String> {
+        &self.extra_attrs
+    }
+
+    /// Set the attributes of this category that are not part of RSS.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use rss::Category;
+    ///
+    /// let mut category = Category::default();
+    /// category.set_extra_attrs(BTreeMap::new());
+    /// ```
+    pub fn set_extra_attrs<V>(&mut self, extra_attrs: V)
+    where
+        V: Into<BTreeMap<String, String>>,
+    {
+        self.extra_attrs = extra_attrs.into();
+    }
 }
 
 impl Category {
     /// Builds a Category from source XML
-    pub fn from_xml<R: BufRead>(
-        reader: &mut Reader<R>,
-        mut atts: Attributes,
-    ) -> Result<Self, Error> {
-        let mut category = Category::default();
-
-        for attr in atts.with_checks(false).flatten() {
-            if attr.key == b"domain" {
-                category.domain = Some(attr.unescape_and_decode_value(reader)?);
-                break;
-            }
-        }
-
-        category.name = element_text(reader)?.unwrap_or_default();
-        Ok(category)
+    pub fn from_xml<R: BufRead>(reader: &mut Reader<R>, atts: Attributes) -> Result<Self, Error> {
+        read_from_reader(reader, |parser| parser.read_category(atts)).map(CategoryRef::into_owned)
     }
 }
 
 impl ToXml for Category {
-    fn to_xml<W: Write>(&self, writer: &mut Writer<W>) -> Result<(), XmlError> {
-        let name = b"category";
-        let mut element = BytesStart::borrowed(name, name.len());
+    fn to_xml<W: Write>(&self, writer: &mut XmlWriter<W>) -> Result<(), XmlError> {
+        let name = "category";
+        let mut element = BytesStart::new(name);
         if let Some(ref domain) = self.domain {
             element.push_attribute(("domain", &**domain));
         }
+        element.extend_attributes(
+            self.extra_attrs
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str())),
+        );
         writer.write_event(Event::Start(element))?;
-        writer.write_event(Event::Text(BytesText::from_plain_str(&self.name)))?;
-        writer.write_event(Event::End(BytesEnd::borrowed(name)))?;
+        writer.write_event(Event::Text(BytesText::new(&self.name)))?;
+        writer.write_event(Event::End(BytesEnd::new(name)))?;
         Ok(())
     }
 }
@@ -8,28 +8,54 @@
 use std::collections::BTreeMap;
 use std::io::{BufRead, Write};
 use std::str::{self, FromStr};
+use std::time::Duration;
 
 use quick_xml::events::attributes::Attributes;
-use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, Event};
+use quick_xml::events::{BytesEnd, BytesStart, Event};
 use quick_xml::Error as XmlError;
 use quick_xml::Reader;
-use quick_xml::Writer;
 
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Datelike, FixedOffset, TimeZone, Timelike, Utc};
+#[cfg(feature = "async")]
+use tokio::io::AsyncBufRead;
+
+use crate::borrowed::{read_from_reader, ChannelParser, ChannelRef};
+#[cfg(feature = "chrono")]
+use crate::canonical::canonical_dates;
+use crate::canonical::{canonical_options, canonicalize, ContentHasher};
 use crate::category::Category;
 use crate::cloud::Cloud;
-use crate::error::Error;
+#[cfg(feature = "chrono")]
+use crate::date::{format_date, parse_date};
+use crate::error::{Error, ParseError};
 #[cfg(feature = "atom")]
 use crate::extension::atom;
 use crate::extension::dublincore;
 use crate::extension::itunes;
 use crate::extension::syndication;
-use crate::extension::util::{extension_name, parse_extension};
+use crate::extension::util::take_extensions;
 use crate::extension::ExtensionMap;
 use crate::image::Image;
 use crate::item::Item;
+use crate::markup::TextKind;
+use crate::namespaces::Namespaces;
+use crate::parser::{ParseOptions, ParsedChannel};
+use crate::person::Person;
+#[cfg(feature = "async")]
+use crate::reader::AsyncChannelReader;
+use crate::reader::ChannelReader;
+#[cfg(feature = "chrono")]
+use crate::schedule::{day_name, parse_days, DaySet};
+use crate::schedule::{format_ttl, parse_hours, parse_ttl, HourSet};
 use crate::textinput::TextInput;
-use crate::toxml::{ToXml, WriterExt};
-use crate::util::element_text;
+use crate::toxml::{ToXml, WriterExt, XmlWriter};
+use crate::unknown::{UnknownElement, UnknownWriter};
+use crate::util::{RDF_NAMESPACE, RSS1_NAMESPACE};
+use crate::writer::{
+    convert_for_version, output_encoding, write_prolog, Encode, RssVersion, WriteOptions,
+    WriteWarning,
+};
 
 /// Represents the channel of an RSS feed.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -50,6 +76,8 @@ pub struct Channel {
     pub link: String,
     /// A description of the channel.
     pub description: String,
+    /// The kind of text in the description.
+    pub description_kind: TextKind,
     /// The language of the channel.
     pub language: Option<String>,
     /// The copyright notice for the channel.
@@ -100,9 +128,18 @@ pub struct Channel {
     pub dublin_core_ext: Option<dublincore::DublinCoreExtension>,
     /// The Syndication extension for the channel.
     pub syndication_ext: Option<syndication::SyndicationExtension>,
-    /// The namespaces present in the RSS tag.
+    /// The namespaces declared on the RSS and channel tags.
     #[cfg_attr(feature = "builders", builder(setter(each = "namespace")))]
     pub namespaces: BTreeMap<String, String>,
+    /// The URI that identifies the channel in an RSS 1.0 feed, from its `rdf:about` attribute.
+    pub about: Option<String>,
+    /// The elements without a namespace prefix that are not part of RSS.
+    #[cfg_attr(feature = "builders", builder(setter(each = "unknown_element")))]
+    pub unknown_elements: Vec<UnknownElement>,
+    /// The attributes of the channel element that are not part of RSS, such as `xml:lang`, keyed
+    /// by qualified name.
+    #[cfg_attr(feature = "builders", builder(setter(each = "extra_attr")))]
+    pub extra_attrs: BTreeMap<String, String>,
 }
 
 impl Channel {
@@ -202,6 +239,37 @@ impl Channel {
         self.description = description.into();
     }
 
+    /// Return the kind of text in the description of this channel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::{Channel, TextKind};
+    ///
+    /// let mut channel = Channel::default();
+    /// channel.set_description_kind(TextKind::Html);
+    /// assert_eq!(channel.description_kind(), TextKind::Html);
+    /// ```
+    pub fn description_kind(&self) -> TextKind {
+        self.description_kind
+    }
+
+    /// Set the kind of text in the description of this channel.
+    ///
+    /// Text of the kind `TextKind::Xhtml` is written as raw XML.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::{Channel, TextKind};
+    ///
+    /// let mut channel = Channel::default();
+    /// channel.set_description_kind(TextKind::Xhtml);
+    /// ```
+    pub fn set_description_kind(&mut self, description_kind: TextKind) {
+        self.description_kind = description_kind;
+    }
+
     /// Return the language of this channel.
     ///
     /// # Examples
@@ -299,6 +367,46 @@ impl Channel {
         self.managing_editor = managing_editor.into();
     }
 
+    /// Return the managing editor of this channel as a `Person`.
+    ///
+    /// Returns `None` if there is no managing editor or it is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::Channel;
+    ///
+    /// let mut channel = Channel::default();
+    /// channel.set_managing_editor("johndoe@example.com (John Doe)".to_string());
+    /// let person = channel.managing_editor_person().unwrap();
+    /// assert_eq!(person.name(), Some("John Doe"));
+    /// assert_eq!(person.email(), Some("johndoe@example.com"));
+    /// ```
+    pub fn managing_editor_person(&self) -> Option<Person> {
+        let person = Person::parse(self.managing_editor.as_deref()?);
+        Some(person).filter(|person| !person.is_empty())
+    }
+
+    /// Set the managing editor of this channel to a `Person`.
+    ///
+    /// The person is written in the RSS 2.0 form `email (name)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::{Channel, Person};
+    ///
+    /// let mut channel = Channel::default();
+    /// channel.set_managing_editor_person(Person::parse("John Doe <johndoe@example.com>"));
+    /// assert_eq!(channel.managing_editor(), Some("johndoe@example.com (John Doe)"));
+    /// ```
+    pub fn set_managing_editor_person<V>(&mut self, managing_editor: V)
+    where
+        V: Into<Option<Person>>,
+    {
+        self.managing_editor = managing_editor.into().map(|person| person.to_string());
+    }
+
     /// Return the email address for webmaster of this channel.
     ///
     /// # Examples
@@ -331,6 +439,46 @@ impl Channel {
         self.webmaster = webmaster.into();
     }
 
+    /// Return the webmaster of this channel as a `Person`.
+    ///
+    /// Returns `None` if there is no webmaster or it is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::Channel;
+    ///
+    /// let mut channel = Channel::default();
+    /// channel.set_webmaster("John Doe <johndoe@example.com>".to_string());
+    /// let person = channel.webmaster_person().unwrap();
+    /// assert_eq!(person.name(), Some("John Doe"));
+    /// assert_eq!(person.email(), Some("johndoe@example.com"));
+    /// ```
+    pub fn webmaster_person(&self) -> Option<Person> {
+        let person = Person::parse(self.webmaster.as_deref()?);
+        Some(person).filter(|person| !person.is_empty())
+    }
+
+    /// Set the webmaster of this channel to a `Person`.
+    ///
+    /// The person is written in the RSS 2.0 form `email (name)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::{Channel, Person};
+    ///
+    /// let mut channel = Channel::default();
+    /// channel.set_webmaster_person(Person::parse("John Doe <johndoe@example.com>"));
+    /// assert_eq!(channel.webmaster(), Some("johndoe@example.com (John Doe)"));
+    /// ```
+    pub fn set_webmaster_person<V>(&mut self, webmaster: V)
+    where
+        V: Into<Option<Person>>,
+    {
+        self.webmaster = webmaster.into().map(|person| person.to_string());
+    }
+
     /// Return the publication date for the content of this channel as an RFC822 timestamp.
     ///
     /// # Examples
@@ -360,14 +508,14 @@ impl Channel {
     ///
     /// ## Using chrono::DateTime
     /// ```
-    /// # #[cfg(feature = "validation")]
+    /// # #[cfg(feature = "chrono")]
     /// # {
     /// use rss::Channel;
     /// use chrono::{TimeZone, Utc};
     ///
     /// let mut channel = Channel::default();
-    /// channel.set_pub_date(Utc.ymd(2017, 1, 1).and_hms(12, 0, 0).to_rfc2822());
-    /// assert_eq!(channel.pub_date(), Some("Sun, 01 Jan 2017 12:00:00 +0000"));
+    /// channel.set_pub_date(Utc.with_ymd_and_hms(2017, 1, 1, 12, 0, 0).unwrap().to_rfc2822());
+    /// assert_eq!(channel.pub_date(), Some("Sun, 1 Jan 2017 12:00:00 +0000"));
     /// # }
     /// ```
     pub fn set_pub_date<V>(&mut self, pub_date: V)
@@ -377,6 +525,46 @@ impl Channel {
         self.pub_date = pub_date.into();
     }
 
+    /// Return the publication date of this channel, parsed as a date.
+    ///
+    /// Besides RFC 2822 timestamps, the common variants of them and ISO 8601 dates are accepted.
+    /// Returns `None` if there is no publication date or it cannot be parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::Channel;
+    /// use chrono::{TimeZone, Utc};
+    ///
+    /// let mut channel = Channel::default();
+    /// channel.set_pub_date("Sun, 1 Jan 2017 04:00 PST".to_string());
+    /// assert_eq!(
+    ///     channel.pub_date_parsed(),
+    ///     Some(Utc.with_ymd_and_hms(2017, 1, 1, 12, 0, 0).unwrap().into())
+    /// );
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn pub_date_parsed(&self) -> Option<DateTime<FixedOffset>> {
+        self.pub_date.as_deref().and_then(parse_date)
+    }
+
+    /// Set the publication date of this channel to a date, formatted as an RFC 2822 timestamp.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::Channel;
+    /// use chrono::{TimeZone, Utc};
+    ///
+    /// let mut channel = Channel::default();
+    /// channel.set_pub_date_datetime(Utc.with_ymd_and_hms(2017, 1, 1, 12, 0, 0).unwrap());
+    /// assert_eq!(channel.pub_date(), Some("Sun, 1 Jan 2017 12:00:00 +0000"));
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn set_pub_date_datetime<Tz: TimeZone>(&mut self, pub_date: DateTime<Tz>) {
+        self.pub_date = Some(format_date(&pub_date));
+    }
+
     /// Return the time that the content of this channel was last changed as an RFC822 timestamp.
     ///
     /// # Examples
@@ -406,14 +594,14 @@ impl Channel {
     ///
     /// ## Using chrono::DateTime
     /// ```
-    /// # #[cfg(feature = "validation")]
+    /// # #[cfg(feature = "chrono")]
     /// # {
     /// use rss::Channel;
     /// use chrono::{TimeZone, Utc};
     ///
     /// let mut channel = Channel::default();
-    /// channel.set_last_build_date(Utc.ymd(2017, 1, 1).and_hms(12, 0, 0).to_rfc2822());
-    /// assert_eq!(channel.last_build_date(), Some("Sun, 01 Jan 2017 12:00:00 +0000"));
+    /// channel.set_last_build_date(Utc.with_ymd_and_hms(2017, 1, 1, 12, 0, 0).unwrap().to_rfc2822());
+    /// assert_eq!(channel.last_build_date(), Some("Sun, 1 Jan 2017 12:00:00 +0000"));
     /// # }
     /// ```
     pub fn set_last_build_date<V>(&mut self, last_build_date: V)
@@ -423,6 +611,46 @@ impl Channel {
         self.last_build_date = last_build_date.into();
     }
 
+    /// Return the last build date of this channel, parsed as a date.
+    ///
+    /// Besides RFC 2822 timestamps, the common variants of them and ISO 8601 dates are accepted.
+    /// Returns `None` if there is no last build date or it cannot be parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::Channel;
+    /// use chrono::{TimeZone, Utc};
+    ///
+    /// let mut channel = Channel::default();
+    /// channel.set_last_build_date("Sun, 1 Jan 2017 04:00 PST".to_string());
+    /// assert_eq!(
+    ///     channel.last_build_date_parsed(),
+    ///     Some(Utc.with_ymd_and_hms(2017, 1, 1, 12, 0, 0).unwrap().into())
+    /// );
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn last_build_date_parsed(&self) -> Option<DateTime<FixedOffset>> {
+        self.last_build_date.as_deref().and_then(parse_date)
+    }
+
+    /// Set the last build date of this channel to a date, formatted as an RFC 2822 timestamp.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::Channel;
+    /// use chrono::{TimeZone, Utc};
+    ///
+    /// let mut channel = Channel::default();
+    /// channel.set_last_build_date_datetime(Utc.with_ymd_and_hms(2017, 1, 1, 12, 0, 0).unwrap());
+    /// assert_eq!(channel.last_build_date(), Some("Sun, 1 Jan 2017 12:00:00 +0000"));
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn set_last_build_date_datetime<Tz: TimeZone>(&mut self, last_build_date: DateTime<Tz>) {
+        self.last_build_date = Some(format_date(&last_build_date));
+    }
+
     /// Return the categories that this channel belongs to.
     ///
     /// # Examples
@@ -592,6 +820,40 @@ impl Channel {
         self.ttl = ttl.into();
     }
 
+    /// Return the time to live of this channel as a duration.
+    ///
+    /// Returns `None` if there is no time to live or it is not a whole number of minutes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use rss::Channel;
+    ///
+    /// let mut channel = Channel::default();
+    /// channel.set_ttl("60".to_string());
+    /// assert_eq!(channel.ttl_duration(), Some(Duration::from_secs(3600)));
+    /// ```
+    pub fn ttl_duration(&self) -> Option<Duration> {
+        self.ttl.as_deref().and_then(parse_ttl)
+    }
+
+    /// Set the time to live of this channel to a duration, rounded up to whole minutes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use rss::Channel;
+    ///
+    /// let mut channel = Channel::default();
+    /// channel.set_ttl_duration(Duration::from_secs(90));
+    /// assert_eq!(channel.ttl(), Some("2"));
+    /// ```
+    pub fn set_ttl_duration(&mut self, ttl: Duration) {
+        self.ttl = Some(format_ttl(ttl));
+    }
+
     /// Return the image to be displayed with this channel.
     ///
     /// # Examples
@@ -708,6 +970,40 @@ impl Channel {
         self.skip_hours = skip_hours.into();
     }
 
+    /// Return the hours that aggregators can skip for refreshing content as a set.
+    ///
+    /// Hours that are not valid are left out. Hour 24, which some feeds use for midnight, is
+    /// returned as hour 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::Channel;
+    ///
+    /// let mut channel = Channel::default();
+    /// channel.set_skip_hours(vec!["1".to_string(), "24".to_string(), "noon".to_string()]);
+    /// let hours = channel.skip_hours_set();
+    /// assert_eq!(hours.iter().collect::<Vec<_>>(), vec![0, 1]);
+    /// ```
+    pub fn skip_hours_set(&self) -> HourSet {
+        parse_hours(&self.skip_hours)
+    }
+
+    /// Set the hours that aggregators can skip for refreshing content from a set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::{Channel, HourSet};
+    ///
+    /// let mut channel = Channel::default();
+    /// channel.set_skip_hours_set(vec![6, 22].into_iter().collect::<HourSet>());
+    /// assert_eq!(channel.skip_hours(), ["6", "22"]);
+    /// ```
+    pub fn set_skip_hours_set(&mut self, skip_hours: HourSet) {
+        self.skip_hours = skip_hours.iter().map(|hour| hour.to_string()).collect();
+    }
+
     /// Return the days that aggregators can skip for refreshing content.
     ///
     /// # Examples
@@ -745,6 +1041,78 @@ impl Channel {
         self.skip_days = skip_days.into();
     }
 
+    /// Return the days that aggregators can skip for refreshing content as a set.
+    ///
+    /// Days are matched case-insensitively, and may be abbreviated. Days that are not valid are
+    /// left out.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::Weekday;
+    /// use rss::Channel;
+    ///
+    /// let mut channel = Channel::default();
+    /// channel.set_skip_days(vec!["saturday".to_string(), "SUN".to_string()]);
+    /// let days = channel.skip_days_set();
+    /// assert!(days.contains(Weekday::Sat));
+    /// assert!(days.contains(Weekday::Sun));
+    /// assert_eq!(days.len(), 2);
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn skip_days_set(&self) -> DaySet {
+        parse_days(&self.skip_days)
+    }
+
+    /// Set the days that aggregators can skip for refreshing content from a set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::Weekday;
+    /// use rss::{Channel, DaySet};
+    ///
+    /// let mut channel = Channel::default();
+    /// let days = vec![Weekday::Sun, Weekday::Sat].into_iter().collect::<DaySet>();
+    /// channel.set_skip_days_set(days);
+    /// assert_eq!(channel.skip_days(), ["Saturday", "Sunday"]);
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn set_skip_days_set(&mut self, skip_days: DaySet) {
+        self.skip_days = skip_days
+            .iter()
+            .map(|day| day_name(day).to_string())
+            .collect();
+    }
+
+    /// Whether aggregators can skip refreshing this channel at `time`, because its hour or day
+    /// is in `skipHours` or `skipDays`.
+    ///
+    /// Both are in GMT, so `time` is converted to UTC first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::{FixedOffset, TimeZone};
+    /// use rss::Channel;
+    ///
+    /// let mut channel = Channel::default();
+    /// channel.set_skip_hours(vec!["0".to_string()]);
+    /// channel.set_skip_days(vec!["Sunday".to_string()]);
+    ///
+    /// let offset = FixedOffset::east_opt(2 * 3600).unwrap();
+    /// // midnight in UTC
+    /// assert!(channel.is_skipped_at(&offset.with_ymd_and_hms(2017, 1, 4, 2, 30, 0).unwrap()));
+    /// // a Sunday in UTC
+    /// assert!(channel.is_skipped_at(&offset.with_ymd_and_hms(2017, 1, 1, 12, 0, 0).unwrap()));
+    /// assert!(!channel.is_skipped_at(&offset.with_ymd_and_hms(2017, 1, 4, 12, 0, 0).unwrap()));
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn is_skipped_at<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> bool {
+        let time = time.with_timezone(&Utc);
+        self.skip_hours_set().contains(time.hour()) || self.skip_days_set().contains(time.weekday())
+    }
+
     /// Return the items in this channel.
     ///
     /// # Examples
@@ -1018,130 +1386,343 @@ impl Channel {
     {
         self.namespaces = namespaces.into()
     }
+
+    /// Return the URI that identifies this channel in an RSS 1.0 feed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::Channel;
+    ///
+    /// let mut channel = Channel::default();
+    /// channel.set_about("http://example.com/feed.rdf".to_string());
+    /// assert_eq!(channel.about(), Some("http://example.com/feed.rdf"));
+    /// ```
+    pub fn about(&self) -> Option<&str> {
+        self.about.as_deref()
+    }
+
+    /// Set the URI that identifies this channel in an RSS 1.0 feed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::Channel;
+    ///
+    /// let mut channel = Channel::default();
+    /// channel.set_about("http://example.com/feed.rdf".to_string());
+    /// ```
+    pub fn set_about<V>(&mut self, about: V)
+    where
+        V: Into<Option<String>>,
+    {
+        self.about = about.into();
+    }
+
+    /// Return the elements without a namespace prefix that are not part of RSS in this channel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::{Channel, UnknownElement};
+    ///
+    /// let mut channel = Channel::default();
+    /// channel.set_unknown_elements(vec![UnknownElement::default()]);
+    /// assert_eq!(channel.unknown_elements().len(), 1);
+    /// ```
+    pub fn unknown_elements(&self) -> &[UnknownElement] {
+        &self.unknown_elements
+    }
+
+    /// Return a mutable slice of the unknown elements in this channel.
+    pub fn unknown_elements_mut(&mut self) -> &mut [UnknownElement] {
+        &mut self.unknown_elements
+    }
+
+    /// Set the elements without a namespace prefix that are not part of RSS in this channel.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::{Channel, UnknownElement};
+    ///
+    /// let mut channel = Channel::default();
+    /// channel.set_unknown_elements(vec![UnknownElement::default()]);
+    /// ```
+    pub fn set_unknown_elements<V>(&mut self, unknown_elements: V)
+    where
+        V: Into<Vec<UnknownElement>>,
+    {
+        self.unknown_elements = unknown_elements.into();
+    }
+
+    /// Return the attributes of this channel that are not part of RSS.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use rss::Channel;
+    ///
+    /// let mut extra_attrs = BTreeMap::new();
+    /// extra_attrs.insert("xml:lang".to_string(), "en".to_string());
+    ///
+    /// let mut channel = Channel::default();
+    /// channel.set_extra_attrs(extra_attrs);
+    /// assert_eq!(channel.extra_attrs()["xml:lang"], "en");
+    /// ```
+    pub fn extra_attrs(&self) -> &BTreeMap<String, String> {
+        &self.extra_attrs
+    }
+
+    /// Set the attributes of this channel that are not part of RSS.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use rss::Channel;
+    ///
+    /// let mut channel = Channel::default();
+    /// channel.set_extra_attrs(BTreeMap::new());
+    /// ```
+    pub fn set_extra_attrs<V>(&mut self, extra_attrs: V)
+    where
+        V: Into<BTreeMap<String, String>>,
+    {
+        self.extra_attrs = extra_attrs.into();
+    }
 }
 
 impl Channel {
     /// Attempt to read an RSS channel from a reader.
     ///
+    /// An error is returned along with the location at which it occurred.
+    ///
     /// # Example
     ///
     /// ```rust,ignore
     /// let reader: BufRead = ...;
     /// let channel = Channel::read_from(reader).unwrap();
     /// ```
-    pub fn read_from<R: BufRead>(reader: R) -> Result<Channel, Error> {
-        let mut reader = Reader::from_reader(reader);
-        reader.trim_text(true).expand_empty_elements(true);
-        let mut namespaces = BTreeMap::new();
-        let mut buf = Vec::new();
-        let mut skip_buf = Vec::new();
-
-        let mut channel: Option<Channel> = None;
-
-        // for parsing RSS 0.9, 1.0 feeds
-        let mut items: Option<Vec<Item>> = None;
-        let mut image: Option<Image> = None;
-        let mut text_input: Option<TextInput> = None;
-
-        // find opening element
-        loop {
-            match reader.read_event(&mut buf)? {
-                Event::Start(element) => {
-                    if element.name() == b"rss" || element.name() == b"rdf:RDF" {
-                        for attr in element.attributes().with_checks(false).flatten() {
-                            if attr.key.starts_with(b"xmlns:") {
-                                let prefix = str::from_utf8(&attr.key[6..])?.to_string();
-                                let namespace = attr.unescape_and_decode_value(&reader)?;
-                                namespaces.insert(prefix, namespace);
-                            }
-                        }
-
-                        break;
-                    } else {
-                        return Err(Error::InvalidStartTag);
-                    }
-                }
-                Event::Eof => return Err(Error::Eof),
-                _ => continue,
-            }
-        }
+    pub fn read_from<R: BufRead>(reader: R) -> Result<Channel, ParseError> {
+        Channel::read_from_with_options(reader, ParseOptions::default())
+            .map(|parsed| parsed.channel)
+    }
 
-        loop {
-            match reader.read_event(&mut buf)? {
-                Event::Start(element) => match element.name() {
-                    b"channel" => {
-                        let inner =
-                            Channel::from_xml(&namespaces, &mut reader, element.attributes())?;
-                        channel = Some(inner);
-                    }
-                    b"item" => {
-                        let item = Item::from_xml(&namespaces, &mut reader, element.attributes())?;
-                        if items.is_none() {
-                            items = Some(Vec::new());
-                        }
-                        items.as_mut().unwrap().push(item);
-                    }
-                    b"image" => {
-                        let inner = Image::from_xml(&mut reader, element.attributes())?;
-                        image = Some(inner);
-                    }
-                    b"textinput" => {
-                        let inner = TextInput::from_xml(&mut reader, element.attributes())?;
-                        text_input = Some(inner);
-                    }
-                    name => reader.read_to_end(name, &mut skip_buf)?,
-                },
-                Event::End(_) | Event::Eof => break,
-                _ => {}
-            }
-            buf.clear();
+    /// Attempt to read an RSS channel from a reader using the given options.
+    ///
+    /// The problems in the input that were skipped or repaired are returned along with the
+    /// channel, and an error is returned along with the location at which it occurred.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::{Channel, ParseOptions};
+    ///
+    /// let input = "<rss><channel><title>Title</title><item><title>Item</item></channel>";
+    /// let options = ParseOptions {
+    ///     lenient: true,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let parsed = Channel::read_from_with_options(input.as_bytes(), options).unwrap();
+    /// assert_eq!(parsed.channel.title(), "Title");
+    /// assert_eq!(parsed.channel.items()[0].title(), Some("Item"));
+    /// assert_eq!(parsed.warnings.len(), 2);
+    /// ```
+    pub fn read_from_with_options<R: BufRead>(
+        reader: R,
+        options: ParseOptions,
+    ) -> Result<ParsedChannel, ParseError> {
+        let mut reader = ChannelReader::with_options(reader, options)?;
+        let mut items = Vec::new();
+
+        for item in &mut reader {
+            items.push(item?);
         }
 
-        if let Some(mut channel) = channel {
-            if let Some(mut items) = items {
-                channel.items.append(&mut items);
-            }
+        reader.finish(items)
+    }
 
-            if image.is_some() {
-                channel.image = image;
-            }
+    /// Attempt to read an RSS channel from an asynchronous reader.
+    ///
+    /// The input is parsed as it arrives. The result is the same as for `read_from`.
+    ///
+    /// **Note**: This requires the `async` feature.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let reader: AsyncBufRead + Unpin = ...;
+    /// let channel = Channel::read_from_async(reader).await.unwrap();
+    /// ```
+    #[cfg(feature = "async")]
+    pub async fn read_from_async<A>(reader: A) -> Result<Channel, ParseError>
+    where
+        A: AsyncBufRead + Unpin,
+    {
+        let mut reader = AsyncChannelReader::new(reader).await?;
+        let mut items = Vec::new();
 
-            if text_input.is_some() {
-                channel.text_input = text_input;
-            }
+        while let Some(item) = reader.next_item().await {
+            items.push(item?);
+        }
 
-            channel.namespaces = namespaces;
+        reader.finish(items).map(|parsed| parsed.channel)
+    }
 
-            Ok(channel)
-        } else {
-            Err(Error::Eof)
+    /// Writes this channel as the version of RSS in `options`, which the channel has been
+    /// converted for.
+    fn write_document<W: Write>(
+        &self,
+        writer: W,
+        options: &WriteOptions,
+        namespaces: &Namespaces,
+    ) -> Result<W, Error> {
+        let writer = XmlWriter::new(writer, options);
+        match options.version {
+            RssVersion::V1_0 => self.write_rdf(writer, options, namespaces),
+            _ => self.write(writer, options, namespaces),
         }
     }
 
-    fn write<W: Write>(&self, mut writer: Writer<W>) -> Result<W, Error> {
-        writer.write_event(Event::Decl(BytesDecl::new(b"1.0", Some(b"utf-8"), None)))?;
+    fn write<W: Write>(
+        &self,
+        mut writer: XmlWriter<W>,
+        options: &WriteOptions,
+        namespaces: &Namespaces,
+    ) -> Result<W, Error> {
+        write_rss_start(namespaces, options, &mut writer)?;
+        self.write_xml(namespaces, options, &mut writer)?;
+        write_rss_end(&mut writer)?;
+        Ok(writer.into_inner())
+    }
+
+    fn write_rdf<W: Write>(
+        &self,
+        mut writer: XmlWriter<W>,
+        options: &WriteOptions,
+        namespaces: &Namespaces,
+    ) -> Result<W, Error> {
+        write_prolog(&mut writer, options)?;
 
-        let name = b"rss";
-        let mut element = BytesStart::borrowed(name, name.len());
-        element.push_attribute(("version", "2.0"));
+        let name = "rdf:RDF";
+        let mut element = BytesStart::new(name);
+        element.push_attribute(("xmlns:rdf", RDF_NAMESPACE));
+        element.push_attribute(("xmlns", RSS1_NAMESPACE));
 
-        let used_namespaces = self.used_namespaces();
-        let mut namespaces: BTreeMap<&String, &String> = BTreeMap::new();
-        namespaces.extend(&used_namespaces);
-        namespaces.extend(&self.namespaces);
-        for (name, url) in namespaces {
+        for (name, url) in namespaces.declared() {
+            if name == "rdf" {
+                continue;
+            }
             element.push_attribute((format!("xmlns:{}", name).as_bytes(), url.as_bytes()));
         }
 
         writer.write_event(Event::Start(element))?;
 
-        self.to_xml(&mut writer)?;
+        let channel_name = "channel";
+        let mut element = BytesStart::new(channel_name);
+        element.push_attribute(("rdf:about", self.about.as_deref().unwrap_or(&self.link)));
+        element.extend_attributes(
+            self.extra_attrs
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str())),
+        );
+        writer.write_event(Event::Start(element))?;
+
+        let mut unknown = UnknownWriter::new(&self.unknown_elements);
+        unknown.write_after(&mut writer, None)?;
+        writer.write_text_element("title", &self.title)?;
+        unknown.write_after(&mut writer, Some("title"))?;
+        writer.write_text_element("link", &self.link)?;
+        unknown.write_after(&mut writer, Some("link"))?;
+        match self.description_kind {
+            TextKind::Xhtml => writer.write_raw_element("description", &self.description)?,
+            _ => writer.write_styled_element(
+                "description",
+                &self.description,
+                options.channel_description,
+            )?,
+        }
+        unknown.write_after(&mut writer, Some("description"))?;
+
+        if let Some(image) = self.image.as_ref() {
+            writer.write_resource_element("image", image.rdf_about())?;
+        }
+
+        let items_name = "items";
+        writer.write_event(Event::Start(BytesStart::new(items_name)))?;
+        let seq_name = "rdf:Seq";
+        writer.write_event(Event::Start(BytesStart::new(seq_name)))?;
+        for item in &self.items {
+            writer.write_resource_element("rdf:li", item.rdf_about())?;
+        }
+        writer.write_event(Event::End(BytesEnd::new(seq_name)))?;
+        writer.write_event(Event::End(BytesEnd::new(items_name)))?;
+
+        if let Some(text_input) = self.text_input.as_ref() {
+            writer.write_resource_element("textinput", text_input.rdf_about())?;
+        }
+
+        unknown.write_rest(&mut writer)?;
+
+        self.write_extensions(namespaces, &mut writer)?;
 
-        writer.write_event(Event::End(BytesEnd::borrowed(name)))?;
+        writer.write_event(Event::End(BytesEnd::new(channel_name)))?;
+
+        if let Some(image) = self.image.as_ref() {
+            image.write_rdf(&mut writer)?;
+        }
+
+        for item in &self.items {
+            item.write_rdf(namespaces, options, &mut writer)?;
+        }
+
+        if let Some(text_input) = self.text_input.as_ref() {
+            text_input.write_rdf(&mut writer)?;
+        }
+
+        writer.write_event(Event::End(BytesEnd::new(name)))?;
 
         Ok(writer.into_inner())
     }
 
+    /// Writes the extensions of this channel. `namespaces` are the namespaces declared for the
+    /// document, which decide the prefixes the extensions are written with.
+    fn write_extensions<W: Write>(
+        &self,
+        namespaces: &Namespaces,
+        writer: &mut XmlWriter<W>,
+    ) -> Result<(), XmlError> {
+        for map in self.extensions.values() {
+            for extensions in map.values() {
+                for extension in extensions {
+                    extension.write_xml(namespaces, writer)?;
+                }
+            }
+        }
+
+        #[cfg(feature = "atom")]
+        if let Some(ext) = &self.atom_ext {
+            ext.to_xml(namespaces.declared(), writer)?;
+        }
+
+        if let Some(ext) = &self.itunes_ext {
+            ext.to_xml(writer)?;
+        }
+
+        if let Some(ext) = &self.dublin_core_ext {
+            ext.to_xml(writer)?;
+        }
+
+        if let Some(ext) = &self.syndication_ext {
+            ext.to_xml(namespaces.declared(), writer)?;
+        }
+
+        Ok(())
+    }
+
     /// Attempt to write the RSS channel as XML to a writer.
     ///
     /// # Example
@@ -1152,7 +1733,33 @@ impl Channel {
     /// channel.write_to(writer).unwrap();
     /// ```
     pub fn write_to<W: Write>(&self, writer: W) -> Result<W, Error> {
-        self.write(::quick_xml::Writer::new(writer))
+        self.write_document(
+            writer,
+            &WriteOptions::default(),
+            &Namespaces::for_channel(self),
+        )
+    }
+
+    /// Attempt to write the RSS channel as an RSS 1.0 document to a writer.
+    ///
+    /// RSS 1.0 has fewer elements than RSS 2.0, so only the title, link and description of the
+    /// channel and its items, the image, the text input, `content:encoded` and the extensions are
+    /// written. The `rdf:about` URI of the channel, the items and the text input defaults to
+    /// their link, and that of the image to its URL.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let channel: Channel = ...;
+    /// let writer: Write = ...;
+    /// channel.write_rdf_to(writer).unwrap();
+    /// ```
+    pub fn write_rdf_to<W: Write>(&self, writer: W) -> Result<W, Error> {
+        let options = WriteOptions {
+            version: RssVersion::V1_0,
+            ..Default::default()
+        };
+        self.write_document(writer, &options, &Namespaces::for_channel(self))
     }
 
     /// Attempt to write the RSS channel as pretty XML to a writer.
@@ -1170,14 +1777,129 @@ impl Channel {
         indent_char: u8,
         indent_size: usize,
     ) -> Result<W, Error> {
-        self.write(::quick_xml::Writer::new_with_indent(
-            writer,
-            indent_char,
-            indent_size,
-        ))
+        let options = WriteOptions {
+            indent: Some((indent_char, indent_size)),
+            ..Default::default()
+        };
+        self.write_document(writer, &options, &Namespaces::for_channel(self))
+    }
+
+    /// Attempt to write the RSS channel as XML to a writer, using the given options.
+    ///
+    /// The elements that the requested version of RSS does not support are left out, or written
+    /// as an equivalent element where there is one. Returns the writer along with a warning for
+    /// each element that was left out, and for each prefix that is not bound to any namespace.
+    /// Writing a channel without a language as RSS 0.91 fails with `Error::Version`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::{ChannelBuilder, EnclosureBuilder, ItemBuilder, RssVersion, WriteOptions};
+    ///
+    /// let channel = ChannelBuilder::default()
+    ///     .title("Title")
+    ///     .language("en-us".to_string())
+    ///     .items(vec![ItemBuilder::default()
+    ///         .title("Episode 1".to_string())
+    ///         .enclosure(EnclosureBuilder::default().url("http://example.com/1.mp3").build())
+    ///         .build()])
+    ///     .build();
+    ///
+    /// let options = WriteOptions {
+    ///     version: RssVersion::V0_91,
+    ///     ..Default::default()
+    /// };
+    /// let (output, warnings) = channel.write_with_options(Vec::new(), &options).unwrap();
+    /// assert!(String::from_utf8(output).unwrap().contains(r#"<rss version="0.91">"#));
+    /// assert_eq!(warnings[0].path, "channel/item/enclosure");
+    /// ```
+    pub fn write_with_options<W: Write>(
+        &self,
+        writer: W,
+        options: &WriteOptions,
+    ) -> Result<(W, Vec<WriteWarning>), Error> {
+        let (channel, mut warnings) = convert_for_version(self, options.version)?;
+        let encoding = output_encoding(options)?;
+        let namespaces = Namespaces::for_channel(&channel);
+        warnings.extend_from_slice(namespaces.warnings());
+        let writer = channel.write_document(Encode::new(writer, encoding), options, &namespaces)?;
+        let writer = writer.finish().map_err(XmlError::from)?;
+        Ok((writer, warnings))
+    }
+
+    /// Attempt to write the RSS channel in canonical form to a writer.
+    ///
+    /// The canonical form is an RSS 2.0 document that is the same, byte for byte, for channels
+    /// that differ only in the way they would otherwise be written: elements are written in a
+    /// fixed order without indentation, attributes are sorted by name, text and attribute values
+    /// are escaped the same way, CDATA sections are written as text, whitespace at the start and
+    /// end of text is removed, and elements without content are self-closing. With the `chrono`
+    /// feature, dates that can be parsed are written in UTC in a single format.
+    ///
+    /// This makes the canonical form suitable for diffs and for detecting changes, but it is not
+    /// meant for publishing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::Channel;
+    ///
+    /// let mut first = Channel::default();
+    /// first.set_title("Title");
+    /// first.set_description("First line\r\nSecond line");
+    ///
+    /// let mut second = Channel::default();
+    /// second.set_title(" Title\n");
+    /// second.set_description("First line\nSecond line");
+    ///
+    /// let output = first.write_canonical(Vec::new()).unwrap();
+    /// assert_eq!(output, second.write_canonical(Vec::new()).unwrap());
+    /// ```
+    pub fn write_canonical<W: Write>(&self, writer: W) -> Result<W, Error> {
+        #[cfg(feature = "chrono")]
+        let channel = &{
+            let mut channel = self.clone();
+            canonical_dates(&mut channel);
+            channel
+        };
+        #[cfg(not(feature = "chrono"))]
+        let channel = self;
+
+        let namespaces = Namespaces::for_channel(channel);
+        let document = channel.write_document(Vec::new(), &canonical_options(), &namespaces)?;
+        let document = canonicalize(&document)?;
+        let mut writer = writer;
+        writer.write_all(&document).map_err(XmlError::from)?;
+        Ok(writer)
+    }
+
+    /// Return a hash of the canonical form of the RSS channel, which changes when the content of
+    /// the channel changes.
+    ///
+    /// The hash is a 64-bit FNV-1a hash of the output of `write_canonical`, so it is the same on
+    /// every platform, but it may change between versions of this crate if the canonical form
+    /// does. It is meant for detecting changes, not for security.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::Channel;
+    ///
+    /// let mut channel: Channel = "<rss><channel><title>Title</title></channel></rss>"
+    ///     .parse()
+    ///     .unwrap();
+    /// let hash = channel.content_hash().unwrap();
+    ///
+    /// channel.set_title("Another title");
+    /// assert_ne!(channel.content_hash().unwrap(), hash);
+    /// ```
+    pub fn content_hash(&self) -> Result<u64, Error> {
+        let hasher = self.write_canonical(ContentHasher::new())?;
+        Ok(hasher.finish())
     }
 }
 
+#[allow(clippy::to_string_trait_impl)]
 impl ToString for Channel {
     fn to_string(&self) -> String {
         let buf = self.write_to(Vec::new()).unwrap_or_default();
@@ -1191,279 +1913,291 @@ impl Channel {
     pub fn from_xml<R: BufRead>(
         namespaces: &BTreeMap<String, String>,
         reader: &mut Reader<R>,
-        _: Attributes,
+        atts: Attributes,
     ) -> Result<Self, Error> {
-        let mut channel = Channel::default();
-        let mut buf = Vec::new();
-        let mut skip_buf = Vec::new();
-
-        loop {
-            match reader.read_event(&mut buf)? {
-                Event::Start(element) => match element.name() {
-                    b"category" => {
-                        let category = Category::from_xml(reader, element.attributes())?;
-                        channel.categories.push(category);
-                    }
-                    b"cloud" => {
-                        let cloud = Cloud::from_xml(reader, element.attributes())?;
-                        channel.cloud = Some(cloud);
-                    }
-                    b"image" => {
-                        let image = Image::from_xml(reader, element.attributes())?;
-                        channel.image = Some(image);
-                    }
-                    b"textInput" => {
-                        let text_input = TextInput::from_xml(reader, element.attributes())?;
-                        channel.text_input = Some(text_input);
-                    }
-                    b"item" => {
-                        let item = Item::from_xml(&namespaces, reader, element.attributes())?;
-                        channel.items.push(item);
-                    }
-                    b"title" => {
-                        if let Some(content) = element_text(reader)? {
-                            channel.title = content;
-                        }
-                    }
-                    b"link" => {
-                        if let Some(content) = element_text(reader)? {
-                            channel.link = content;
-                        }
-                    }
-                    b"description" => {
-                        if let Some(content) = element_text(reader)? {
-                            channel.description = content;
-                        }
-                    }
-                    b"language" => channel.language = element_text(reader)?,
-                    b"copyright" => channel.copyright = element_text(reader)?,
-                    b"managingEditor" => {
-                        channel.managing_editor = element_text(reader)?;
-                    }
-                    b"webMaster" => channel.webmaster = element_text(reader)?,
-                    b"pubDate" => channel.pub_date = element_text(reader)?,
-                    b"lastBuildDate" => {
-                        channel.last_build_date = element_text(reader)?;
-                    }
-                    b"generator" => channel.generator = element_text(reader)?,
-                    b"rating" => channel.rating = element_text(reader)?,
-                    b"docs" => channel.docs = element_text(reader)?,
-                    b"ttl" => channel.ttl = element_text(reader)?,
-                    b"skipHours" => loop {
-                        skip_buf.clear();
-                        match reader.read_event(&mut skip_buf)? {
-                            Event::Start(element) => {
-                                if element.name() == b"hour" {
-                                    if let Some(content) = element_text(reader)? {
-                                        channel.skip_hours.push(content);
-                                    }
-                                } else {
-                                    reader.read_to_end(element.name(), &mut Vec::new())?;
-                                }
-                            }
-                            Event::End(_) | Event::Eof => break,
-                            _ => {}
-                        }
-                    },
-                    b"skipDays" => loop {
-                        skip_buf.clear();
-                        match reader.read_event(&mut skip_buf)? {
-                            Event::Start(element) => {
-                                if element.name() == b"day" {
-                                    if let Some(content) = element_text(reader)? {
-                                        channel.skip_days.push(content);
-                                    }
-                                } else {
-                                    reader.read_to_end(element.name(), &mut Vec::new())?;
-                                }
-                            }
-                            Event::End(_) | Event::Eof => break,
-                            _ => {}
-                        }
-                    },
-                    n => {
-                        if let Some((ns, name)) = extension_name(element.name()) {
-                            parse_extension(
-                                reader,
-                                element.attributes(),
-                                ns,
-                                name,
-                                &mut channel.extensions,
-                            )?;
-                        } else {
-                            reader.read_to_end(n, &mut skip_buf)?;
-                        }
-                    }
-                },
-                Event::End(_) => break,
-                Event::Eof => return Err(Error::Eof),
-                _ => {}
-            }
+        read_from_reader(reader, |parser| {
+            ChannelParser::read_channel(parser, namespaces, atts)
+        })
+        .map(ChannelRef::into_owned)
+    }
+
+    /// Moves the extensions with a known namespace into their typed fields.
+    ///
+    /// `extension_namespaces` maps the prefixes of the extensions to the namespaces they were
+    /// bound to when they were read.
+    pub(crate) fn parse_extensions(&mut self, extension_namespaces: &BTreeMap<String, String>) {
+        if self.extensions.is_empty() {
+            return;
+        }
 
-            buf.clear();
+        // Extensions in other namespaces are left in place to support pass-through of unknown
+        // extensions
+        #[cfg(feature = "atom")]
+        if let Some(map) =
+            take_extensions(&mut self.extensions, extension_namespaces, atom::NAMESPACE)
+        {
+            self.atom_ext = Some(atom::AtomExtension::from_map(map));
         }
 
-        if !channel.extensions.is_empty() {
-            // Process each of the namespaces we know (note that the values are not removed prior and reused to support pass-through of unknown extensions)
-            for (prefix, namespace) in namespaces {
-                match namespace.as_ref() {
-                    #[cfg(feature = "atom")]
-                    atom::NAMESPACE => channel
-                        .extensions
-                        .remove(prefix)
-                        .map(|v| channel.atom_ext = Some(atom::AtomExtension::from_map(v))),
-                    itunes::NAMESPACE => channel.extensions.remove(prefix).map(|v| {
-                        channel.itunes_ext = Some(itunes::ITunesChannelExtension::from_map(v))
-                    }),
-                    dublincore::NAMESPACE => channel.extensions.remove(prefix).map(|v| {
-                        channel.dublin_core_ext = Some(dublincore::DublinCoreExtension::from_map(v))
-                    }),
-                    syndication::NAMESPACE => channel.extensions.remove(prefix).map(|v| {
-                        channel.syndication_ext =
-                            Some(syndication::SyndicationExtension::from_map(v))
-                    }),
-                    _ => None,
-                };
-            }
+        if let Some(map) = take_extensions(
+            &mut self.extensions,
+            extension_namespaces,
+            itunes::NAMESPACE,
+        ) {
+            self.itunes_ext = Some(itunes::ITunesChannelExtension::from_map(map));
         }
 
-        Ok(channel)
+        if let Some(map) = take_extensions(
+            &mut self.extensions,
+            extension_namespaces,
+            dublincore::NAMESPACE,
+        ) {
+            self.dublin_core_ext = Some(dublincore::DublinCoreExtension::from_map(map));
+        }
+
+        if let Some(map) = take_extensions(
+            &mut self.extensions,
+            extension_namespaces,
+            syndication::NAMESPACE,
+        ) {
+            self.syndication_ext = Some(syndication::SyndicationExtension::from_map(map));
+        }
     }
 }
 
 impl ToXml for Channel {
-    fn to_xml<W: Write>(&self, writer: &mut Writer<W>) -> Result<(), XmlError> {
-        let name = b"channel";
+    fn to_xml<W: Write>(&self, writer: &mut XmlWriter<W>) -> Result<(), XmlError> {
+        self.write_xml(
+            &Namespaces::for_channel(self),
+            &WriteOptions::default(),
+            writer,
+        )
+    }
 
-        writer.write_event(Event::Start(BytesStart::borrowed(name, name.len())))?;
+    fn used_namespaces(&self) -> BTreeMap<String, String> {
+        let mut namespaces = BTreeMap::new();
+        for item in &self.items {
+            namespaces.extend(item.used_namespaces());
+        }
+        #[cfg(feature = "atom")]
+        if self.atom_ext.is_some() {
+            namespaces.insert("atom".to_owned(), atom::NAMESPACE.to_owned());
+        }
+        if let Some(ext) = self.itunes_ext() {
+            namespaces.extend(ext.used_namespaces());
+        }
+        if let Some(ext) = self.dublin_core_ext() {
+            namespaces.extend(ext.used_namespaces());
+        }
+        if let Some(ext) = self.syndication_ext() {
+            namespaces.extend(ext.used_namespaces());
+        }
+        if self.about.is_some()
+            || self
+                .image
+                .as_ref()
+                .is_some_and(|image| image.about.is_some())
+            || self
+                .text_input
+                .as_ref()
+                .is_some_and(|text_input| text_input.about.is_some())
+        {
+            namespaces.insert("rdf".to_owned(), RDF_NAMESPACE.to_owned());
+        }
+        namespaces
+    }
+}
+
+/// Writes the XML declaration and the start tag of the `rss` element of an RSS 0.91, 0.92 or 2.0
+/// document.
+pub(crate) fn write_rss_start<W: Write>(
+    namespaces: &Namespaces,
+    options: &WriteOptions,
+    writer: &mut XmlWriter<W>,
+) -> Result<(), Error> {
+    write_prolog(writer, options)?;
+
+    let name = "rss";
+    let mut element = BytesStart::new(name);
+    element.push_attribute(("version", options.version.as_str()));
+    for (name, url) in namespaces.declared() {
+        element.push_attribute((format!("xmlns:{}", name).as_bytes(), url.as_bytes()));
+    }
+
+    writer.write_event(Event::Start(element))?;
+    Ok(())
+}
+
+/// Writes the end tag of the `rss` element.
+pub(crate) fn write_rss_end<W: Write>(writer: &mut XmlWriter<W>) -> Result<(), XmlError> {
+    writer.write_event(Event::End(BytesEnd::new("rss")))
+}
+
+impl Channel {
+    /// Writes this channel, using `namespaces` to decide the prefixes of its extensions.
+    fn write_xml<W: Write>(
+        &self,
+        namespaces: &Namespaces,
+        options: &WriteOptions,
+        writer: &mut XmlWriter<W>,
+    ) -> Result<(), XmlError> {
+        let mut unknown = self.write_start(namespaces, options, writer)?;
+        for item in &self.items {
+            item.write_xml(namespaces, options, writer)?;
+        }
+        Channel::write_end(&mut unknown, writer)
+    }
 
-        writer.write_text_element(b"title", &self.title)?;
-        writer.write_text_element(b"link", &self.link)?;
-        writer.write_text_element(b"description", &self.description)?;
+    /// Writes the start tag of this channel and the elements that come before its items.
+    ///
+    /// Returns the unknown elements that come after the items, which are written by `write_end`.
+    pub(crate) fn write_start<W: Write>(
+        &self,
+        namespaces: &Namespaces,
+        options: &WriteOptions,
+        writer: &mut XmlWriter<W>,
+    ) -> Result<UnknownWriter<'_>, XmlError> {
+        let name = "channel";
+        let mut element = BytesStart::new(name);
+        if let Some(about) = self.about.as_ref() {
+            element.push_attribute(("rdf:about", about.as_str()));
+        }
+
+        element.extend_attributes(
+            self.extra_attrs
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str())),
+        );
+
+        writer.write_event(Event::Start(element))?;
+
+        let mut unknown = UnknownWriter::new(&self.unknown_elements);
+        unknown.write_after(writer, None)?;
+
+        writer.write_text_element("title", &self.title)?;
+        unknown.write_after(writer, Some("title"))?;
+        writer.write_text_element("link", &self.link)?;
+        unknown.write_after(writer, Some("link"))?;
+        match self.description_kind {
+            TextKind::Xhtml => writer.write_raw_element("description", &self.description)?,
+            _ => writer.write_styled_element(
+                "description",
+                &self.description,
+                options.channel_description,
+            )?,
+        }
+        unknown.write_after(writer, Some("description"))?;
 
         if let Some(language) = self.language.as_ref() {
-            writer.write_text_element(b"language", language)?;
+            writer.write_text_element("language", language)?;
         }
+        unknown.write_after(writer, Some("language"))?;
 
         if let Some(copyright) = self.copyright.as_ref() {
-            writer.write_text_element(b"copyright", copyright)?;
+            writer.write_text_element("copyright", copyright)?;
         }
+        unknown.write_after(writer, Some("copyright"))?;
 
         if let Some(managing_editor) = self.managing_editor.as_ref() {
-            writer.write_text_element(b"managingEditor", managing_editor)?;
+            writer.write_text_element("managingEditor", managing_editor)?;
         }
+        unknown.write_after(writer, Some("managingEditor"))?;
 
         if let Some(webmaster) = self.webmaster.as_ref() {
-            writer.write_text_element(b"webMaster", webmaster)?;
+            writer.write_text_element("webMaster", webmaster)?;
         }
+        unknown.write_after(writer, Some("webMaster"))?;
 
         if let Some(pub_date) = self.pub_date.as_ref() {
-            writer.write_text_element(b"pubDate", pub_date)?;
+            writer.write_text_element("pubDate", pub_date)?;
         }
+        unknown.write_after(writer, Some("pubDate"))?;
 
         if let Some(last_build_date) = self.last_build_date.as_ref() {
-            writer.write_text_element(b"lastBuildDate", last_build_date)?;
+            writer.write_text_element("lastBuildDate", last_build_date)?;
         }
+        unknown.write_after(writer, Some("lastBuildDate"))?;
 
         writer.write_objects(&self.categories)?;
+        unknown.write_after(writer, Some("category"))?;
 
         if let Some(generator) = self.generator.as_ref() {
-            writer.write_text_element(b"generator", generator)?;
+            writer.write_text_element("generator", generator)?;
         }
+        unknown.write_after(writer, Some("generator"))?;
 
         if let Some(rating) = self.rating.as_ref() {
-            writer.write_text_element(b"rating", rating)?;
+            writer.write_text_element("rating", rating)?;
         }
+        unknown.write_after(writer, Some("rating"))?;
 
         if let Some(docs) = self.docs.as_ref() {
-            writer.write_text_element(b"docs", docs)?;
+            writer.write_text_element("docs", docs)?;
         }
+        unknown.write_after(writer, Some("docs"))?;
 
         if let Some(cloud) = self.cloud.as_ref() {
             writer.write_object(cloud)?;
         }
+        unknown.write_after(writer, Some("cloud"))?;
 
         if let Some(ttl) = self.ttl.as_ref() {
-            writer.write_text_element(b"ttl", ttl)?;
+            writer.write_text_element("ttl", ttl)?;
         }
+        unknown.write_after(writer, Some("ttl"))?;
 
         if let Some(image) = self.image.as_ref() {
             writer.write_object(image)?;
         }
+        unknown.write_after(writer, Some("image"))?;
 
         if let Some(text_input) = self.text_input.as_ref() {
             writer.write_object(text_input)?;
         }
+        unknown.write_after(writer, Some("textInput"))?;
 
         if !self.skip_hours.is_empty() {
-            let name = b"skipHours";
-            writer.write_event(Event::Start(BytesStart::borrowed(name, name.len())))?;
+            let name = "skipHours";
+            writer.write_event(Event::Start(BytesStart::new(name)))?;
             for hour in &self.skip_hours {
-                writer.write_text_element(b"hour", hour)?;
+                writer.write_text_element("hour", hour)?;
             }
-            writer.write_event(Event::End(BytesEnd::borrowed(name)))?;
+            writer.write_event(Event::End(BytesEnd::new(name)))?;
         }
+        unknown.write_after(writer, Some("skipHours"))?;
 
         if !self.skip_days.is_empty() {
-            let name = b"skipDays";
-            writer.write_event(Event::Start(BytesStart::borrowed(name, name.len())))?;
+            let name = "skipDays";
+            writer.write_event(Event::Start(BytesStart::new(name)))?;
             for day in &self.skip_days {
-                writer.write_text_element(b"day", day)?;
-            }
-            writer.write_event(Event::End(BytesEnd::borrowed(name)))?;
-        }
-
-        for map in self.extensions.values() {
-            for extensions in map.values() {
-                for extension in extensions {
-                    extension.to_xml(writer)?;
-                }
+                writer.write_text_element("day", day)?;
             }
+            writer.write_event(Event::End(BytesEnd::new(name)))?;
         }
+        unknown.write_after(writer, Some("skipDays"))?;
 
-        #[cfg(feature = "atom")]
-        if let Some(ext) = &self.atom_ext {
-            ext.to_xml(writer)?;
-        }
-
-        if let Some(ext) = &self.itunes_ext {
-            ext.to_xml(writer)?;
-        }
-
-        if let Some(ext) = &self.dublin_core_ext {
-            ext.to_xml(writer)?;
-        }
-
-        if let Some(ext) = &self.syndication_ext {
-            ext.to_xml(&self.namespaces, writer)?;
-        }
+        self.write_extensions(namespaces, writer)?;
 
-        writer.write_objects(&self.items)?;
-
-        writer.write_event(Event::End(BytesEnd::borrowed(name)))?;
-        Ok(())
+        Ok(unknown)
     }
 
-    fn used_namespaces(&self) -> BTreeMap<String, String> {
-        let mut namespaces = BTreeMap::new();
-        for item in &self.items {
-            namespaces.extend(item.used_namespaces());
-        }
-        if let Some(ext) = self.itunes_ext() {
-            namespaces.extend(ext.used_namespaces());
-        }
-        if let Some(ext) = self.dublin_core_ext() {
-            namespaces.extend(ext.used_namespaces());
-        }
-        namespaces
+    /// Writes the unknown elements that come after the items of a channel, and the end tag of the
+    /// channel.
+    pub(crate) fn write_end<W: Write>(
+        unknown: &mut UnknownWriter,
+        writer: &mut XmlWriter<W>,
+    ) -> Result<(), XmlError> {
+        unknown.write_after(writer, Some("item"))?;
+        unknown.write_rest(writer)?;
+        writer.write_event(Event::End(BytesEnd::new("channel")))
     }
 }
 
 impl FromStr for Channel {
-    type Err = Error;
+    type Err = ParseError;
 
     #[inline]
-    fn from_str(s: &str) -> Result<Channel, Error> {
+    fn from_str(s: &str) -> Result<Channel, ParseError> {
         Channel::read_from(s.as_bytes())
     }
 }
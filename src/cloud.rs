@@ -5,16 +5,17 @@
 // This program is free software; you can redistribute it and/or modify
 // it under the terms of the MIT License and/or Apache 2.0 License.
 
+use std::collections::BTreeMap;
 use std::io::{BufRead, Write};
 
 use quick_xml::events::attributes::Attributes;
 use quick_xml::events::{BytesStart, Event};
 use quick_xml::Error as XmlError;
 use quick_xml::Reader;
-use quick_xml::Writer;
 
+use crate::borrowed::{read_from_reader, CloudRef};
 use crate::error::Error;
-use crate::toxml::ToXml;
+use crate::toxml::{ToXml, XmlWriter};
 
 /// Represents a cloud in an RSS feed.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -39,6 +40,10 @@ pub struct Cloud {
     pub register_procedure: String,
     /// The protocol to register with.
     pub protocol: String,
+    /// The attributes of the cloud element that are not part of RSS, such as `xml:lang`, keyed
+    /// by qualified name.
+    #[cfg_attr(feature = "builders", builder(setter(each = "extra_attr")))]
+    pub extra_attrs: BTreeMap<String, String>,
 }
 
 impl Cloud {
@@ -201,47 +206,56 @@ impl Cloud {
     {
         self.protocol = protocol.into();
     }
+
+    /// Return the attributes of this cloud that are not part of RSS.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use rss::Cloud;
+    ///
+    /// let mut extra_attrs = BTreeMap::new();
+    /// extra_attrs.insert("xml:lang".to_string(), "en".to_string());
+    ///
+    /// let mut cloud = Cloud::default();
+    /// cloud.set_extra_attrs(extra_attrs);
+    /// assert_eq!(cloud.extra_attrs()["xml:lang"], "en");
+    /// ```
+    pub fn extra_attrs(&self) -> &BTreeMap<String, String> {
+        &self.extra_attrs
+    }
+
+    /// Set the attributes of this cloud that are not part of RSS.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use rss::Cloud;
+    ///
+    /// let mut cloud = Cloud::default();
+    /// cloud.set_extra_attrs(BTreeMap::new());
+    /// ```
+    pub fn set_extra_attrs<V>(&mut self, extra_attrs: V)
+    where
+        V: Into<BTreeMap<String, String>>,
+    {
+        self.extra_attrs = extra_attrs.into();
+    }
 }
 
 impl Cloud {
     /// Builds a Cloud from source XML
-    pub fn from_xml<R: BufRead>(
-        reader: &mut Reader<R>,
-        mut atts: Attributes,
-    ) -> Result<Self, Error> {
-        let mut cloud = Cloud::default();
-
-        for att in atts.with_checks(false).flatten() {
-            match att.key {
-                b"domain" => {
-                    cloud.domain = att.unescape_and_decode_value(reader)?;
-                }
-                b"port" => {
-                    cloud.port = att.unescape_and_decode_value(reader)?;
-                }
-                b"path" => {
-                    cloud.path = att.unescape_and_decode_value(reader)?;
-                }
-                b"registerProcedure" => {
-                    cloud.register_procedure = att.unescape_and_decode_value(reader)?;
-                }
-                b"protocol" => {
-                    cloud.protocol = att.unescape_and_decode_value(reader)?;
-                }
-                _ => {}
-            }
-        }
-
-        reader.read_to_end(b"cloud", &mut Vec::new())?;
-
-        Ok(cloud)
+    pub fn from_xml<R: BufRead>(reader: &mut Reader<R>, atts: Attributes) -> Result<Self, Error> {
+        read_from_reader(reader, |parser| parser.read_cloud(atts)).map(CloudRef::into_owned)
     }
 }
 
 impl ToXml for Cloud {
-    fn to_xml<W: Write>(&self, writer: &mut Writer<W>) -> Result<(), XmlError> {
-        let name = b"cloud";
-        let mut element = BytesStart::borrowed(name, name.len());
+    fn to_xml<W: Write>(&self, writer: &mut XmlWriter<W>) -> Result<(), XmlError> {
+        let name = "cloud";
+        let mut element = BytesStart::new(name);
 
         element.push_attribute(("domain", self.domain.as_str()));
         element.push_attribute(("port", self.port.as_str()));
@@ -249,6 +263,12 @@ impl ToXml for Cloud {
         element.push_attribute(("registerProcedure", self.register_procedure.as_str()));
         element.push_attribute(("protocol", self.protocol.as_str()));
 
+        element.extend_attributes(
+            self.extra_attrs
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str())),
+        );
+
         writer.write_event(Event::Empty(element))?;
         Ok(())
     }
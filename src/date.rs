@@ -0,0 +1,305 @@
+// This file is part of rss.
+//
+// Copyright © 2015-2021 The rust-syndication Developers
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the MIT License and/or Apache 2.0 License.
+
+use chrono::{
+    DateTime, FixedOffset, NaiveDate, NaiveDateTime, Offset, SecondsFormat, TimeZone, Utc,
+};
+
+/// Parses a date as it appears in feeds.
+///
+/// Besides RFC 2822 dates, this accepts the variants that are common in practice: named time
+/// zones such as `PST`, missing seconds, weekdays or time zones, two-digit years, full month and
+/// weekday names, and ISO 8601 dates. Dates without a time zone are taken to be in UTC.
+pub(crate) fn parse_date(text: &str) -> Option<DateTime<FixedOffset>> {
+    let text = text.trim();
+    DateTime::parse_from_rfc2822(text)
+        .ok()
+        .or_else(|| parse_rfc822(text))
+        .or_else(|| parse_iso8601(text))
+}
+
+/// Parses a W3C date and time, as used by the Dublin Core and Syndication extensions.
+///
+/// This accepts any ISO 8601 date, as well as the reduced precision forms `YYYY` and `YYYY-MM`,
+/// which are taken to be the start of the year or month in UTC.
+pub(crate) fn parse_w3cdtf(text: &str) -> Option<DateTime<FixedOffset>> {
+    let text = text.trim();
+    if let Some(date) = parse_iso8601(text) {
+        return Some(date);
+    }
+
+    let mut fields = text.splitn(2, '-');
+    let year = fields.next().filter(|year| year.len() == 4)?.parse().ok()?;
+    let month = match fields.next() {
+        Some(month) if month.len() == 2 => month.parse().ok()?,
+        Some(_) => return None,
+        None => 1,
+    };
+    let date = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+    Some(Utc.fix().from_utc_datetime(&date))
+}
+
+/// Formats a date as a W3C date and time.
+pub(crate) fn format_w3cdtf<Tz: TimeZone>(date: &DateTime<Tz>) -> String {
+    date.with_timezone(&date.offset().fix())
+        .to_rfc3339_opts(SecondsFormat::AutoSi, true)
+}
+
+/// Formats a date as an RFC 2822 timestamp.
+pub(crate) fn format_date<Tz: TimeZone>(date: &DateTime<Tz>) -> String {
+    date.with_timezone(&date.offset().fix()).to_rfc2822()
+}
+
+fn parse_rfc822(text: &str) -> Option<DateTime<FixedOffset>> {
+    let mut tokens = text
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|token| !token.is_empty())
+        .peekable();
+
+    // the weekday is optional, and is not checked against the date
+    if tokens.peek()?.parse::<u32>().is_err() && month(tokens.peek()?).is_none() {
+        tokens.next();
+    }
+
+    // most dates are written as `1 Jan 2017`, but `Jan 1 2017` is common too
+    let first = tokens.next()?;
+    let (day, month) = match month(first) {
+        Some(month) => (tokens.next()?.parse().ok()?, month),
+        None => (first.parse().ok()?, month(tokens.next()?)?),
+    };
+    let year = year(tokens.next()?)?;
+    let date = NaiveDate::from_ymd_opt(year, month, day)?;
+
+    let time = match tokens.next() {
+        Some(time) => parse_time(date, time)?,
+        None => date.and_hms_opt(0, 0, 0)?,
+    };
+    let offset = match tokens.next() {
+        Some(zone) => offset(zone)?,
+        None => Utc.fix(),
+    };
+    if tokens.next().is_some() {
+        return None;
+    }
+
+    offset.from_local_datetime(&time).single()
+}
+
+fn parse_time(date: NaiveDate, text: &str) -> Option<NaiveDateTime> {
+    let mut parts = text.split(':');
+    let hour = parts.next()?.parse().ok()?;
+    let minute = parts.next()?.parse().ok()?;
+    let second = match parts.next() {
+        // fractions of a second are ignored
+        Some(second) => second.split('.').next()?.parse().ok()?,
+        None => 0,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+
+    date.and_hms_opt(hour, minute, second)
+}
+
+fn parse_iso8601(text: &str) -> Option<DateTime<FixedOffset>> {
+    if let Ok(date) = DateTime::parse_from_rfc3339(text) {
+        return Some(date);
+    }
+
+    const ZONED: &[&str] = &[
+        "%Y-%m-%dT%H:%M:%S%.f%z",
+        "%Y-%m-%dT%H:%M%z",
+        "%Y-%m-%d %H:%M:%S%.f%z",
+        "%Y-%m-%d %H:%M:%S%.f %z",
+        "%Y-%m-%d %H:%M%z",
+    ];
+    for format in ZONED {
+        if let Ok(date) = DateTime::parse_from_str(text, format) {
+            return Some(date);
+        }
+    }
+
+    const NAIVE: &[&str] = &[
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%d %H:%M",
+    ];
+    let naive = NAIVE
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .or_else(|| {
+            let date = NaiveDate::parse_from_str(text, "%Y-%m-%d").ok()?;
+            date.and_hms_opt(0, 0, 0)
+        })?;
+    Some(Utc.fix().from_utc_datetime(&naive))
+}
+
+/// Returns the number of the month named `name`, which may be abbreviated.
+fn month(name: &str) -> Option<u32> {
+    const MONTHS: [&str; 12] = [
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+    ];
+
+    let name = name.trim_end_matches('.').to_ascii_lowercase();
+    if name.len() < 3 {
+        return None;
+    }
+    MONTHS
+        .iter()
+        .position(|month| name.starts_with(month))
+        .map(|index| index as u32 + 1)
+}
+
+/// Parses a year, taking two-digit years to be between 1950 and 2049 as in RFC 2822.
+fn year(text: &str) -> Option<i32> {
+    let year = text.parse::<i32>().ok()?;
+    match text.len() {
+        2 if year < 50 => Some(year + 2000),
+        2 => Some(year + 1900),
+        3 => Some(year + 1900),
+        4 => Some(year),
+        _ => None,
+    }
+}
+
+/// Parses a numeric time zone such as `+0200` or `+02:00`, or one of the named time zones that
+/// are common in feeds.
+fn offset(zone: &str) -> Option<FixedOffset> {
+    if let Some(sign) = zone.chars().next().filter(|c| *c == '+' || *c == '-') {
+        let digits = zone[1..].replace(':', "");
+        if digits.len() != 4 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let hours = digits[..2].parse::<i32>().ok()?;
+        let minutes = digits[2..].parse::<i32>().ok()?;
+        let seconds = (hours * 60 + minutes) * 60;
+        return FixedOffset::east_opt(if sign == '-' { -seconds } else { seconds });
+    }
+
+    let hours = match zone.to_ascii_uppercase().as_str() {
+        "UT" | "UTC" | "GMT" | "Z" => 0,
+        "EST" => -5,
+        "EDT" => -4,
+        "CST" => -6,
+        "CDT" => -5,
+        "MST" => -7,
+        "MDT" => -6,
+        "PST" => -8,
+        "PDT" => -7,
+        "AKST" => -9,
+        "AKDT" => -8,
+        "HST" => -10,
+        "WET" => 0,
+        "WEST" | "BST" | "CET" => 1,
+        "CEST" | "EET" => 2,
+        "EEST" => 3,
+        "JST" => 9,
+        "AEST" => 10,
+        "AEDT" => 11,
+        _ => return None,
+    };
+    FixedOffset::east_opt(hours * 3600)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(year: i32, month: u32, day: u32, hour: u32, minute: u32, second: u32) -> String {
+        Utc.with_ymd_and_hms(year, month, day, hour, minute, second)
+            .unwrap()
+            .to_rfc3339()
+    }
+
+    fn parse(text: &str) -> Option<String> {
+        parse_date(text).map(|date| date.with_timezone(&Utc).to_rfc3339())
+    }
+
+    #[test]
+    fn test_parse_rfc2822() {
+        let expected = Some(utc(2017, 1, 1, 12, 0, 0));
+        assert_eq!(parse("Sun, 01 Jan 2017 12:00:00 +0000"), expected);
+        assert_eq!(parse("Sun, 1 Jan 2017 12:00:00 GMT"), expected);
+        assert_eq!(parse("Sun, 1 Jan 2017 14:00:00 +0200"), expected);
+    }
+
+    #[test]
+    fn test_parse_variants() {
+        let expected = Some(utc(2017, 1, 1, 12, 0, 0));
+        assert_eq!(parse("Sun, 01 Jan 2017 04:00:00 PST"), expected);
+        assert_eq!(parse("Sun, 01 Jan 2017 08:00:00 edt"), expected);
+        assert_eq!(parse("Sun, 01 Jan 2017 12:00 GMT"), expected);
+        assert_eq!(parse("Sun, 01 Jan 17 12:00:00 GMT"), expected);
+        assert_eq!(parse("01 Jan 2017 12:00:00 +0000"), expected);
+        assert_eq!(parse("Sunday, 01 January 2017 12:00:00 UTC"), expected);
+        assert_eq!(parse("Sun, Jan 01 2017 12:00:00 GMT"), expected);
+        assert_eq!(parse("Sun, 01 Jan 2017 12:00:00"), expected);
+        assert_eq!(parse("Sun, 01 Jan 2017 13:00:00 +01:00"), expected);
+        assert_eq!(parse("Sun, 01 Jan 2017 12:00:00.250 GMT"), expected);
+        assert_eq!(parse("  Sun,01 Jan 2017 12:00:00 GMT "), expected);
+        assert_eq!(
+            parse("01 Jan 99 12:00:00 GMT"),
+            Some(utc(1999, 1, 1, 12, 0, 0))
+        );
+        assert_eq!(parse("01 Jan 2017"), Some(utc(2017, 1, 1, 0, 0, 0)));
+    }
+
+    #[test]
+    fn test_parse_iso8601() {
+        let expected = Some(utc(2017, 1, 1, 12, 0, 0));
+        assert_eq!(parse("2017-01-01T12:00:00Z"), expected);
+        assert_eq!(parse("2017-01-01T14:00:00+02:00"), expected);
+        assert_eq!(
+            parse("2017-01-01T12:00:00.5Z"),
+            parse("2017-01-01T12:00:00.5+00:00")
+        );
+        assert_eq!(parse("2017-01-01T14:00+0200"), expected);
+        assert_eq!(parse("2017-01-01 12:00:00"), expected);
+        assert_eq!(parse("2017-01-01T12:00"), expected);
+        assert_eq!(parse("2017-01-01"), Some(utc(2017, 1, 1, 0, 0, 0)));
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(parse(""), None);
+        assert_eq!(parse("yesterday"), None);
+        assert_eq!(parse("Sun, 32 Jan 2017 12:00:00 GMT"), None);
+        assert_eq!(parse("Sun, 01 Foo 2017 12:00:00 GMT"), None);
+        assert_eq!(parse("Sun, 01 Jan 2017 25:00:00 GMT"), None);
+        assert_eq!(parse("Sun, 01 Jan 2017 12:00:00 XYZ"), None);
+    }
+
+    #[test]
+    fn test_parse_w3cdtf() {
+        let w3cdtf = |text| parse_w3cdtf(text).map(|date| date.with_timezone(&Utc).to_rfc3339());
+        assert_eq!(
+            w3cdtf("2000-01-01T12:00+00:00"),
+            Some(utc(2000, 1, 1, 12, 0, 0))
+        );
+        assert_eq!(
+            w3cdtf("2000-01-01T12:00:00Z"),
+            Some(utc(2000, 1, 1, 12, 0, 0))
+        );
+        assert_eq!(w3cdtf("2000-01-01"), Some(utc(2000, 1, 1, 0, 0, 0)));
+        assert_eq!(w3cdtf("2000-03"), Some(utc(2000, 3, 1, 0, 0, 0)));
+        assert_eq!(w3cdtf("2000"), Some(utc(2000, 1, 1, 0, 0, 0)));
+        assert_eq!(w3cdtf("2000-13"), None);
+        assert_eq!(w3cdtf("200"), None);
+        assert_eq!(w3cdtf("Sun, 01 Jan 2017 12:00:00 GMT"), None);
+    }
+
+    #[test]
+    fn test_format_date() {
+        let date = FixedOffset::east_opt(2 * 3600)
+            .unwrap()
+            .with_ymd_and_hms(2017, 1, 1, 12, 0, 0)
+            .unwrap();
+        assert_eq!(format_date(&date), "Sun, 1 Jan 2017 12:00:00 +0200");
+        assert_eq!(parse_date(&format_date(&date)), Some(date));
+    }
+}
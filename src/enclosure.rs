@@ -5,16 +5,22 @@
 // This program is free software; you can redistribute it and/or modify
 // it under the terms of the MIT License and/or Apache 2.0 License.
 
+use std::collections::BTreeMap;
 use std::io::{BufRead, Write};
 
 use quick_xml::events::attributes::Attributes;
 use quick_xml::events::{BytesStart, Event};
 use quick_xml::Error as XmlError;
 use quick_xml::Reader;
-use quick_xml::Writer;
 
+#[cfg(feature = "mime")]
+use mime::Mime;
+#[cfg(feature = "url")]
+use url::Url;
+
+use crate::borrowed::{read_from_reader, EnclosureRef};
 use crate::error::Error;
-use crate::toxml::ToXml;
+use crate::toxml::{ToXml, XmlWriter};
 
 /// Represents an enclosure in an RSS item.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -35,6 +41,23 @@ pub struct Enclosure {
     pub length: String,
     /// The MIME type of the enclosure.
     pub mime_type: String,
+    /// The attributes of the enclosure element that are not part of RSS, such as `xml:lang`, keyed
+    /// by qualified name.
+    #[cfg_attr(feature = "builders", builder(setter(each = "extra_attr")))]
+    pub extra_attrs: BTreeMap<String, String>,
+}
+
+/// The class of media of an enclosure, from the top-level type of its MIME type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MediaClass {
+    /// An `audio/*` type.
+    Audio,
+    /// A `video/*` type.
+    Video,
+    /// An `image/*` type.
+    Image,
+    /// Any other type, or a missing type.
+    Other,
 }
 
 impl Enclosure {
@@ -133,47 +156,167 @@ impl Enclosure {
     {
         self.mime_type = mime_type.into();
     }
-}
 
-impl Enclosure {
-    /// Builds an Enclosure from source XML
-    pub fn from_xml<R: BufRead>(
-        reader: &mut Reader<R>,
-        mut atts: Attributes,
-    ) -> Result<Self, Error> {
-        let mut enclosure = Enclosure::default();
-
-        for attr in atts.with_checks(false).flatten() {
-            match attr.key {
-                b"url" => {
-                    enclosure.url = attr.unescape_and_decode_value(reader)?;
-                }
-                b"length" => {
-                    enclosure.length = attr.unescape_and_decode_value(reader)?;
-                }
-                b"type" => {
-                    enclosure.mime_type = attr.unescape_and_decode_value(reader)?;
-                }
-                _ => {}
-            }
+    /// Return the content length of this enclosure as a number of bytes.
+    ///
+    /// Returns `None` if the length is not a number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::Enclosure;
+    ///
+    /// let mut enclosure = Enclosure::default();
+    /// enclosure.set_length("1000");
+    /// assert_eq!(enclosure.length_parsed(), Some(1000));
+    /// ```
+    pub fn length_parsed(&self) -> Option<u64> {
+        self.length.trim().parse().ok()
+    }
+
+    /// Set the content length of this enclosure to a number of bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::Enclosure;
+    ///
+    /// let mut enclosure = Enclosure::default();
+    /// enclosure.set_length_bytes(1000);
+    /// assert_eq!(enclosure.length(), "1000");
+    /// ```
+    pub fn set_length_bytes(&mut self, length: u64) {
+        self.length = length.to_string();
+    }
+
+    /// Return the class of media of this enclosure, from the top-level type of its MIME type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::{Enclosure, MediaClass};
+    ///
+    /// let mut enclosure = Enclosure::default();
+    /// enclosure.set_mime_type("Audio/MPEG");
+    /// assert_eq!(enclosure.media_class(), MediaClass::Audio);
+    /// ```
+    pub fn media_class(&self) -> MediaClass {
+        let top_level = self.mime_type.split('/').next().unwrap_or_default();
+        match top_level.trim().to_ascii_lowercase().as_str() {
+            "audio" => MediaClass::Audio,
+            "video" => MediaClass::Video,
+            "image" => MediaClass::Image,
+            _ => MediaClass::Other,
+        }
+    }
+
+    /// Return the MIME type of this enclosure as a media type.
+    ///
+    /// Returns `None` if the MIME type is not valid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::Enclosure;
+    ///
+    /// let mut enclosure = Enclosure::default();
+    /// enclosure.set_mime_type("audio/mpeg");
+    /// let mime_type = enclosure.mime_type_parsed().unwrap();
+    /// assert_eq!(mime_type.type_(), mime::AUDIO);
+    /// assert_eq!(mime_type.subtype(), "mpeg");
+    /// ```
+    #[cfg(feature = "mime")]
+    pub fn mime_type_parsed(&self) -> Option<Mime> {
+        self.mime_type.trim().parse().ok()
+    }
+
+    /// Return the URL of this enclosure, resolved against `base`, which is usually the link of
+    /// the channel.
+    ///
+    /// Returns `None` if the URL is relative and `base` is not a valid URL, or if the URL is not
+    /// valid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::Enclosure;
+    ///
+    /// let mut enclosure = Enclosure::default();
+    /// enclosure.set_url("/episodes/1.mp3");
+    /// let url = enclosure.url_resolved("http://example.com/podcast/").unwrap();
+    /// assert_eq!(url.as_str(), "http://example.com/episodes/1.mp3");
+    /// ```
+    #[cfg(feature = "url")]
+    pub fn url_resolved(&self, base: &str) -> Option<Url> {
+        let url = self.url.trim();
+        match Url::parse(url) {
+            Ok(url) => Some(url),
+            Err(url::ParseError::RelativeUrlWithoutBase) => Url::parse(base).ok()?.join(url).ok(),
+            Err(_) => None,
         }
+    }
+
+    /// Return the attributes of this enclosure that are not part of RSS.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use rss::Enclosure;
+    ///
+    /// let mut extra_attrs = BTreeMap::new();
+    /// extra_attrs.insert("xml:lang".to_string(), "en".to_string());
+    ///
+    /// let mut enclosure = Enclosure::default();
+    /// enclosure.set_extra_attrs(extra_attrs);
+    /// assert_eq!(enclosure.extra_attrs()["xml:lang"], "en");
+    /// ```
+    pub fn extra_attrs(&self) -> &BTreeMap<String, String> {
+        &self.extra_attrs
+    }
 
-        reader.read_to_end(b"enclosure", &mut Vec::new())?;
+    /// Set the attributes of this enclosure that are not part of RSS.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use rss::Enclosure;
+    ///
+    /// let mut enclosure = Enclosure::default();
+    /// enclosure.set_extra_attrs(BTreeMap::new());
+    /// ```
+    pub fn set_extra_attrs<V>(&mut self, extra_attrs: V)
+    where
+        V: Into<BTreeMap<String, String>>,
+    {
+        self.extra_attrs = extra_attrs.into();
+    }
+}
 
-        Ok(enclosure)
+impl Enclosure {
+    /// Builds an Enclosure from source XML
+    pub fn from_xml<R: BufRead>(reader: &mut Reader<R>, atts: Attributes) -> Result<Self, Error> {
+        read_from_reader(reader, |parser| parser.read_enclosure(atts)).map(EnclosureRef::into_owned)
     }
 }
 
 impl ToXml for Enclosure {
-    fn to_xml<W: Write>(&self, writer: &mut Writer<W>) -> Result<(), XmlError> {
-        let name = b"enclosure";
+    fn to_xml<W: Write>(&self, writer: &mut XmlWriter<W>) -> Result<(), XmlError> {
+        let name = "enclosure";
 
-        let mut element = BytesStart::borrowed(name, name.len());
+        let mut element = BytesStart::new(name);
 
         element.push_attribute(("url", self.url.as_str()));
         element.push_attribute(("length", self.length.as_str()));
         element.push_attribute(("type", self.mime_type.as_str()));
 
+        element.extend_attributes(
+            self.extra_attrs
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str())),
+        );
+
         writer.write_event(Event::Empty(element))?;
         Ok(())
     }
@@ -181,6 +324,37 @@ impl ToXml for Enclosure {
 
 #[cfg(feature = "builders")]
 impl EnclosureBuilder {
+    /// Set the content length of the enclosure to a number of bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::EnclosureBuilder;
+    ///
+    /// let enclosure = EnclosureBuilder::default().length_bytes(1000).build();
+    /// assert_eq!(enclosure.length(), "1000");
+    /// ```
+    pub fn length_bytes(&mut self, length: u64) -> &mut Self {
+        self.length(length.to_string())
+    }
+
+    /// Set the MIME type of the enclosure to a media type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::EnclosureBuilder;
+    ///
+    /// let enclosure = EnclosureBuilder::default()
+    ///     .media_type(&mime::IMAGE_PNG)
+    ///     .build();
+    /// assert_eq!(enclosure.mime_type(), "image/png");
+    /// ```
+    #[cfg(feature = "mime")]
+    pub fn media_type(&mut self, mime_type: &Mime) -> &mut Self {
+        self.mime_type(mime_type.to_string())
+    }
+
     /// Builds a new `Enclosure`.
     pub fn build(&self) -> Enclosure {
         self.build_impl().unwrap()
@@ -0,0 +1,344 @@
+// This file is part of rss.
+//
+// Copyright © 2015-2021 The rust-syndication Developers
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the MIT License and/or Apache 2.0 License.
+
+#[cfg(feature = "async")]
+use std::future::poll_fn;
+use std::io::{self, BufRead, Read};
+use std::ops::Range;
+#[cfg(feature = "async")]
+use std::pin::Pin;
+use std::str;
+#[cfg(feature = "async")]
+use std::task::{Context, Poll};
+
+use encoding_rs::{Decoder, Encoding, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252};
+#[cfg(feature = "async")]
+use tokio::io::{AsyncBufRead, AsyncRead, ReadBuf};
+
+/// The number of bytes at the start of the input that are used to detect its encoding.
+const PREFIX_LEN: usize = 4096;
+
+/// The encoding of the input and the problems that were found while detecting it.
+pub(crate) struct Detection {
+    pub encoding: &'static Encoding,
+    /// The length of the byte order mark.
+    bom_len: usize,
+    pub warnings: Vec<String>,
+}
+
+/// Detects the encoding of a document that starts with `prefix`.
+///
+/// The first of these that is present and known is used:
+///
+/// 1. the byte order mark,
+/// 2. the charset `hint`,
+/// 3. the encoding in the XML declaration,
+/// 4. UTF-8 if `prefix` is valid UTF-8, and Windows-1252 otherwise.
+pub(crate) fn detect(prefix: &[u8], hint: Option<&str>) -> Detection {
+    let mut warnings = Vec::new();
+    let declared = declared_encoding(prefix, &mut warnings);
+
+    let (encoding, bom_len) = if let Some((encoding, bom_len)) = Encoding::for_bom(prefix) {
+        (encoding, bom_len)
+    } else if let Some(encoding) = hint.and_then(|hint| charset_hint(hint, &mut warnings)) {
+        (encoding, 0)
+    } else if let Some(encoding) = declared {
+        (encoding, 0)
+    } else if is_utf8(prefix) {
+        (UTF_8, 0)
+    } else {
+        (WINDOWS_1252, 0)
+    };
+
+    if let Some(declared) = declared {
+        if declared != encoding {
+            warnings.push(format!(
+                "the document declares the encoding {} but is read as {}",
+                declared.name(),
+                encoding.name()
+            ));
+        }
+    }
+
+    Detection {
+        encoding,
+        bom_len,
+        warnings,
+    }
+}
+
+fn charset_hint(hint: &str, warnings: &mut Vec<String>) -> Option<&'static Encoding> {
+    let encoding = Encoding::for_label(hint.trim().as_bytes());
+    if encoding.is_none() {
+        warnings.push(format!("unknown charset `{}`", hint));
+    }
+    encoding
+}
+
+/// Returns the encoding named in the XML declaration at the start of `prefix`.
+///
+/// A declaration encoded in UTF-16 without a byte order mark is recognised by its first
+/// characters.
+fn declared_encoding(prefix: &[u8], warnings: &mut Vec<String>) -> Option<&'static Encoding> {
+    if prefix.starts_with(b"<\0?\0") {
+        return Some(UTF_16LE);
+    }
+    if prefix.starts_with(b"\0<\0?") {
+        return Some(UTF_16BE);
+    }
+
+    let label = &prefix[declared_encoding_range(prefix)?];
+    match Encoding::for_label(label) {
+        // the declaration could be read as ASCII, so the document cannot be UTF-16
+        Some(encoding) if encoding == UTF_16LE || encoding == UTF_16BE => Some(UTF_8),
+        Some(encoding) => Some(encoding),
+        None => {
+            warnings.push(format!(
+                "unknown encoding `{}` in the XML declaration",
+                String::from_utf8_lossy(label)
+            ));
+            None
+        }
+    }
+}
+
+/// Returns the range of the value of the `encoding` pseudo-attribute of the XML declaration at
+/// the start of `input`.
+fn declared_encoding_range(input: &[u8]) -> Option<Range<usize>> {
+    let bom_len = if input.starts_with(b"\xEF\xBB\xBF") {
+        3
+    } else {
+        0
+    };
+    let input = &input[bom_len..];
+    if !input.starts_with(b"<?xml") || !input.get(5)?.is_ascii_whitespace() {
+        return None;
+    }
+
+    let end = input.windows(2).position(|w| w == b"?>")?;
+    let decl = &input[..end];
+    let mut pos = decl.windows(8).position(|w| w == b"encoding")? + 8;
+
+    let skip_whitespace = |mut pos: usize| {
+        while decl.get(pos).is_some_and(u8::is_ascii_whitespace) {
+            pos += 1;
+        }
+        pos
+    };
+
+    pos = skip_whitespace(pos);
+    if decl.get(pos) != Some(&b'=') {
+        return None;
+    }
+    pos = skip_whitespace(pos + 1);
+
+    let quote = *decl.get(pos).filter(|&&b| b == b'"' || b == b'\'')?;
+    let start = pos + 1;
+    let len = decl[start..].iter().position(|&b| b == quote)?;
+    Some(bom_len + start..bom_len + start + len)
+}
+
+/// Whether `input` is valid UTF-8, ignoring a sequence that is cut off at its end.
+fn is_utf8(input: &[u8]) -> bool {
+    match str::from_utf8(input) {
+        Ok(_) => true,
+        Err(err) => err.error_len().is_none(),
+    }
+}
+
+/// A reader that converts its input to UTF-8.
+///
+/// The encoding in the XML declaration is replaced with UTF-8, so that the XML parser does not
+/// decode the converted input again, and the byte order mark is removed. Sequences that are not
+/// valid in the encoding are replaced with U+FFFD.
+pub(crate) struct Decode<R> {
+    inner: R,
+    decoder: Decoder,
+    /// Converted input that was not consumed yet.
+    buf: Vec<u8>,
+    pos: usize,
+    eof: bool,
+}
+
+impl<R: BufRead> Decode<R> {
+    /// Detects the encoding of `inner` from its first few kilobytes and the charset `hint`, and
+    /// returns a reader that converts it to UTF-8.
+    pub fn new(mut inner: R, hint: Option<&str>) -> io::Result<(Self, Detection)> {
+        let mut prefix = Vec::new();
+        let mut eof = false;
+
+        while prefix.len() < PREFIX_LEN {
+            let available = inner.fill_buf()?;
+            if available.is_empty() {
+                eof = true;
+                break;
+            }
+            let len = available.len().min(PREFIX_LEN - prefix.len());
+            prefix.extend_from_slice(&available[..len]);
+            inner.consume(len);
+        }
+
+        Ok(Decode::with_prefix(inner, &prefix, eof, hint))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<R: AsyncBufRead + Unpin> Decode<R> {
+    /// Detects the encoding of the asynchronous reader `inner` the way `new` does, but only
+    /// waits for the start of the input, so that a document that arrives slowly can be parsed
+    /// as it arrives.
+    pub async fn new_async(mut inner: R, hint: Option<&str>) -> io::Result<(Self, Detection)> {
+        let mut prefix = Vec::new();
+        let mut eof = false;
+
+        while prefix.len() < PREFIX_LEN && !eof {
+            let more = poll_fn(|cx| match Pin::new(&mut inner).poll_fill_buf(cx) {
+                Poll::Ready(Ok(available)) => {
+                    eof = available.is_empty();
+                    let len = available.len().min(PREFIX_LEN - prefix.len());
+                    prefix.extend_from_slice(&available[..len]);
+                    Pin::new(&mut inner).consume(len);
+                    Poll::Ready(Ok(true))
+                }
+                Poll::Ready(Err(err)) => Poll::Ready(Err(err)),
+                // the rest of the input that is available already is used
+                Poll::Pending if !prefix.is_empty() => Poll::Ready(Ok(false)),
+                Poll::Pending => Poll::Pending,
+            })
+            .await?;
+            if !more {
+                break;
+            }
+        }
+
+        Ok(Decode::with_prefix(inner, &prefix, eof, hint))
+    }
+}
+
+impl<R> Decode<R> {
+    /// Returns a reader for the rest of `inner` after `prefix`, which was read from it already.
+    fn with_prefix(inner: R, prefix: &[u8], eof: bool, hint: Option<&str>) -> (Self, Detection) {
+        let detection = detect(prefix, hint);
+        // UTF-8 is decoded as well, which replaces the sequences that are not valid
+        let mut decode = Decode {
+            inner,
+            decoder: detection.encoding.new_decoder_without_bom_handling(),
+            buf: Vec::new(),
+            pos: 0,
+            eof,
+        };
+        decode_to(
+            &mut decode.decoder,
+            &prefix[detection.bom_len..],
+            &mut decode.buf,
+            eof,
+        );
+
+        if let Some(range) = declared_encoding_range(&decode.buf) {
+            decode.buf.splice(range, b"UTF-8".iter().cloned());
+        }
+
+        (decode, detection)
+    }
+
+    /// Returns the converted input that was not consumed yet.
+    #[cfg(feature = "async")]
+    pub fn buffered(&self) -> &[u8] {
+        &self.buf[self.pos..]
+    }
+}
+
+/// Converts `input` and appends it to `buf`, returning the number of bytes of `input` that were
+/// read.
+fn decode_to(decoder: &mut Decoder, input: &[u8], buf: &mut Vec<u8>, last: bool) -> usize {
+    let start = buf.len();
+    let max_len = decoder
+        .max_utf8_buffer_length(input.len())
+        .unwrap_or(input.len() * 3 + 16);
+    buf.resize(start + max_len, 0);
+    let (_, read, written, _) = decoder.decode_to_utf8(input, &mut buf[start..], last);
+    buf.truncate(start + written);
+    read
+}
+
+impl<R: BufRead> Read for Decode<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.consume(len);
+        Ok(len)
+    }
+}
+
+impl<R: BufRead> BufRead for Decode<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.pos == self.buf.len() {
+            self.buf.clear();
+            self.pos = 0;
+            while self.buf.is_empty() && !self.eof {
+                let input = self.inner.fill_buf()?;
+                self.eof = input.is_empty();
+                let read = decode_to(&mut self.decoder, input, &mut self.buf, self.eof);
+                self.inner.consume(read);
+            }
+        }
+
+        Ok(&self.buf[self.pos..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.buf.len());
+    }
+}
+
+#[cfg(feature = "async")]
+impl<R: AsyncBufRead + Unpin> AsyncRead for Decode<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let available = match self.as_mut().poll_fill_buf(cx) {
+            Poll::Ready(Ok(available)) => available,
+            Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+            Poll::Pending => return Poll::Pending,
+        };
+        let len = available.len().min(buf.remaining());
+        buf.put_slice(&available[..len]);
+        self.consume(len);
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "async")]
+impl<R: AsyncBufRead + Unpin> AsyncBufRead for Decode<R> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+        if this.pos == this.buf.len() {
+            this.buf.clear();
+            this.pos = 0;
+            while this.buf.is_empty() && !this.eof {
+                let input = match Pin::new(&mut this.inner).poll_fill_buf(cx) {
+                    Poll::Ready(Ok(input)) => input,
+                    Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+                    Poll::Pending => return Poll::Pending,
+                };
+                this.eof = input.is_empty();
+                let read = decode_to(&mut this.decoder, input, &mut this.buf, this.eof);
+                Pin::new(&mut this.inner).consume(read);
+            }
+        }
+
+        Poll::Ready(Ok(&this.buf[this.pos..]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.get_mut();
+        this.pos = (this.pos + amt).min(this.buf.len());
+    }
+}
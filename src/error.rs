@@ -11,8 +11,10 @@ use std::str::Utf8Error;
 
 use quick_xml::Error as XmlError;
 
+use crate::writer::RssVersion;
+
 #[derive(Debug)]
-/// Errors that occur during parsing.
+/// Errors that occur during parsing and writing.
 pub enum Error {
     /// An error while converting bytes to UTF8.
     Utf8(Utf8Error),
@@ -22,6 +24,111 @@ pub enum Error {
     InvalidStartTag,
     /// The end of the input was reached without finding a complete channel element.
     Eof,
+    /// The input exceeded one of the configured `Limits`.
+    LimitExceeded {
+        /// The kind of limit that was exceeded.
+        kind: LimitKind,
+        /// The value of the limit.
+        limit: usize,
+    },
+    /// The channel cannot be written as the requested version of RSS.
+    Version {
+        /// The version the channel was written as.
+        version: RssVersion,
+        /// The reason the channel cannot be written as that version.
+        reason: String,
+    },
+    /// The channel cannot be written in the requested encoding, because it is unknown or is one
+    /// that can only be read, such as UTF-16.
+    Encoding(String),
+    /// A namespace was registered with a `ChannelWriter` after the first item was written.
+    HeaderWritten,
+}
+
+/// An error that occurred while reading a channel, along with the location in the input at
+/// which it occurred.
+#[derive(Debug)]
+pub struct ParseError {
+    /// The error that occurred.
+    pub error: Error,
+    /// The location in the input at which it occurred.
+    pub location: Location,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at {}", self.error, self.location)
+    }
+}
+
+impl StdError for ParseError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.error.source()
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(err: ParseError) -> Error {
+        err.error
+    }
+}
+
+/// A location in the input.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Location {
+    /// The byte offset in the input.
+    pub offset: usize,
+    /// The line number, starting at 1.
+    pub line: usize,
+    /// The column in bytes, starting at 1.
+    pub column: usize,
+    /// The path of the element, for example `rss/channel/item[12]/enclosure`.
+    ///
+    /// The index of an element is only included when it is not the first element with that
+    /// name in its parent.
+    pub path: String,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if !self.path.is_empty() {
+            write!(f, "{}, ", self.path)?;
+        }
+        if self.line > 0 {
+            write!(f, "line {}, column {}, ", self.line, self.column)?;
+        }
+        write!(f, "byte {}", self.offset)
+    }
+}
+
+/// The kinds of `Limits` that can be exceeded while reading a channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitKind {
+    /// The nesting depth of the elements.
+    Depth,
+    /// The number of items in the channel.
+    Items,
+    /// The length in bytes of a text node, comment or attribute value.
+    TextLength,
+    /// The number of attributes of an element.
+    Attributes,
+    /// The number of elements nested in a single extension element.
+    ExtensionChildren,
+    /// The length in bytes of the input.
+    InputLength,
+}
+
+impl fmt::Display for LimitKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            LimitKind::Depth => "nesting depth",
+            LimitKind::Items => "number of items",
+            LimitKind::TextLength => "text length",
+            LimitKind::Attributes => "number of attributes",
+            LimitKind::ExtensionChildren => "number of extension children",
+            LimitKind::InputLength => "input length",
+        })
+    }
 }
 
 impl StdError for Error {
@@ -29,7 +136,12 @@ impl StdError for Error {
         match *self {
             Error::Utf8(ref err) => Some(err),
             Error::Xml(ref err) => Some(err),
-            Error::InvalidStartTag | Error::Eof => None,
+            Error::InvalidStartTag
+            | Error::Eof
+            | Error::LimitExceeded { .. }
+            | Error::Version { .. }
+            | Error::Encoding(_)
+            | Error::HeaderWritten => None,
         }
     }
 }
@@ -41,6 +153,18 @@ impl fmt::Display for Error {
             Error::Xml(ref err) => fmt::Display::fmt(err, f),
             Error::InvalidStartTag => write!(f, "the input did not begin with an rss tag"),
             Error::Eof => write!(f, "reached end of input without finding a complete channel"),
+            Error::LimitExceeded { kind, limit } => {
+                write!(f, "the {} exceeds the limit of {}", kind, limit)
+            }
+            Error::Version {
+                version,
+                ref reason,
+            } => write!(f, "cannot write RSS {}: {}", version, reason),
+            Error::Encoding(ref label) => write!(f, "cannot write the encoding `{}`", label),
+            Error::HeaderWritten => write!(
+                f,
+                "namespaces cannot be registered after the first item is written"
+            ),
         }
     }
 }
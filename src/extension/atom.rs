@@ -11,10 +11,9 @@ use std::io::Write;
 pub use atom_syndication::Link;
 use quick_xml::events::{BytesStart, Event};
 use quick_xml::Error as XmlError;
-use quick_xml::Writer;
 
 use crate::extension::Extension;
-use crate::toxml::ToXml;
+use crate::toxml::XmlWriter;
 
 /// The Atom XML namespace.
 pub const NAMESPACE: &str = "http://www.w3.org/2005/Atom";
@@ -78,11 +77,17 @@ impl AtomExtension {
     }
 }
 
-impl ToXml for AtomExtension {
-    fn to_xml<W: Write>(&self, writer: &mut Writer<W>) -> Result<(), XmlError> {
+impl AtomExtension {
+    /// Serialises this extension to the nominated writer, using the prefix bound to the Atom
+    /// namespace in `namespaces`, or `atom` if there is none.
+    pub(crate) fn to_xml<W: Write>(
+        &self,
+        namespaces: &BTreeMap<String, String>,
+        writer: &mut XmlWriter<W>,
+    ) -> Result<(), XmlError> {
+        let name = format!("{}:link", prefix(namespaces));
         for link in &self.links {
-            let name = b"link";
-            let mut element = BytesStart::borrowed(name, name.len());
+            let mut element = BytesStart::new(name.as_str());
             element.push_attribute(("href", &*link.href));
             element.push_attribute(("rel", &*link.rel));
 
@@ -108,6 +113,14 @@ impl ToXml for AtomExtension {
     }
 }
 
+/// Returns the prefix bound to the Atom namespace in `namespaces`, or `atom` if there is none.
+pub(crate) fn prefix(namespaces: &BTreeMap<String, String>) -> &str {
+    namespaces
+        .iter()
+        .find(|(prefix, namespace)| !prefix.is_empty() && *namespace == NAMESPACE)
+        .map_or("atom", |(prefix, _)| prefix.as_str())
+}
+
 #[cfg(feature = "builders")]
 impl AtomExtensionBuilder {
     /// Builds a new `AtomExtension`.
@@ -0,0 +1,165 @@
+// This file is part of rss.
+//
+// Copyright © 2015-2021 The rust-syndication Developers
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the MIT License and/or Apache 2.0 License.
+
+use std::time::Duration;
+
+/// The longest duration that is parsed, which keeps fractional seconds well within the range of a
+/// `Duration`.
+const MAX_SECONDS: f64 = 1e12;
+
+/// Parses the duration of a podcast episode.
+///
+/// Besides the `HH:MM:SS`, `H:MM:SS`, `MM:SS` and `M:SS` formats and a plain number of seconds,
+/// this accepts the variants that are common in practice: fractional seconds, fields that are out
+/// of range such as `90:00`, and units such as `1h2m` or `1 hr 30 min`.
+pub(crate) fn parse_duration(text: &str) -> Option<Duration> {
+    let text = text.trim();
+    if text.contains(':') {
+        parse_clock(text)
+    } else if text.bytes().any(|b| b.is_ascii_alphabetic()) {
+        parse_units(text)
+    } else {
+        seconds(text)
+    }
+}
+
+/// Formats a duration as `HH:MM:SS`, dropping fractions of a second.
+pub(crate) fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!(
+        "{:02}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+fn parse_clock(text: &str) -> Option<Duration> {
+    let fields = text.split(':').map(str::trim).collect::<Vec<_>>();
+    if fields.len() > 3 {
+        return None;
+    }
+
+    // only the seconds may have a fraction
+    let (last, rest) = fields.split_last()?;
+    let mut minutes = 0u64;
+    for field in rest {
+        if field.is_empty() || !field.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        minutes = minutes.checked_mul(60)?.checked_add(field.parse().ok()?)?;
+    }
+
+    Duration::from_secs(minutes.checked_mul(60)?).checked_add(seconds(last)?)
+}
+
+fn parse_units(text: &str) -> Option<Duration> {
+    let mut total = Duration::from_secs(0);
+    let mut rest = text;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+        if rest.is_empty() {
+            return Some(total);
+        }
+
+        let end = rest
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(rest.len());
+        let amount = seconds(&rest[..end])?;
+        rest = rest[end..].trim_start();
+
+        let end = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let factor = match rest[..end].to_ascii_lowercase().as_str() {
+            "h" | "hr" | "hrs" | "hour" | "hours" => 3600,
+            "m" | "min" | "mins" | "minute" | "minutes" => 60,
+            "s" | "sec" | "secs" | "second" | "seconds" => 1,
+            _ => return None,
+        };
+        total = total.checked_add(amount.checked_mul(factor)?)?;
+        rest = &rest[end..];
+    }
+}
+
+/// Parses a number of seconds, which may have a fraction.
+fn seconds(text: &str) -> Option<Duration> {
+    if text.is_empty() || !text.bytes().all(|b| b.is_ascii_digit() || b == b'.') {
+        return None;
+    }
+    if let Ok(seconds) = text.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let seconds = text.parse::<f64>().ok()?;
+    if seconds > MAX_SECONDS {
+        return None;
+    }
+    Some(Duration::from_secs_f64(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(seconds: u64) -> Option<Duration> {
+        Some(Duration::from_secs(seconds))
+    }
+
+    #[test]
+    fn test_parse_clock() {
+        assert_eq!(parse_duration("01:02:03"), secs(3723));
+        assert_eq!(parse_duration("1:02:03"), secs(3723));
+        assert_eq!(parse_duration("62:03"), secs(3723));
+        assert_eq!(parse_duration("2:03"), secs(123));
+        assert_eq!(parse_duration(" 0:90 "), secs(90));
+        assert_eq!(
+            parse_duration("1:02:03.5"),
+            Some(Duration::from_millis(3_723_500))
+        );
+    }
+
+    #[test]
+    fn test_parse_seconds() {
+        assert_eq!(parse_duration("3723"), secs(3723));
+        assert_eq!(
+            parse_duration("3723.25"),
+            Some(Duration::from_millis(3_723_250))
+        );
+    }
+
+    #[test]
+    fn test_parse_units() {
+        assert_eq!(parse_duration("1h2m"), secs(3720));
+        assert_eq!(parse_duration("1h 2m 3s"), secs(3723));
+        assert_eq!(parse_duration("1 hr 30 min"), secs(5400));
+        assert_eq!(parse_duration("45 Minutes"), secs(2700));
+        assert_eq!(parse_duration("1.5h"), secs(5400));
+        assert_eq!(parse_duration("1h, 2m"), secs(3720));
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("unknown"), None);
+        assert_eq!(parse_duration("-5"), None);
+        assert_eq!(parse_duration("1e5"), None);
+        assert_eq!(parse_duration("1:2:3:4"), None);
+        assert_eq!(parse_duration("1.5:00"), None);
+        assert_eq!(parse_duration("1::00"), None);
+        assert_eq!(parse_duration("5 days"), None);
+        assert_eq!(parse_duration("h"), None);
+        assert_eq!(parse_duration("99999999999999999999"), None);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_secs(3723)), "01:02:03");
+        assert_eq!(format_duration(Duration::from_millis(59_900)), "00:00:59");
+        assert_eq!(format_duration(Duration::from_secs(360_000)), "100:00:00");
+    }
+}
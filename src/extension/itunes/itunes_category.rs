@@ -9,9 +9,8 @@ use std::io::Write;
 
 use quick_xml::events::{BytesEnd, BytesStart, Event};
 use quick_xml::Error as XmlError;
-use quick_xml::Writer;
 
-use crate::toxml::ToXml;
+use crate::toxml::{ToXml, XmlWriter};
 
 /// A category for an iTunes podcast.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -100,9 +99,9 @@ impl ITunesCategory {
 }
 
 impl ToXml for ITunesCategory {
-    fn to_xml<W: Write>(&self, writer: &mut Writer<W>) -> Result<(), XmlError> {
-        let name = b"itunes:category";
-        let mut element = BytesStart::borrowed(name, name.len());
+    fn to_xml<W: Write>(&self, writer: &mut XmlWriter<W>) -> Result<(), XmlError> {
+        let name = "itunes:category";
+        let mut element = BytesStart::new(name);
         element.push_attribute(("text", &*self.text));
         writer.write_event(Event::Start(element))?;
 
@@ -110,7 +109,7 @@ impl ToXml for ITunesCategory {
             subcategory.to_xml(writer)?;
         }
 
-        writer.write_event(Event::End(BytesEnd::borrowed(name)))?;
+        writer.write_event(Event::End(BytesEnd::new(name)))?;
         Ok(())
     }
 }
@@ -10,13 +10,13 @@ use std::io::Write;
 
 use quick_xml::events::{BytesStart, Event};
 use quick_xml::Error as XmlError;
-use quick_xml::Writer;
 
-use super::{parse_categories, parse_image, parse_owner};
-use crate::extension::itunes::{ITunesCategory, ITunesOwner};
+use super::itunes_values::{format_flag, parse_flag};
+use super::{parse_categories, parse_image, parse_owner, NAMESPACE};
+use crate::extension::itunes::{Explicit, ITunesCategory, ITunesOwner, ShowType};
 use crate::extension::util::remove_extension_value;
 use crate::extension::Extension;
-use crate::toxml::{ToXml, WriterExt};
+use crate::toxml::{ToXml, WriterExt, XmlWriter};
 
 /// An iTunes channel element extension.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -134,6 +134,44 @@ impl ITunesChannelExtension {
         self.block = block.into();
     }
 
+    /// Return whether this podcast is blocked from appearing in the iTunes Store.
+    ///
+    /// A value of `Yes` or `True` sets the flag, and any other value clears it. Returns `None` if
+    /// there is no value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::extension::itunes::ITunesChannelExtension;
+    ///
+    /// let mut extension = ITunesChannelExtension::default();
+    /// extension.set_block("Yes".to_string());
+    /// assert_eq!(extension.block_parsed(), Some(true));
+    /// ```
+    pub fn block_parsed(&self) -> Option<bool> {
+        self.block.as_deref().map(parse_flag)
+    }
+
+    /// Set whether this podcast is blocked from appearing in the iTunes Store.
+    ///
+    /// The flag is written as `Yes` or `No`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::extension::itunes::ITunesChannelExtension;
+    ///
+    /// let mut extension = ITunesChannelExtension::default();
+    /// extension.set_block_from(true);
+    /// assert_eq!(extension.block(), Some("Yes"));
+    /// ```
+    pub fn set_block_from<V>(&mut self, block: V)
+    where
+        V: Into<Option<bool>>,
+    {
+        self.block = block.into().map(format_flag);
+    }
+
     /// Return the iTunes categories that the podcast belongs to.
     ///
     /// # Examples
@@ -243,6 +281,47 @@ impl ITunesChannelExtension {
         self.explicit = explicit.into();
     }
 
+    /// Return whether this podcast contains explicit content.
+    ///
+    /// A value of `Yes`, `Explicit` or `True` is read as `Explicit::Yes`, a value of `No` or
+    /// `False` as `Explicit::No`, and a value of `Clean` as `Explicit::Clean`. Returns `None` if
+    /// there is no value or it is not one of these.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::extension::itunes::{Explicit, ITunesChannelExtension};
+    ///
+    /// let mut extension = ITunesChannelExtension::default();
+    /// extension.set_explicit("Yes".to_string());
+    /// assert_eq!(extension.explicit_parsed(), Some(Explicit::Yes));
+    /// ```
+    pub fn explicit_parsed(&self) -> Option<Explicit> {
+        self.explicit.as_deref().and_then(Explicit::parse)
+    }
+
+    /// Set whether this podcast contains explicit content.
+    ///
+    /// The value is written as `true`, `false` or `clean`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::extension::itunes::{Explicit, ITunesChannelExtension};
+    ///
+    /// let mut extension = ITunesChannelExtension::default();
+    /// extension.set_explicit_from(Explicit::No);
+    /// assert_eq!(extension.explicit(), Some("false"));
+    /// ```
+    pub fn set_explicit_from<V>(&mut self, explicit: V)
+    where
+        V: Into<Option<Explicit>>,
+    {
+        self.explicit = explicit
+            .into()
+            .map(|explicit| explicit.as_str().to_string());
+    }
+
     /// Return whether the podcast is complete and no new episodes will be posted.
     ///
     /// A value of `Yes` indicates that the podcast is complete.
@@ -279,6 +358,44 @@ impl ITunesChannelExtension {
         self.complete = complete.into();
     }
 
+    /// Return whether this podcast is complete and will not be updated.
+    ///
+    /// A value of `Yes` or `True` sets the flag, and any other value clears it. Returns `None` if
+    /// there is no value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::extension::itunes::ITunesChannelExtension;
+    ///
+    /// let mut extension = ITunesChannelExtension::default();
+    /// extension.set_complete("Yes".to_string());
+    /// assert_eq!(extension.complete_parsed(), Some(true));
+    /// ```
+    pub fn complete_parsed(&self) -> Option<bool> {
+        self.complete.as_deref().map(parse_flag)
+    }
+
+    /// Set whether this podcast is complete and will not be updated.
+    ///
+    /// The flag is written as `Yes` or `No`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::extension::itunes::ITunesChannelExtension;
+    ///
+    /// let mut extension = ITunesChannelExtension::default();
+    /// extension.set_complete_from(true);
+    /// assert_eq!(extension.complete(), Some("Yes"));
+    /// ```
+    pub fn set_complete_from<V>(&mut self, complete: V)
+    where
+        V: Into<Option<bool>>,
+    {
+        self.complete = complete.into().map(format_flag);
+    }
+
     /// Return the new feed URL for this podcast.
     ///
     /// # Examples
@@ -478,6 +595,41 @@ impl ITunesChannelExtension {
     {
         self.r#type = t.into();
     }
+
+    /// Return the type of this podcast.
+    ///
+    /// Returns `None` if there is no type or it is neither "episodic" nor "serial".
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::extension::itunes::{ITunesChannelExtension, ShowType};
+    ///
+    /// let mut extension = ITunesChannelExtension::default();
+    /// extension.set_type("serial".to_string());
+    /// assert_eq!(extension.type_parsed(), Some(ShowType::Serial));
+    /// ```
+    pub fn type_parsed(&self) -> Option<ShowType> {
+        self.r#type.as_deref().and_then(ShowType::parse)
+    }
+
+    /// Set the type of this podcast.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::extension::itunes::{ITunesChannelExtension, ShowType};
+    ///
+    /// let mut extension = ITunesChannelExtension::default();
+    /// extension.set_type_from(ShowType::Episodic);
+    /// assert_eq!(extension.r#type(), Some("episodic"));
+    /// ```
+    pub fn set_type_from<V>(&mut self, t: V)
+    where
+        V: Into<Option<ShowType>>,
+    {
+        self.r#type = t.into().map(|t| t.as_str().to_string());
+    }
 }
 
 impl ITunesChannelExtension {
@@ -501,34 +653,34 @@ impl ITunesChannelExtension {
 }
 
 impl ToXml for ITunesChannelExtension {
-    fn to_xml<W: Write>(&self, writer: &mut Writer<W>) -> Result<(), XmlError> {
+    fn to_xml<W: Write>(&self, writer: &mut XmlWriter<W>) -> Result<(), XmlError> {
         if let Some(author) = self.author.as_ref() {
-            writer.write_text_element(b"itunes:author", author)?;
+            writer.write_text_element("itunes:author", author)?;
         }
 
         if let Some(block) = self.block.as_ref() {
-            writer.write_text_element(b"itunes:block", block)?;
+            writer.write_text_element("itunes:block", block)?;
         }
 
         writer.write_objects(&self.categories)?;
 
         if let Some(image) = self.image.as_ref() {
-            let name = b"itunes:image";
-            let mut element = BytesStart::borrowed(name, name.len());
+            let name = "itunes:image";
+            let mut element = BytesStart::new(name);
             element.push_attribute(("href", &**image));
             writer.write_event(Event::Empty(element))?;
         }
 
         if let Some(explicit) = self.explicit.as_ref() {
-            writer.write_text_element(b"itunes:explicit", explicit)?;
+            writer.write_text_element("itunes:explicit", explicit)?;
         }
 
         if let Some(complete) = self.complete.as_ref() {
-            writer.write_text_element(b"itunes:complete", complete)?;
+            writer.write_text_element("itunes:complete", complete)?;
         }
 
         if let Some(new_feed_url) = self.new_feed_url.as_ref() {
-            writer.write_text_element(b"itunes:new-feed-url", new_feed_url)?;
+            writer.write_text_element("itunes:new-feed-url", new_feed_url)?;
         }
 
         if let Some(owner) = self.owner.as_ref() {
@@ -536,23 +688,29 @@ impl ToXml for ITunesChannelExtension {
         }
 
         if let Some(subtitle) = self.subtitle.as_ref() {
-            writer.write_text_element(b"itunes:subtitle", subtitle)?;
+            writer.write_text_element("itunes:subtitle", subtitle)?;
         }
 
         if let Some(summary) = self.summary.as_ref() {
-            writer.write_text_element(b"itunes:summary", summary)?;
+            writer.write_text_element("itunes:summary", summary)?;
         }
 
         if let Some(keywords) = self.keywords.as_ref() {
-            writer.write_text_element(b"itunes:keywords", keywords)?;
+            writer.write_text_element("itunes:keywords", keywords)?;
         }
 
         if let Some(r#type) = self.r#type.as_ref() {
-            writer.write_text_element(b"itunes:type", r#type)?;
+            writer.write_text_element("itunes:type", r#type)?;
         }
 
         Ok(())
     }
+
+    fn used_namespaces(&self) -> BTreeMap<String, String> {
+        let mut namespaces = BTreeMap::new();
+        namespaces.insert("itunes".to_owned(), NAMESPACE.to_owned());
+        namespaces
+    }
 }
 
 #[cfg(feature = "builders")]
@@ -7,15 +7,17 @@
 
 use std::collections::BTreeMap;
 use std::io::Write;
+use std::time::Duration;
 
 use quick_xml::events::{BytesEnd, BytesStart, Event};
 use quick_xml::Error as XmlError;
-use quick_xml::Writer;
 
-use super::{parse_image, NAMESPACE};
+use super::duration::{format_duration, parse_duration};
+use super::itunes_values::{format_flag, parse_flag, parse_number};
+use super::{parse_image, EpisodeType, Explicit, NAMESPACE};
 use crate::extension::util::remove_extension_value;
 use crate::extension::Extension;
-use crate::toxml::{ToXml, WriterExt};
+use crate::toxml::{ToXml, WriterExt, XmlWriter};
 
 /// An iTunes item element extension.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -135,6 +137,44 @@ impl ITunesItemExtension {
         self.block = block.into();
     }
 
+    /// Return whether this podcast episode is blocked from appearing in the iTunes Store.
+    ///
+    /// A value of `Yes` or `True` sets the flag, and any other value clears it. Returns `None` if
+    /// there is no value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::extension::itunes::ITunesItemExtension;
+    ///
+    /// let mut extension = ITunesItemExtension::default();
+    /// extension.set_block("Yes".to_string());
+    /// assert_eq!(extension.block_parsed(), Some(true));
+    /// ```
+    pub fn block_parsed(&self) -> Option<bool> {
+        self.block.as_deref().map(parse_flag)
+    }
+
+    /// Set whether this podcast episode is blocked from appearing in the iTunes Store.
+    ///
+    /// The flag is written as `Yes` or `No`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::extension::itunes::ITunesItemExtension;
+    ///
+    /// let mut extension = ITunesItemExtension::default();
+    /// extension.set_block_from(true);
+    /// assert_eq!(extension.block(), Some("Yes"));
+    /// ```
+    pub fn set_block_from<V>(&mut self, block: V)
+    where
+        V: Into<Option<bool>>,
+    {
+        self.block = block.into().map(format_flag);
+    }
+
     /// Return the artwork URL for this podcast episode.
     ///
     /// # Examples
@@ -203,6 +243,49 @@ impl ITunesItemExtension {
         self.duration = duration.into();
     }
 
+    /// Return the duration of this podcast episode as a `Duration`.
+    ///
+    /// Besides the formats above and a plain number of seconds, this accepts fractional seconds
+    /// and durations with units such as `1h2m`. Returns `None` if there is no duration or it
+    /// cannot be parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use rss::extension::itunes::ITunesItemExtension;
+    ///
+    /// let mut extension = ITunesItemExtension::default();
+    /// extension.set_duration("1:02:03".to_string());
+    /// assert_eq!(extension.duration_parsed(), Some(Duration::from_secs(3723)));
+    ///
+    /// extension.set_duration("1h2m".to_string());
+    /// assert_eq!(extension.duration_parsed(), Some(Duration::from_secs(3720)));
+    /// ```
+    pub fn duration_parsed(&self) -> Option<Duration> {
+        self.duration.as_deref().and_then(parse_duration)
+    }
+
+    /// Set the duration of this podcast episode from a `Duration`.
+    ///
+    /// The duration is written as HH:MM:SS, without fractions of a second.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use rss::extension::itunes::ITunesItemExtension;
+    ///
+    /// let mut extension = ITunesItemExtension::default();
+    /// extension.set_duration_from(Duration::from_secs(3723));
+    /// assert_eq!(extension.duration(), Some("01:02:03"));
+    /// ```
+    pub fn set_duration_from(&mut self, duration: Duration) {
+        self.duration = Some(format_duration(duration));
+    }
+
     /// Return whether this podcast episode contains explicit content.
     ///
     /// A value of `Yes`, `Explicit`, or `True` indicates that the episode contains explicit
@@ -243,6 +326,47 @@ impl ITunesItemExtension {
         self.explicit = explicit.into();
     }
 
+    /// Return whether this podcast episode contains explicit content.
+    ///
+    /// A value of `Yes`, `Explicit` or `True` is read as `Explicit::Yes`, a value of `No` or
+    /// `False` as `Explicit::No`, and a value of `Clean` as `Explicit::Clean`. Returns `None` if
+    /// there is no value or it is not one of these.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::extension::itunes::{Explicit, ITunesItemExtension};
+    ///
+    /// let mut extension = ITunesItemExtension::default();
+    /// extension.set_explicit("Yes".to_string());
+    /// assert_eq!(extension.explicit_parsed(), Some(Explicit::Yes));
+    /// ```
+    pub fn explicit_parsed(&self) -> Option<Explicit> {
+        self.explicit.as_deref().and_then(Explicit::parse)
+    }
+
+    /// Set whether this podcast episode contains explicit content.
+    ///
+    /// The value is written as `true`, `false` or `clean`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::extension::itunes::{Explicit, ITunesItemExtension};
+    ///
+    /// let mut extension = ITunesItemExtension::default();
+    /// extension.set_explicit_from(Explicit::No);
+    /// assert_eq!(extension.explicit(), Some("false"));
+    /// ```
+    pub fn set_explicit_from<V>(&mut self, explicit: V)
+    where
+        V: Into<Option<Explicit>>,
+    {
+        self.explicit = explicit
+            .into()
+            .map(|explicit| explicit.as_str().to_string());
+    }
+
     /// Return whether this podcast episode contains embedded closed captioning.
     ///
     /// A value of `Yes` indicates that it does. Any other value indicates that it does not.
@@ -279,6 +403,44 @@ impl ITunesItemExtension {
         self.closed_captioned = closed_captioned.into();
     }
 
+    /// Return whether this podcast episode contains embedded closed captioning.
+    ///
+    /// A value of `Yes` or `True` sets the flag, and any other value clears it. Returns `None` if
+    /// there is no value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::extension::itunes::ITunesItemExtension;
+    ///
+    /// let mut extension = ITunesItemExtension::default();
+    /// extension.set_closed_captioned("Yes".to_string());
+    /// assert_eq!(extension.closed_captioned_parsed(), Some(true));
+    /// ```
+    pub fn closed_captioned_parsed(&self) -> Option<bool> {
+        self.closed_captioned.as_deref().map(parse_flag)
+    }
+
+    /// Set whether this podcast episode contains embedded closed captioning.
+    ///
+    /// The flag is written as `Yes` or `No`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::extension::itunes::ITunesItemExtension;
+    ///
+    /// let mut extension = ITunesItemExtension::default();
+    /// extension.set_closed_captioned_from(true);
+    /// assert_eq!(extension.closed_captioned(), Some("Yes"));
+    /// ```
+    pub fn set_closed_captioned_from<V>(&mut self, closed_captioned: V)
+    where
+        V: Into<Option<bool>>,
+    {
+        self.closed_captioned = closed_captioned.into().map(format_flag);
+    }
+
     /// Return the value used to override the default sorting order for episodes.
     ///
     /// # Examples
@@ -448,6 +610,41 @@ impl ITunesItemExtension {
         self.episode = episode.into()
     }
 
+    /// Return the episode number of this podcast episode as a number.
+    ///
+    /// Returns `None` if there is no episode number or it is not a number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::extension::itunes::ITunesItemExtension;
+    ///
+    /// let mut extension = ITunesItemExtension::default();
+    /// extension.set_episode("3".to_string());
+    /// assert_eq!(extension.episode_parsed(), Some(3));
+    /// ```
+    pub fn episode_parsed(&self) -> Option<u32> {
+        self.episode.as_deref().and_then(parse_number)
+    }
+
+    /// Set the episode number of this podcast episode from a number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::extension::itunes::ITunesItemExtension;
+    ///
+    /// let mut extension = ITunesItemExtension::default();
+    /// extension.set_episode_from(3);
+    /// assert_eq!(extension.episode(), Some("3"));
+    /// ```
+    pub fn set_episode_from<V>(&mut self, episode: V)
+    where
+        V: Into<Option<u32>>,
+    {
+        self.episode = episode.into().map(|episode| episode.to_string());
+    }
+
     /// Return the season of this podcast episode
     ///
     /// The season will be a string although it is typically a number in practice
@@ -485,6 +682,41 @@ impl ITunesItemExtension {
         self.season = season.into()
     }
 
+    /// Return the season number of this podcast episode as a number.
+    ///
+    /// Returns `None` if there is no season number or it is not a number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::extension::itunes::ITunesItemExtension;
+    ///
+    /// let mut extension = ITunesItemExtension::default();
+    /// extension.set_season("3".to_string());
+    /// assert_eq!(extension.season_parsed(), Some(3));
+    /// ```
+    pub fn season_parsed(&self) -> Option<u32> {
+        self.season.as_deref().and_then(parse_number)
+    }
+
+    /// Set the season number of this podcast episode from a number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::extension::itunes::ITunesItemExtension;
+    ///
+    /// let mut extension = ITunesItemExtension::default();
+    /// extension.set_season_from(3);
+    /// assert_eq!(extension.season(), Some("3"));
+    /// ```
+    pub fn set_season_from<V>(&mut self, season: V)
+    where
+        V: Into<Option<u32>>,
+    {
+        self.season = season.into().map(|season| season.to_string());
+    }
+
     /// Return the episode_type of this podcast episode
     ///
     /// The episode type will be a string usually "full" "trailer" or "bonus"
@@ -521,6 +753,43 @@ impl ITunesItemExtension {
     {
         self.episode_type = episode_type.into()
     }
+
+    /// Return the type of this podcast episode.
+    ///
+    /// Returns `None` if there is no type or it is not "full", "trailer" or "bonus".
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::extension::itunes::{EpisodeType, ITunesItemExtension};
+    ///
+    /// let mut extension = ITunesItemExtension::default();
+    /// extension.set_episode_type("trailer".to_string());
+    /// assert_eq!(extension.episode_type_parsed(), Some(EpisodeType::Trailer));
+    /// ```
+    pub fn episode_type_parsed(&self) -> Option<EpisodeType> {
+        self.episode_type.as_deref().and_then(EpisodeType::parse)
+    }
+
+    /// Set the type of this podcast episode.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::extension::itunes::{EpisodeType, ITunesItemExtension};
+    ///
+    /// let mut extension = ITunesItemExtension::default();
+    /// extension.set_episode_type_from(EpisodeType::Bonus);
+    /// assert_eq!(extension.episode_type(), Some("bonus"));
+    /// ```
+    pub fn set_episode_type_from<V>(&mut self, episode_type: V)
+    where
+        V: Into<Option<EpisodeType>>,
+    {
+        self.episode_type = episode_type
+            .into()
+            .map(|episode_type| episode_type.as_str().to_string());
+    }
 }
 
 impl ITunesItemExtension {
@@ -545,61 +814,61 @@ impl ITunesItemExtension {
 }
 
 impl ToXml for ITunesItemExtension {
-    fn to_xml<W: Write>(&self, writer: &mut Writer<W>) -> Result<(), XmlError> {
+    fn to_xml<W: Write>(&self, writer: &mut XmlWriter<W>) -> Result<(), XmlError> {
         if let Some(author) = self.author.as_ref() {
-            writer.write_text_element(b"itunes:author", author)?;
+            writer.write_text_element("itunes:author", author)?;
         }
 
         if let Some(block) = self.block.as_ref() {
-            writer.write_text_element(b"itunes:block", block)?;
+            writer.write_text_element("itunes:block", block)?;
         }
 
         if let Some(image) = self.image.as_ref() {
-            let name = b"itunes:image";
-            let mut element = BytesStart::borrowed(name, name.len());
+            let name = "itunes:image";
+            let mut element = BytesStart::new(name);
             element.push_attribute(("href", &**image));
             writer.write_event(Event::Start(element))?;
-            writer.write_event(Event::End(BytesEnd::borrowed(name)))?;
+            writer.write_event(Event::End(BytesEnd::new(name)))?;
         }
 
         if let Some(duration) = self.duration.as_ref() {
-            writer.write_text_element(b"itunes:duration", duration)?;
+            writer.write_text_element("itunes:duration", duration)?;
         }
 
         if let Some(explicit) = self.explicit.as_ref() {
-            writer.write_text_element(b"itunes:explicit", explicit)?;
+            writer.write_text_element("itunes:explicit", explicit)?;
         }
 
         if let Some(closed_captioned) = self.closed_captioned.as_ref() {
-            writer.write_text_element(b"itunes:isClosedCaptioned", closed_captioned)?;
+            writer.write_text_element("itunes:isClosedCaptioned", closed_captioned)?;
         }
 
         if let Some(order) = self.order.as_ref() {
-            writer.write_text_element(b"itunes:order", order)?;
+            writer.write_text_element("itunes:order", order)?;
         }
 
         if let Some(subtitle) = self.subtitle.as_ref() {
-            writer.write_text_element(b"itunes:subtitle", subtitle)?;
+            writer.write_text_element("itunes:subtitle", subtitle)?;
         }
 
         if let Some(summary) = self.summary.as_ref() {
-            writer.write_text_element(b"itunes:summary", summary)?;
+            writer.write_text_element("itunes:summary", summary)?;
         }
 
         if let Some(keywords) = self.keywords.as_ref() {
-            writer.write_text_element(b"itunes:keywords", keywords)?;
+            writer.write_text_element("itunes:keywords", keywords)?;
         }
 
         if let Some(episode) = self.episode.as_ref() {
-            writer.write_text_element(b"itunes:episode", episode.to_string())?;
+            writer.write_text_element("itunes:episode", episode)?;
         }
 
         if let Some(season) = self.season.as_ref() {
-            writer.write_text_element(b"itunes:season", season.to_string())?;
+            writer.write_text_element("itunes:season", season)?;
         }
 
         if let Some(episode_type) = self.episode_type.as_ref() {
-            writer.write_text_element(b"itunes:episodeType", episode_type.to_string())?;
+            writer.write_text_element("itunes:episodeType", episode_type)?;
         }
 
         Ok(())
@@ -0,0 +1,134 @@
+// This file is part of rss.
+//
+// Copyright © 2015-2021 The rust-syndication Developers
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the MIT License and/or Apache 2.0 License.
+
+/// Whether a podcast or podcast episode contains explicit content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Explicit {
+    /// The content is explicit. Read from `true`, `yes` or `explicit`, and written as `true`.
+    Yes,
+    /// The content is not explicit. Read from `false` or `no`, and written as `false`.
+    No,
+    /// The content is a clean version of explicit content. Read and written as `clean`.
+    Clean,
+}
+
+impl Explicit {
+    /// Return the canonical spelling of this value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::extension::itunes::Explicit;
+    ///
+    /// assert_eq!(Explicit::Yes.as_str(), "true");
+    /// ```
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Explicit::Yes => "true",
+            Explicit::No => "false",
+            Explicit::Clean => "clean",
+        }
+    }
+
+    pub(crate) fn parse(text: &str) -> Option<Self> {
+        match text.trim().to_ascii_lowercase().as_str() {
+            "true" | "yes" | "explicit" => Some(Explicit::Yes),
+            "false" | "no" => Some(Explicit::No),
+            "clean" => Some(Explicit::Clean),
+            _ => None,
+        }
+    }
+}
+
+/// The type of a podcast episode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EpisodeType {
+    /// A complete episode.
+    Full,
+    /// A short promotional piece for an upcoming episode or show.
+    Trailer,
+    /// Extra content for the show, such as behind the scenes material.
+    Bonus,
+}
+
+impl EpisodeType {
+    /// Return the canonical spelling of this value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::extension::itunes::EpisodeType;
+    ///
+    /// assert_eq!(EpisodeType::Trailer.as_str(), "trailer");
+    /// ```
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EpisodeType::Full => "full",
+            EpisodeType::Trailer => "trailer",
+            EpisodeType::Bonus => "bonus",
+        }
+    }
+
+    pub(crate) fn parse(text: &str) -> Option<Self> {
+        match text.trim().to_ascii_lowercase().as_str() {
+            "full" => Some(EpisodeType::Full),
+            "trailer" => Some(EpisodeType::Trailer),
+            "bonus" => Some(EpisodeType::Bonus),
+            _ => None,
+        }
+    }
+}
+
+/// The type of a podcast, which tells in which order its episodes are meant to be listened to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShowType {
+    /// Episodes are meant to be listened to newest first.
+    Episodic,
+    /// Episodes are meant to be listened to oldest first.
+    Serial,
+}
+
+impl ShowType {
+    /// Return the canonical spelling of this value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::extension::itunes::ShowType;
+    ///
+    /// assert_eq!(ShowType::Serial.as_str(), "serial");
+    /// ```
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ShowType::Episodic => "episodic",
+            ShowType::Serial => "serial",
+        }
+    }
+
+    pub(crate) fn parse(text: &str) -> Option<Self> {
+        match text.trim().to_ascii_lowercase().as_str() {
+            "episodic" => Some(ShowType::Episodic),
+            "serial" => Some(ShowType::Serial),
+            _ => None,
+        }
+    }
+}
+
+/// Parses a flag such as `block`, which is set by `yes` or `true`. Any other value clears it.
+pub(crate) fn parse_flag(text: &str) -> bool {
+    matches!(text.trim().to_ascii_lowercase().as_str(), "yes" | "true")
+}
+
+/// Formats a flag as `Yes` or `No`.
+pub(crate) fn format_flag(flag: bool) -> String {
+    if flag { "Yes" } else { "No" }.to_string()
+}
+
+/// Parses a number such as the `episode` or `season` of an episode.
+pub(crate) fn parse_number(text: &str) -> Option<u32> {
+    text.trim().parse().ok()
+}
@@ -9,19 +9,23 @@ use std::collections::BTreeMap;
 
 use crate::extension::Extension;
 
+mod duration;
 mod itunes_category;
 mod itunes_channel_extension;
 mod itunes_item_extension;
 mod itunes_owner;
+mod itunes_values;
 
 pub use self::itunes_category::*;
 pub use self::itunes_channel_extension::*;
 pub use self::itunes_item_extension::*;
 pub use self::itunes_owner::*;
+pub use self::itunes_values::{EpisodeType, Explicit, ShowType};
 
 /// The iTunes XML namespace.
 pub const NAMESPACE: &str = "http://www.itunes.com/dtds/podcast-1.0.dtd";
 
+#[allow(clippy::question_mark)]
 fn parse_image(map: &mut BTreeMap<String, Vec<Extension>>) -> Option<String> {
     let mut element = match map.remove("image").map(|mut v| v.remove(0)) {
         Some(element) => element,
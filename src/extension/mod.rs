@@ -11,9 +11,10 @@ use std::str;
 
 use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
 use quick_xml::Error as XmlError;
-use quick_xml::Writer;
 
-use crate::toxml::ToXml;
+use crate::markup::TextKind;
+use crate::namespaces::Namespaces;
+use crate::toxml::{ToXml, XmlWriter};
 
 /// Types and methods for [Atom](https://www.rssboard.org/rss-profile#namespace-elements-atom) extensions.
 #[cfg(feature = "atom")]
@@ -58,6 +59,9 @@ pub struct Extension {
     /// elements.
     #[cfg_attr(feature = "builders", builder(setter(each = "child")))]
     pub children: BTreeMap<String, Vec<Extension>>,
+    /// The kind of text in the content. Content of the kind `TextKind::Xhtml` is the raw XML
+    /// inside the element, which includes the children.
+    pub kind: TextKind,
 }
 
 impl Extension {
@@ -98,24 +102,57 @@ impl Extension {
     pub fn children(&self) -> &BTreeMap<String, Vec<Extension>> {
         &self.children
     }
+
+    /// Return the kind of text in the content of this extension.
+    pub fn kind(&self) -> TextKind {
+        self.kind
+    }
+
+    /// Set the kind of text in the content of this extension.
+    pub fn set_kind(&mut self, kind: TextKind) {
+        self.kind = kind;
+    }
 }
 
 impl ToXml for Extension {
-    fn to_xml<W: Write>(&self, writer: &mut Writer<W>) -> Result<(), XmlError> {
-        let name = self.name.as_bytes();
-        let mut element = BytesStart::borrowed_name(name);
-        element.extend_attributes(self.attrs.iter().map(|a| (a.0.as_str(), a.1.as_str())));
-        writer.write_event(Event::Start(element))?;
+    fn to_xml<W: Write>(&self, writer: &mut XmlWriter<W>) -> Result<(), XmlError> {
+        self.write_xml(&Namespaces::default(), writer)
+    }
+}
 
-        if let Some(ref value) = self.value {
-            writer.write_event(Event::Text(BytesText::from_plain_str(value)))?;
+impl Extension {
+    /// Writes this extension, replacing the prefixes that `namespaces` renamed to avoid a
+    /// conflict, unless the extension declares them itself.
+    pub(crate) fn write_xml<W: Write>(
+        &self,
+        namespaces: &Namespaces,
+        writer: &mut XmlWriter<W>,
+    ) -> Result<(), XmlError> {
+        let namespaces = namespaces.scoped(&self.attrs);
+        let namespaces = namespaces.as_ref();
+        let name = namespaces.rename_name(&self.name);
+        let mut element = BytesStart::new(name.as_str());
+        for (key, value) in &self.attrs {
+            element.push_attribute((namespaces.rename_name(key).as_str(), value.as_str()));
         }
+        writer.write_event(Event::Start(element))?;
 
-        for extension in self.children.values().flatten() {
-            extension.to_xml(writer)?;
+        match self.value {
+            Some(ref value) if self.kind == TextKind::Xhtml => {
+                writer.write_event(Event::Text(BytesText::from_escaped(value.as_str())))?;
+            }
+            _ => {
+                if let Some(ref value) = self.value {
+                    writer.write_event(Event::Text(BytesText::new(value)))?;
+                }
+
+                for extension in self.children.values().flatten() {
+                    extension.write_xml(namespaces, writer)?;
+                }
+            }
         }
 
-        writer.write_event(Event::End(BytesEnd::borrowed(name)))?;
+        writer.write_event(Event::End(BytesEnd::new(name)))?;
         Ok(())
     }
 }
@@ -10,11 +10,14 @@ use std::fmt;
 use std::io::Write;
 use std::str::FromStr;
 
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, Datelike, Duration, FixedOffset, Months, TimeZone};
 use quick_xml::Error as XmlError;
-use quick_xml::Writer;
 
+#[cfg(feature = "chrono")]
+use crate::date::{format_w3cdtf, parse_w3cdtf};
 use crate::extension::Extension;
-use crate::toxml::WriterExt;
+use crate::toxml::{WriterExt, XmlWriter};
 
 /// The Syndication XML namespace.
 pub const NAMESPACE: &str = "http://purl.org/rss/1.0/modules/syndication/";
@@ -39,7 +42,7 @@ impl FromStr for UpdatePeriod {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
+        match s.trim().to_ascii_lowercase().as_str() {
             "hourly" => Ok(UpdatePeriod::Hourly),
             "daily" => Ok(UpdatePeriod::Daily),
             "weekly" => Ok(UpdatePeriod::Weekly),
@@ -77,7 +80,7 @@ impl fmt::Display for UpdatePeriod {
 pub struct SyndicationExtension {
     /// The refresh period for this channel
     pub period: UpdatePeriod,
-    /// Number of periods between refreshes
+    /// Number of refreshes in each period
     pub frequency: u32,
     /// Timestamp from which the refresh periods are calculated
     pub base: String,
@@ -94,12 +97,12 @@ impl SyndicationExtension {
         self.base = base.to_owned();
     }
 
-    /// Retrieve the number of periods between refreshes
+    /// Retrieve the number of refreshes in each period
     pub fn frequency(&self) -> u32 {
         self.frequency
     }
 
-    /// Set the number of periods between refreshes
+    /// Set the number of refreshes in each period
     pub fn set_frequency(&mut self, frequency: u32) {
         self.frequency = frequency;
     }
@@ -114,27 +117,167 @@ impl SyndicationExtension {
         self.period = period;
     }
 
-    /// Serialises this extension to the nominated writer
-    pub fn to_xml<W: Write>(
+    /// Retrieve the base timestamp from which the refresh periods are calculated as a date.
+    ///
+    /// The base is a W3C date and time such as `2000-01-01T12:00+00:00`. Returns `None` if it
+    /// cannot be parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::extension::syndication::SyndicationExtension;
+    ///
+    /// let mut syn = SyndicationExtension::default();
+    /// syn.set_base("2000-01-01T12:00+00:00");
+    /// assert_eq!(
+    ///     syn.base_parsed().map(|base| base.to_rfc3339()),
+    ///     Some("2000-01-01T12:00:00+00:00".to_string())
+    /// );
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn base_parsed(&self) -> Option<DateTime<FixedOffset>> {
+        parse_w3cdtf(&self.base)
+    }
+
+    /// Set the base from which the refresh periods are calculated to a date.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use rss::extension::syndication::SyndicationExtension;
+    ///
+    /// let mut syn = SyndicationExtension::default();
+    /// syn.set_base_datetime(&Utc.with_ymd_and_hms(2000, 1, 1, 12, 0, 0).unwrap());
+    /// assert_eq!(syn.base(), "2000-01-01T12:00:00Z");
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn set_base_datetime<Tz: TimeZone>(&mut self, base: &DateTime<Tz>) {
+        self.base = format_w3cdtf(base);
+    }
+
+    /// Return the first refresh of this channel after `now`.
+    ///
+    /// The channel is refreshed `frequency` times in each period, at even intervals starting at
+    /// the base. Monthly and yearly periods follow the calendar, so that a monthly refresh with a
+    /// base on the 15th happens on the 15th of each month. Returns `None` if the base cannot be
+    /// parsed, the frequency is zero or the refresh is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use rss::extension::syndication::{SyndicationExtension, UpdatePeriod};
+    ///
+    /// let mut syn = SyndicationExtension::default();
+    /// syn.set_period(UpdatePeriod::Hourly);
+    /// syn.set_frequency(2);
+    /// syn.set_base("2000-01-01T12:00+00:00");
+    ///
+    /// let now = Utc.with_ymd_and_hms(2020, 6, 1, 8, 10, 0).unwrap();
+    /// let next = syn.next_update_after(&now).unwrap();
+    /// assert_eq!(next, Utc.with_ymd_and_hms(2020, 6, 1, 8, 30, 0).unwrap());
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn next_update_after<Tz: TimeZone>(
+        &self,
+        now: &DateTime<Tz>,
+    ) -> Option<DateTime<FixedOffset>> {
+        let base = self.base_parsed()?;
+        if self.frequency == 0 {
+            return None;
+        }
+
+        let now = now.with_timezone(base.offset());
+        if now < base {
+            return Some(base);
+        }
+
+        let length = match self.period {
+            UpdatePeriod::Hourly => Duration::hours(1),
+            UpdatePeriod::Daily => Duration::days(1),
+            UpdatePeriod::Weekly => Duration::weeks(1),
+            UpdatePeriod::Monthly => return next_calendar_update(base, 1, self.frequency, now),
+            UpdatePeriod::Yearly => return next_calendar_update(base, 12, self.frequency, now),
+        };
+        next_update(base, length, self.frequency, now)
+    }
+
+    /// Serialises this extension to the nominated writer, using the prefix bound to the
+    /// Syndication namespace in `namespaces`, or `sy` if there is none.
+    pub(crate) fn to_xml<W: Write>(
         &self,
         namespaces: &BTreeMap<String, String>,
-        writer: &mut Writer<W>,
+        writer: &mut XmlWriter<W>,
     ) -> Result<(), XmlError> {
-        for (prefix, namespace) in namespaces {
-            if NAMESPACE == namespace {
-                writer.write_text_element(
-                    format!("{}:updatePeriod", prefix),
-                    &self.period.to_string(),
-                )?;
-                writer.write_text_element(
-                    format!("{}:updateFrequency", prefix),
-                    &format!("{}", self.frequency),
-                )?;
-                writer.write_text_element(format!("{}:updateBase", prefix), &self.base)?;
-            }
-        }
+        let prefix = namespaces
+            .iter()
+            .find(|(prefix, namespace)| !prefix.is_empty() && *namespace == NAMESPACE)
+            .map_or("sy", |(prefix, _)| prefix.as_str());
+        writer.write_text_element(format!("{}:updatePeriod", prefix), self.period.to_string())?;
+        writer.write_text_element(
+            format!("{}:updateFrequency", prefix),
+            self.frequency.to_string(),
+        )?;
+        writer.write_text_element(format!("{}:updateBase", prefix), &self.base)?;
         Ok(())
     }
+
+    /// Return the namespaces used by this extension.
+    pub(crate) fn used_namespaces(&self) -> BTreeMap<String, String> {
+        let mut namespaces = BTreeMap::new();
+        namespaces.insert("sy".to_owned(), NAMESPACE.to_owned());
+        namespaces
+    }
+}
+
+/// Returns the first of `frequency` refreshes in each period of `length` starting at `base` that
+/// is after `now`.
+#[cfg(feature = "chrono")]
+fn next_update(
+    base: DateTime<FixedOffset>,
+    length: Duration,
+    frequency: u32,
+    now: DateTime<FixedOffset>,
+) -> Option<DateTime<FixedOffset>> {
+    let interval = length.num_milliseconds() / i64::from(frequency);
+    if interval <= 0 {
+        return None;
+    }
+    let elapsed = (now - base).num_milliseconds();
+    let count = elapsed / interval + 1;
+    base.checked_add_signed(Duration::milliseconds(count.checked_mul(interval)?))
+}
+
+/// Returns the first of `frequency` refreshes in each period of `months` calendar months starting
+/// at `base` that is after `now`.
+#[cfg(feature = "chrono")]
+fn next_calendar_update(
+    base: DateTime<FixedOffset>,
+    months: u32,
+    frequency: u32,
+    now: DateTime<FixedOffset>,
+) -> Option<DateTime<FixedOffset>> {
+    let period_start =
+        |index: u32| base.checked_add_months(Months::new(index.checked_mul(months)?));
+
+    // find the period that contains `now`, starting from an estimate that is at most one period
+    // off
+    let elapsed = (now.year() - base.year()) * 12 + now.month() as i32 - base.month() as i32;
+    let mut index = elapsed.max(0) as u32 / months;
+    while index > 0 && period_start(index)? > now {
+        index -= 1;
+    }
+    while period_start(index + 1)? <= now {
+        index += 1;
+    }
+
+    let start = period_start(index)?;
+    let end = period_start(index + 1)?;
+    match next_update(start, end - start, frequency, now)? {
+        next if next >= end => Some(end),
+        next => Some(next),
+    }
 }
 
 impl Default for SyndicationExtension {
@@ -147,37 +290,56 @@ impl Default for SyndicationExtension {
     }
 }
 
-/// Retrieves the extensions for the nominated field and runs the callback if there is at least 1 extension value
-fn with_first_ext_value<'a, F>(map: &'a BTreeMap<String, Vec<Extension>>, field: &str, f: F)
-where
-    F: FnOnce(&'a str),
-{
-    if let Some(extensions) = map.get(field) {
-        if !extensions.is_empty() {
-            if let Some(v) = extensions[0].value.as_ref() {
-                f(v);
-            }
-        }
-    }
+/// Retrieves the value of the first extension for the nominated field, which is empty if the
+/// extension has no value
+fn first_ext_value<'a>(map: &'a BTreeMap<String, Vec<Extension>>, field: &str) -> Option<&'a str> {
+    let extension = map.get(field)?.first()?;
+    Some(extension.value.as_deref().unwrap_or_default())
 }
 
 impl SyndicationExtension {
     /// Creates a `SyndicationExtension` using the specified `BTreeMap`.
+    ///
+    /// Periods and frequencies that are not valid are ignored, leaving the default values.
     pub fn from_map(map: BTreeMap<String, Vec<Extension>>) -> Self {
         let mut syn = SyndicationExtension::default();
 
-        with_first_ext_value(&map, "updatePeriod", |value| {
-            syn.period = value.parse().unwrap()
-        });
-        with_first_ext_value(&map, "updateFrequency", |value| {
-            syn.frequency = value.parse().unwrap()
-        });
-        with_first_ext_value(&map, "updateBase", |value| syn.base = value.to_owned());
+        if let Some(period) = first_ext_value(&map, "updatePeriod").and_then(|v| v.parse().ok()) {
+            syn.period = period;
+        }
+        if let Some(frequency) = first_ext_value(&map, "updateFrequency").and_then(parse_frequency)
+        {
+            syn.frequency = frequency;
+        }
+        if let Some(value) = first_ext_value(&map, "updateBase").filter(|value| !value.is_empty()) {
+            syn.base = value.to_owned();
+        }
 
         syn
     }
 }
 
+/// Parses an update frequency, which has to be a positive integer.
+fn parse_frequency(value: &str) -> Option<u32> {
+    value.trim().parse().ok().filter(|frequency| *frequency > 0)
+}
+
+/// Returns a warning if `value` is not valid for the element with the local name `name`, so that
+/// `SyndicationExtension::from_map` ignores it.
+pub(crate) fn check_value(name: &str, value: &str) -> Option<String> {
+    let valid = match name {
+        "updatePeriod" => value.parse::<UpdatePeriod>().is_ok(),
+        "updateFrequency" => parse_frequency(value).is_some(),
+        _ => true,
+    };
+
+    if valid {
+        None
+    } else {
+        Some(format!("ignored invalid {} `{}`", name, value))
+    }
+}
+
 #[cfg(feature = "builders")]
 impl SyndicationExtensionBuilder {
     /// Builds a new `SyndicationExtension`.
@@ -190,6 +352,46 @@ impl SyndicationExtensionBuilder {
 mod tests {
     use super::*;
 
+    fn map(values: &[(&str, &str)]) -> BTreeMap<String, Vec<Extension>> {
+        values
+            .iter()
+            .map(|(name, value)| {
+                let mut ext = Extension::default();
+                ext.set_value(value.to_string());
+                (name.to_string(), vec![ext])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_from_map_lenient() {
+        let syn = SyndicationExtension::from_map(map(&[
+            ("updatePeriod", " Weekly "),
+            ("updateFrequency", " 3 "),
+        ]));
+        assert_eq!(syn.period, UpdatePeriod::Weekly);
+        assert_eq!(syn.frequency, 3);
+        assert_eq!(check_value("updatePeriod", " Weekly "), None);
+        assert_eq!(check_value("updateFrequency", " 3 "), None);
+    }
+
+    #[test]
+    fn test_from_map_invalid() {
+        let syn = SyndicationExtension::from_map(map(&[
+            ("updatePeriod", "fortnightly"),
+            ("updateFrequency", ""),
+        ]));
+        assert_eq!(syn, SyndicationExtension::default());
+        assert_eq!(
+            check_value("updatePeriod", "fortnightly").unwrap(),
+            "ignored invalid updatePeriod `fortnightly`"
+        );
+        assert_eq!(
+            check_value("updateFrequency", "").unwrap(),
+            "ignored invalid updateFrequency ``"
+        );
+    }
+
     #[test]
     #[cfg(feature = "builders")]
     fn test_builder() {
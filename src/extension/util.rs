@@ -6,88 +6,58 @@
 // it under the terms of the MIT License and/or Apache 2.0 License.
 
 use std::collections::BTreeMap;
-use std::io::BufRead;
-use std::str;
 
-use quick_xml::events::attributes::Attributes;
-use quick_xml::events::Event;
-use quick_xml::Reader;
-
-use crate::error::Error;
-use crate::extension::{Extension, ExtensionMap};
-
-pub fn extension_name(element_name: &[u8]) -> Option<(&[u8], &[u8])> {
-    let mut split = element_name.splitn(2, |b| *b == b':');
-    match split.next() {
-        Some(b"") | None => None,
-        Some(ns) => split.next().map(|name| (ns, name)),
+use crate::extension::{dublincore, itunes, syndication, Extension, ExtensionMap};
+use crate::util::CONTENT_NAMESPACE;
+
+/// Returns the prefix conventionally bound to a known extension namespace.
+pub fn known_prefix(namespace: &str) -> Option<&'static str> {
+    match namespace {
+        // the Atom namespace, which is recognised even without the `atom` feature
+        "http://www.w3.org/2005/Atom" => Some("atom"),
+        itunes::NAMESPACE => Some("itunes"),
+        dublincore::NAMESPACE => Some("dc"),
+        syndication::NAMESPACE => Some("sy"),
+        CONTENT_NAMESPACE => Some("content"),
+        _ => None,
     }
 }
 
-pub fn parse_extension<R>(
-    reader: &mut Reader<R>,
-    atts: Attributes,
-    ns: &[u8],
-    name: &[u8],
-    extensions: &mut ExtensionMap,
-) -> Result<(), Error>
-where
-    R: BufRead,
-{
-    let ns = str::from_utf8(ns)?;
-    let name = str::from_utf8(name)?;
-    let ext = parse_extension_element(reader, atts)?;
-
-    let map = extensions
-        .entry(ns.to_string())
-        .or_insert_with(BTreeMap::new);
-
-    let items = map.entry(name.to_string()).or_insert_with(Vec::new);
-    items.push(ext);
-
-    Ok(())
-}
-
-fn parse_extension_element<R: BufRead>(
-    reader: &mut Reader<R>,
-    mut atts: Attributes,
-) -> Result<Extension, Error> {
-    let mut extension = Extension::default();
-    let mut buf = Vec::new();
-
-    for attr in atts.with_checks(false).flatten() {
-        let key = str::from_utf8(attr.key)?;
-        let value = attr.unescape_and_decode_value(reader)?;
-        extension.attrs.insert(key.to_string(), value);
+/// Returns the namespace conventionally bound to a known extension prefix.
+pub fn known_namespace(prefix: &str) -> Option<&'static str> {
+    match prefix {
+        "atom" => Some("http://www.w3.org/2005/Atom"),
+        "itunes" => Some(itunes::NAMESPACE),
+        "dc" => Some(dublincore::NAMESPACE),
+        "sy" => Some(syndication::NAMESPACE),
+        "content" => Some(CONTENT_NAMESPACE),
+        _ => None,
     }
+}
 
-    loop {
-        match reader.read_event(&mut buf)? {
-            Event::Start(element) => {
-                let ext = parse_extension_element(reader, element.attributes())?;
-                let name = str::from_utf8(element.local_name())?;
-                let items = extension
-                    .children
-                    .entry(name.to_string())
-                    .or_insert_with(Vec::new);
+/// Removes the extensions bound to `namespace` from `extensions`, merging the elements of every
+/// prefix that is bound to it.
+pub fn take_extensions(
+    extensions: &mut ExtensionMap,
+    extension_namespaces: &BTreeMap<String, String>,
+    namespace: &str,
+) -> Option<BTreeMap<String, Vec<Extension>>> {
+    let mut result: Option<BTreeMap<String, Vec<Extension>>> = None;
+
+    for (prefix, ns) in extension_namespaces {
+        if ns != namespace {
+            continue;
+        }
 
-                items.push(ext);
+        if let Some(map) = extensions.remove(prefix) {
+            let merged = result.get_or_insert_with(BTreeMap::new);
+            for (name, mut elements) in map {
+                merged.entry(name).or_default().append(&mut elements);
             }
-            Event::Text(element) | Event::CData(element) => {
-                extension.value = Some(element.unescape_and_decode(reader)?);
-            }
-            Event::End(element) => {
-                extension.name = reader.decode(element.name()).into();
-                break;
-            }
-            Event::Eof => return Err(Error::Eof),
-            _ => {}
         }
-
-        buf.clear();
     }
 
-    Ok(extension)
+    result
 }
 
 pub fn get_extension_values(v: Vec<Extension>) -> Vec<String> {
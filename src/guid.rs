@@ -5,17 +5,17 @@
 // This program is free software; you can redistribute it and/or modify
 // it under the terms of the MIT License and/or Apache 2.0 License.
 
+use std::collections::BTreeMap;
 use std::io::{BufRead, Write};
 
 use quick_xml::events::attributes::Attributes;
 use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
 use quick_xml::Error as XmlError;
 use quick_xml::Reader;
-use quick_xml::Writer;
 
+use crate::borrowed::{read_from_reader, GuidRef};
 use crate::error::Error;
-use crate::toxml::ToXml;
-use crate::util::element_text;
+use crate::toxml::{ToXml, XmlWriter};
 
 /// Represents the GUID of an RSS item.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -34,6 +34,10 @@ pub struct Guid {
     pub value: String,
     /// Indicates if the GUID is a permalink.
     pub permalink: bool,
+    /// The attributes of the GUID element that are not part of RSS, such as `xml:lang`, keyed
+    /// by qualified name.
+    #[cfg_attr(feature = "builders", builder(setter(each = "extra_attr")))]
+    pub extra_attrs: BTreeMap<String, String>,
 }
 
 impl Guid {
@@ -100,6 +104,43 @@ impl Guid {
     {
         self.value = value.into();
     }
+
+    /// Return the attributes of this GUID that are not part of RSS.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use rss::Guid;
+    ///
+    /// let mut extra_attrs = BTreeMap::new();
+    /// extra_attrs.insert("xml:lang".to_string(), "en".to_string());
+    ///
+    /// let mut guid = Guid::default();
+    /// guid.set_extra_attrs(extra_attrs);
+    /// assert_eq!(guid.extra_attrs()["xml:lang"], "en");
+    /// ```
+    pub fn extra_attrs(&self) -> &BTreeMap<String, String> {
+        &self.extra_attrs
+    }
+
+    /// Set the attributes of this GUID that are not part of RSS.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use rss::Guid;
+    ///
+    /// let mut guid = Guid::default();
+    /// guid.set_extra_attrs(BTreeMap::new());
+    /// ```
+    pub fn set_extra_attrs<V>(&mut self, extra_attrs: V)
+    where
+        V: Into<BTreeMap<String, String>>,
+    {
+        self.extra_attrs = extra_attrs.into();
+    }
 }
 
 impl Default for Guid {
@@ -108,43 +149,37 @@ impl Default for Guid {
         Guid {
             value: Default::default(),
             permalink: true,
+            extra_attrs: BTreeMap::new(),
         }
     }
 }
 
 impl Guid {
     /// Builds a Guid from source XML
-    pub fn from_xml<R: BufRead>(
-        reader: &mut Reader<R>,
-        mut atts: Attributes,
-    ) -> Result<Self, Error> {
-        let mut guid = Guid::default();
-
-        for attr in atts.with_checks(false).flatten() {
-            if attr.key == b"isPermaLink" {
-                guid.permalink = &*attr.value != b"false";
-                break;
-            }
-        }
-
-        guid.value = element_text(reader)?.unwrap_or_default();
-        Ok(guid)
+    pub fn from_xml<R: BufRead>(reader: &mut Reader<R>, atts: Attributes) -> Result<Self, Error> {
+        read_from_reader(reader, |parser| parser.read_guid(atts)).map(GuidRef::into_owned)
     }
 }
 
 impl ToXml for Guid {
-    fn to_xml<W: Write>(&self, writer: &mut Writer<W>) -> Result<(), XmlError> {
-        let name = b"guid";
-        let mut element = BytesStart::borrowed(name, name.len());
+    fn to_xml<W: Write>(&self, writer: &mut XmlWriter<W>) -> Result<(), XmlError> {
+        let name = "guid";
+        let mut element = BytesStart::new(name);
         if !self.permalink {
             element.push_attribute(("isPermaLink", "false"));
         }
 
+        element.extend_attributes(
+            self.extra_attrs
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str())),
+        );
+
         writer.write_event(Event::Start(element))?;
 
-        writer.write_event(Event::Text(BytesText::from_plain_str(&self.value)))?;
+        writer.write_event(Event::Text(BytesText::new(&self.value)))?;
 
-        writer.write_event(Event::End(BytesEnd::borrowed(name)))?;
+        writer.write_event(Event::End(BytesEnd::new(name)))?;
         Ok(())
     }
 }
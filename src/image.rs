@@ -5,17 +5,17 @@
 // This program is free software; you can redistribute it and/or modify
 // it under the terms of the MIT License and/or Apache 2.0 License.
 
+use std::collections::BTreeMap;
 use std::io::{BufRead, Write};
 
 use quick_xml::events::attributes::Attributes;
 use quick_xml::events::{BytesEnd, BytesStart, Event};
 use quick_xml::Error as XmlError;
 use quick_xml::Reader;
-use quick_xml::Writer;
 
+use crate::borrowed::{read_from_reader, ImageRef};
 use crate::error::Error;
-use crate::toxml::{ToXml, WriterExt};
-use crate::util::element_text;
+use crate::toxml::{ToXml, WriterExt, XmlWriter};
 
 /// Represents an image in an RSS feed.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -42,6 +42,12 @@ pub struct Image {
     pub height: Option<String>,
     /// The text for the HTML `title` attribute of the link formed around the image.
     pub description: Option<String>,
+    /// The URI that identifies the image in an RSS 1.0 feed, from its `rdf:about` attribute.
+    pub about: Option<String>,
+    /// The attributes of the image element that are not part of RSS, such as `xml:lang`, keyed
+    /// by qualified name.
+    #[cfg_attr(feature = "builders", builder(setter(each = "extra_attr")))]
+    pub extra_attrs: BTreeMap<String, String>,
 }
 
 impl Image {
@@ -242,60 +248,143 @@ impl Image {
     {
         self.description = description.into();
     }
+
+    /// Return the URI that identifies this image in an RSS 1.0 feed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::Image;
+    ///
+    /// let mut image = Image::default();
+    /// image.set_about("http://example.com/image.png".to_string());
+    /// assert_eq!(image.about(), Some("http://example.com/image.png"));
+    /// ```
+    pub fn about(&self) -> Option<&str> {
+        self.about.as_deref()
+    }
+
+    /// Set the URI that identifies this image in an RSS 1.0 feed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::Image;
+    ///
+    /// let mut image = Image::default();
+    /// image.set_about("http://example.com/image.png".to_string());
+    /// ```
+    pub fn set_about<V>(&mut self, about: V)
+    where
+        V: Into<Option<String>>,
+    {
+        self.about = about.into();
+    }
+
+    /// Return the attributes of this image that are not part of RSS.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use rss::Image;
+    ///
+    /// let mut extra_attrs = BTreeMap::new();
+    /// extra_attrs.insert("xml:lang".to_string(), "en".to_string());
+    ///
+    /// let mut image = Image::default();
+    /// image.set_extra_attrs(extra_attrs);
+    /// assert_eq!(image.extra_attrs()["xml:lang"], "en");
+    /// ```
+    pub fn extra_attrs(&self) -> &BTreeMap<String, String> {
+        &self.extra_attrs
+    }
+
+    /// Set the attributes of this image that are not part of RSS.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use rss::Image;
+    ///
+    /// let mut image = Image::default();
+    /// image.set_extra_attrs(BTreeMap::new());
+    /// ```
+    pub fn set_extra_attrs<V>(&mut self, extra_attrs: V)
+    where
+        V: Into<BTreeMap<String, String>>,
+    {
+        self.extra_attrs = extra_attrs.into();
+    }
 }
 
 impl Image {
     /// Builds an Image from source XML
-    pub fn from_xml<R: BufRead>(reader: &mut Reader<R>, _: Attributes) -> Result<Self, Error> {
-        let mut image = Image::default();
-        let mut buf = Vec::new();
-
-        loop {
-            match reader.read_event(&mut buf)? {
-                Event::Start(element) => match element.name() {
-                    b"url" => image.url = element_text(reader)?.unwrap_or_default(),
-                    b"title" => image.title = element_text(reader)?.unwrap_or_default(),
-                    b"link" => image.link = element_text(reader)?.unwrap_or_default(),
-                    b"width" => image.width = element_text(reader)?,
-                    b"height" => image.height = element_text(reader)?,
-                    b"description" => image.description = element_text(reader)?,
-                    n => reader.read_to_end(n, &mut Vec::new())?,
-                },
-                Event::End(_) => break,
-                Event::Eof => return Err(Error::Eof),
-                _ => {}
-            }
-
-            buf.clear();
-        }
+    pub fn from_xml<R: BufRead>(reader: &mut Reader<R>, atts: Attributes) -> Result<Self, Error> {
+        read_from_reader(reader, |parser| parser.read_image(&BTreeMap::new(), atts))
+            .map(ImageRef::into_owned)
+    }
+
+    /// Writes this image as an RSS 1.0 image and leaving out the elements RSS 1.0 does not have.
+    pub(crate) fn write_rdf<W: Write>(&self, writer: &mut XmlWriter<W>) -> Result<(), XmlError> {
+        let name = "image";
+        let mut element = BytesStart::new(name);
+        element.push_attribute(("rdf:about", self.rdf_about()));
+        element.extend_attributes(
+            self.extra_attrs
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str())),
+        );
+        writer.write_event(Event::Start(element))?;
 
-        Ok(image)
+        writer.write_text_element("title", &self.title)?;
+        writer.write_text_element("link", &self.link)?;
+        writer.write_text_element("url", &self.url)?;
+
+        writer.write_event(Event::End(BytesEnd::new(name)))?;
+        Ok(())
+    }
+
+    /// The URI that identifies this image in an RSS 1.0 feed, which defaults to its URL.
+    pub(crate) fn rdf_about(&self) -> &str {
+        self.about.as_deref().unwrap_or(&self.url)
     }
 }
 
 impl ToXml for Image {
-    fn to_xml<W: Write>(&self, writer: &mut Writer<W>) -> Result<(), XmlError> {
-        let name = b"image";
+    fn to_xml<W: Write>(&self, writer: &mut XmlWriter<W>) -> Result<(), XmlError> {
+        let name = "image";
+        let mut element = BytesStart::new(name);
+        if let Some(about) = self.about.as_ref() {
+            element.push_attribute(("rdf:about", about.as_str()));
+        }
+
+        element.extend_attributes(
+            self.extra_attrs
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str())),
+        );
 
-        writer.write_event(Event::Start(BytesStart::borrowed(name, name.len())))?;
+        writer.write_event(Event::Start(element))?;
 
-        writer.write_text_element(b"url", &self.url)?;
-        writer.write_text_element(b"title", &self.title)?;
-        writer.write_text_element(b"link", &self.link)?;
+        writer.write_text_element("url", &self.url)?;
+        writer.write_text_element("title", &self.title)?;
+        writer.write_text_element("link", &self.link)?;
 
         if let Some(width) = self.width.as_ref() {
-            writer.write_text_element(b"width", width)?;
+            writer.write_text_element("width", width)?;
         }
 
         if let Some(height) = self.height.as_ref() {
-            writer.write_text_element(b"height", height)?;
+            writer.write_text_element("height", height)?;
         }
 
         if let Some(description) = self.description.as_ref() {
-            writer.write_text_element(b"description", description)?;
+            writer.write_text_element("description", description)?;
         }
 
-        writer.write_event(Event::End(BytesEnd::borrowed(name)))?;
+        writer.write_event(Event::End(BytesEnd::new(name)))?;
         Ok(())
     }
 }
@@ -0,0 +1,476 @@
+// This file is part of rss.
+//
+// Copyright © 2015-2021 The rust-syndication Developers
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the MIT License and/or Apache 2.0 License.
+
+use std::error::Error as StdError;
+use std::fmt;
+use std::io::{self, BufRead, Read};
+#[cfg(feature = "async")]
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+#[cfg(feature = "async")]
+use std::task::{Context, Poll};
+
+use quick_xml::events::Event;
+use quick_xml::Error as XmlError;
+use quick_xml::Reader;
+#[cfg(feature = "async")]
+use tokio::io::{AsyncBufRead, AsyncRead, ReadBuf};
+
+#[cfg(feature = "async")]
+use crate::encoding::Decode;
+use crate::error::{Error, LimitKind};
+use crate::parser::Limits;
+
+/// The error returned by `Input` when the input exceeds one of the limits.
+#[derive(Debug)]
+pub(crate) struct InputLimitExceeded {
+    pub kind: LimitKind,
+    pub limit: usize,
+}
+
+impl fmt::Display for InputLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the {} of the input exceeds {}", self.kind, self.limit)
+    }
+}
+
+impl StdError for InputLimitExceeded {}
+
+/// The line breaks in the part of the input that was consumed so far.
+#[derive(Debug, Default)]
+pub(crate) struct Lines {
+    /// The number of line breaks.
+    count: AtomicUsize,
+    /// The offset at which the current line starts.
+    start: AtomicUsize,
+}
+
+impl Lines {
+    /// Returns the line and column of `offset`, both starting at 1.
+    ///
+    /// `offset` should be on the current line.
+    pub fn position(&self, offset: usize) -> (usize, usize) {
+        let line = self.count.load(Ordering::Relaxed) + 1;
+        let column = offset.saturating_sub(self.start.load(Ordering::Relaxed)) + 1;
+        (line, column)
+    }
+
+    /// Records the line breaks in `bytes`, which start at `offset` in the input.
+    fn record(&self, offset: usize, bytes: &[u8]) {
+        let count = bytes.iter().filter(|&&b| b == b'\n').count();
+        if let Some(last) = bytes.iter().rposition(|&b| b == b'\n') {
+            self.count.fetch_add(count, Ordering::Relaxed);
+            self.start.store(offset + last + 1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Follows the markup in the input to find text, comments and attribute values that are too long
+/// and tags with too many attributes.
+#[derive(Debug)]
+struct Scanner {
+    max_text_len: Option<usize>,
+    max_attributes: Option<usize>,
+    state: ScanState,
+    /// The length of the current text, comment or attribute value.
+    len: usize,
+    /// The number of attributes of the current tag.
+    attributes: usize,
+    exceeded: Option<InputLimitExceeded>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ScanState {
+    Text,
+    /// After a `<`.
+    Open,
+    /// Inside a start or end tag, and inside the attribute value with the given quote.
+    Tag(Option<u8>),
+    /// After `<!` and the given number of bytes, the first of which is given.
+    Bang(usize, u8),
+    /// Inside a comment, CDATA section, processing instruction or document type declaration,
+    /// which ends with the given bytes, of which the given number were found.
+    Markup(&'static [u8], usize),
+}
+
+impl Scanner {
+    fn new(limits: &Limits) -> Option<Self> {
+        if limits.max_text_len.is_none() && limits.max_attributes.is_none() {
+            return None;
+        }
+
+        Some(Scanner {
+            max_text_len: limits.max_text_len,
+            max_attributes: limits.max_attributes,
+            state: ScanState::Text,
+            len: 0,
+            attributes: 0,
+            exceeded: None,
+        })
+    }
+
+    fn scan(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            if self.exceeded.is_some() {
+                return;
+            }
+            self.state = self.next(b);
+        }
+    }
+
+    fn next(&mut self, b: u8) -> ScanState {
+        match self.state {
+            ScanState::Text if b == b'<' => ScanState::Open,
+            ScanState::Text => self.count_text(ScanState::Text),
+            ScanState::Open => {
+                self.len = 0;
+                match b {
+                    b'!' => ScanState::Bang(0, 0),
+                    b'?' => ScanState::Markup(b"?>", 0),
+                    _ => {
+                        self.attributes = 0;
+                        self.state = ScanState::Tag(None);
+                        self.next(b)
+                    }
+                }
+            }
+            ScanState::Tag(None) => match b {
+                b'"' | b'\'' => {
+                    self.len = 0;
+                    ScanState::Tag(Some(b))
+                }
+                b'=' => {
+                    self.attributes += 1;
+                    self.check(LimitKind::Attributes, self.max_attributes, self.attributes);
+                    ScanState::Tag(None)
+                }
+                b'>' => {
+                    self.len = 0;
+                    ScanState::Text
+                }
+                _ => ScanState::Tag(None),
+            },
+            ScanState::Tag(Some(quote)) if b == quote => ScanState::Tag(None),
+            ScanState::Tag(quote) => self.count_text(ScanState::Tag(quote)),
+            ScanState::Bang(0, _) => self.count_text(ScanState::Bang(1, b)),
+            ScanState::Bang(_, first) => {
+                let end: &'static [u8] = match (first, b) {
+                    (b'-', b'-') => b"-->",
+                    (b'[', _) => b"]]>",
+                    _ => b">",
+                };
+                self.state = ScanState::Markup(end, 0);
+                self.next(b)
+            }
+            ScanState::Markup(end, found) => {
+                let found = if b == end[found] {
+                    found + 1
+                } else if b != end[0] {
+                    0
+                } else if found > 1 && end[1] == end[0] {
+                    // `--->` still ends a comment
+                    found
+                } else {
+                    1
+                };
+                if found == end.len() {
+                    self.len = 0;
+                    ScanState::Text
+                } else {
+                    self.count_text(ScanState::Markup(end, found))
+                }
+            }
+        }
+    }
+
+    fn count_text(&mut self, state: ScanState) -> ScanState {
+        self.len += 1;
+        self.check(LimitKind::TextLength, self.max_text_len, self.len);
+        state
+    }
+
+    fn check(&mut self, kind: LimitKind, limit: Option<usize>, value: usize) {
+        match limit {
+            Some(limit) if value > limit => {
+                self.exceeded = Some(InputLimitExceeded { kind, limit });
+            }
+            _ => {}
+        }
+    }
+}
+
+/// A reader that keeps track of the lines in the input and fails once it exceeds the limits on
+/// the length of the input, of text and attribute values, and on the number of attributes.
+///
+/// Checking the input while it is read, rather than after it was parsed, means that a single huge
+/// text node or tag cannot be buffered in full.
+pub(crate) struct Input<R> {
+    inner: R,
+    consumed: usize,
+    limit: Option<usize>,
+    scanner: Option<Scanner>,
+    lines: Arc<Lines>,
+}
+
+impl<R> Input<R> {
+    pub fn new(inner: R, limits: &Limits) -> Self {
+        Input {
+            inner,
+            consumed: 0,
+            limit: limits.max_input_len,
+            scanner: Scanner::new(limits),
+            lines: Arc::default(),
+        }
+    }
+
+    /// Returns the line breaks of the input, which are updated as it is read.
+    pub fn line_tracker(&self) -> Arc<Lines> {
+        Arc::clone(&self.lines)
+    }
+
+    fn check(&mut self) -> io::Result<()> {
+        if let Some(exceeded) = self.scanner.as_mut().and_then(|s| s.exceeded.take()) {
+            return Err(io::Error::other(exceeded));
+        }
+
+        match self.limit {
+            Some(limit) if self.consumed > limit => Err(io::Error::other(InputLimitExceeded {
+                kind: LimitKind::InputLength,
+                limit,
+            })),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<R: BufRead> Read for Input<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.check()?;
+        let len = self.inner.read(buf)?;
+        self.lines.record(self.consumed, &buf[..len]);
+        if let Some(ref mut scanner) = self.scanner {
+            scanner.scan(&buf[..len]);
+        }
+        self.consumed += len;
+        Ok(len)
+    }
+}
+
+impl<R: BufRead> BufRead for Input<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.check()?;
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        if let Ok(buf) = self.inner.fill_buf() {
+            let buf = &buf[..amt.min(buf.len())];
+            self.lines.record(self.consumed, buf);
+            if let Some(ref mut scanner) = self.scanner {
+                scanner.scan(buf);
+            }
+        }
+        self.consumed += amt;
+        self.inner.consume(amt);
+    }
+}
+
+#[cfg(feature = "async")]
+impl<R: AsyncBufRead + Unpin> AsyncRead for Input<Decode<R>> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        this.check()?;
+        let start = buf.filled().len();
+        match Pin::new(&mut this.inner).poll_read(cx, buf) {
+            Poll::Ready(Ok(())) => {
+                let read = &buf.filled()[start..];
+                this.lines.record(this.consumed, read);
+                if let Some(ref mut scanner) = this.scanner {
+                    scanner.scan(read);
+                }
+                this.consumed += read.len();
+                Poll::Ready(Ok(()))
+            }
+            poll => poll,
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<R: AsyncBufRead + Unpin> AsyncBufRead for Input<Decode<R>> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        let this = self.get_mut();
+        this.check()?;
+        Pin::new(&mut this.inner).poll_fill_buf(cx)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.get_mut();
+        let buffered = this.inner.buffered();
+        let buffered = &buffered[..amt.min(buffered.len())];
+        this.lines.record(this.consumed, buffered);
+        if let Some(ref mut scanner) = this.scanner {
+            scanner.scan(buffered);
+        }
+        this.consumed += amt;
+        Pin::new(&mut this.inner).consume(amt);
+    }
+}
+
+/// A position in the input.
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct Position {
+    /// The byte offset in the input.
+    pub offset: usize,
+    /// The line, starting at 1, or 0 if lines are not tracked.
+    pub line: usize,
+    /// The column, starting at 1, or 0 if lines are not tracked.
+    pub column: usize,
+}
+
+/// A source of the XML events of a document, from which everything is read by `Context`.
+///
+/// Events are reported as they appear in the input: text is not trimmed and empty elements are
+/// not expanded. Events of type `'i` borrow from the input when it is kept in memory, and own
+/// their contents otherwise.
+pub(crate) trait XmlSource<'i> {
+    /// Reads the next event.
+    fn read_event(&mut self) -> Result<Event<'i>, Error>;
+
+    /// Returns the position in the input after the event that was read last.
+    fn position(&self) -> Position;
+
+    /// Returns the input if the events borrow from it.
+    fn input(&self) -> Option<&'i [u8]> {
+        None
+    }
+}
+
+/// Reads the events of a document from a string, borrowing them from it.
+pub(crate) struct SliceSource<'i> {
+    reader: Reader<&'i [u8]>,
+    input: &'i [u8],
+    lines: Lines,
+    /// The offset up to which the line breaks were recorded.
+    recorded: usize,
+    limit: Option<usize>,
+}
+
+impl<'i> SliceSource<'i> {
+    /// Reads `input`, failing once more than `limit` bytes have been read.
+    pub fn new(input: &'i str, limit: Option<usize>) -> Self {
+        let mut reader = Reader::from_str(input);
+        reader.check_end_names(false);
+
+        SliceSource {
+            reader,
+            input: input.as_bytes(),
+            lines: Lines::default(),
+            recorded: 0,
+            limit,
+        }
+    }
+}
+
+impl<'i> XmlSource<'i> for SliceSource<'i> {
+    fn read_event(&mut self) -> Result<Event<'i>, Error> {
+        let event = self.reader.read_event();
+
+        let offset = self.reader.buffer_position().min(self.input.len());
+        if offset > self.recorded {
+            self.lines
+                .record(self.recorded, &self.input[self.recorded..offset]);
+            self.recorded = offset;
+        }
+
+        match self.limit {
+            Some(limit) if offset > limit => Err(Error::LimitExceeded {
+                kind: LimitKind::InputLength,
+                limit,
+            }),
+            _ => Ok(event?),
+        }
+    }
+
+    fn position(&self) -> Position {
+        let offset = self.reader.buffer_position();
+        let (line, column) = self.lines.position(offset);
+        Position {
+            offset,
+            line,
+            column,
+        }
+    }
+
+    fn input(&self) -> Option<&'i [u8]> {
+        Some(self.input)
+    }
+}
+
+/// Reads the events of a document from a buffered reader, copying each of them.
+pub(crate) struct BufSource<'r, R> {
+    reader: &'r mut Reader<R>,
+    buf: Vec<u8>,
+    lines: Option<Arc<Lines>>,
+}
+
+impl<'r, R: BufRead> BufSource<'r, R> {
+    /// Reads from `reader`, which keeps track of the line breaks in `lines` if it reads from an
+    /// `Input`.
+    pub fn new(reader: &'r mut Reader<R>, lines: Option<Arc<Lines>>) -> Self {
+        BufSource {
+            reader,
+            buf: Vec::new(),
+            lines,
+        }
+    }
+}
+
+impl<'r, R: BufRead> XmlSource<'static> for BufSource<'r, R> {
+    fn read_event(&mut self) -> Result<Event<'static>, Error> {
+        self.buf.clear();
+        match self.reader.read_event_into(&mut self.buf) {
+            Ok(event) => Ok(event.into_owned()),
+            Err(err) => Err(read_error(err)),
+        }
+    }
+
+    fn position(&self) -> Position {
+        let offset = self.reader.buffer_position();
+        let (line, column) = match self.lines {
+            Some(ref lines) => lines.position(offset),
+            None => (0, 0),
+        };
+        Position {
+            offset,
+            line,
+            column,
+        }
+    }
+}
+
+/// Converts an error of the XML reader, reporting input that exceeds the limits as
+/// `Error::LimitExceeded`.
+pub(crate) fn read_error(err: XmlError) -> Error {
+    if let XmlError::Io(ref io) = err {
+        if let Some(exceeded) = io
+            .get_ref()
+            .and_then(|err| err.downcast_ref::<InputLimitExceeded>())
+        {
+            return Error::LimitExceeded {
+                kind: exceeded.kind,
+                limit: exceeded.limit,
+            };
+        }
+    }
+    err.into()
+}
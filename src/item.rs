@@ -7,26 +7,37 @@
 
 use std::collections::BTreeMap;
 use std::io::{BufRead, Write};
+use std::str;
 
 use quick_xml::events::attributes::Attributes;
 use quick_xml::events::{BytesEnd, BytesStart, Event};
 use quick_xml::Error as XmlError;
 use quick_xml::Reader;
-use quick_xml::Writer;
 
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, FixedOffset, TimeZone};
+
+use crate::borrowed::{read_from_reader, ItemRef};
 use crate::category::Category;
+#[cfg(feature = "chrono")]
+use crate::date::{format_date, parse_date};
 use crate::enclosure::Enclosure;
 use crate::error::Error;
 #[cfg(feature = "atom")]
 use crate::extension::atom;
 use crate::extension::dublincore;
 use crate::extension::itunes;
-use crate::extension::util::{extension_name, parse_extension};
+use crate::extension::util::take_extensions;
 use crate::extension::ExtensionMap;
 use crate::guid::Guid;
+use crate::markup::TextKind;
+use crate::namespaces::Namespaces;
+use crate::person::Person;
 use crate::source::Source;
-use crate::toxml::{ToXml, WriterExt};
-use crate::util::element_text;
+use crate::toxml::{ToXml, WriterExt, XmlWriter};
+use crate::unknown::{UnknownElement, UnknownWriter};
+use crate::util::{CONTENT_NAMESPACE, RDF_NAMESPACE};
+use crate::writer::WriteOptions;
 
 /// Represents an item in an RSS feed.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -47,6 +58,8 @@ pub struct Item {
     pub link: Option<String>,
     /// The item synopsis.
     pub description: Option<String>,
+    /// The kind of text in the description.
+    pub description_kind: TextKind,
     /// The email address of author of the item.
     pub author: Option<String>,
     /// The categories the item belongs to.
@@ -64,6 +77,13 @@ pub struct Item {
     pub source: Option<Source>,
     /// The HTML contents of the item.
     pub content: Option<String>,
+    /// The kind of text in the contents.
+    pub content_kind: TextKind,
+    /// The URI that identifies the item in an RSS 1.0 feed, from its `rdf:about` attribute.
+    pub about: Option<String>,
+    /// The elements without a namespace prefix that are not part of RSS.
+    #[cfg_attr(feature = "builders", builder(setter(each = "unknown_element")))]
+    pub unknown_elements: Vec<UnknownElement>,
     /// The extensions for the item.
     #[cfg_attr(feature = "builders", builder(setter(each = "extension")))]
     pub extensions: ExtensionMap,
@@ -74,6 +94,10 @@ pub struct Item {
     pub itunes_ext: Option<itunes::ITunesItemExtension>,
     /// The Dublin Core extension for the item.
     pub dublin_core_ext: Option<dublincore::DublinCoreExtension>,
+    /// The attributes of the item element that are not part of RSS, such as `xml:lang`, keyed
+    /// by qualified name.
+    #[cfg_attr(feature = "builders", builder(setter(each = "extra_attr")))]
+    pub extra_attrs: BTreeMap<String, String>,
 }
 
 impl Item {
@@ -173,6 +197,37 @@ impl Item {
         self.description = description.into();
     }
 
+    /// Return the kind of text in the description of this item.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::{Item, TextKind};
+    ///
+    /// let mut item = Item::default();
+    /// item.set_description_kind(TextKind::Html);
+    /// assert_eq!(item.description_kind(), TextKind::Html);
+    /// ```
+    pub fn description_kind(&self) -> TextKind {
+        self.description_kind
+    }
+
+    /// Set the kind of text in the description of this item.
+    ///
+    /// Text of the kind `TextKind::Xhtml` is written as raw XML.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::{Item, TextKind};
+    ///
+    /// let mut item = Item::default();
+    /// item.set_description_kind(TextKind::Xhtml);
+    /// ```
+    pub fn set_description_kind(&mut self, description_kind: TextKind) {
+        self.description_kind = description_kind;
+    }
+
     /// Return the email address for the author of this item.
     ///
     /// # Examples
@@ -205,6 +260,46 @@ impl Item {
         self.author = author.into();
     }
 
+    /// Return the author of this item as a `Person`.
+    ///
+    /// Returns `None` if there is no author or it is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::Item;
+    ///
+    /// let mut item = Item::default();
+    /// item.set_author("johndoe@example.com (John Doe)".to_string());
+    /// let person = item.author_person().unwrap();
+    /// assert_eq!(person.name(), Some("John Doe"));
+    /// assert_eq!(person.email(), Some("johndoe@example.com"));
+    /// ```
+    pub fn author_person(&self) -> Option<Person> {
+        let person = Person::parse(self.author.as_deref()?);
+        Some(person).filter(|person| !person.is_empty())
+    }
+
+    /// Set the author of this item to a `Person`.
+    ///
+    /// The person is written in the RSS 2.0 form `email (name)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::{Item, Person};
+    ///
+    /// let mut item = Item::default();
+    /// item.set_author_person(Person::parse("John Doe <johndoe@example.com>"));
+    /// assert_eq!(item.author(), Some("johndoe@example.com (John Doe)"));
+    /// ```
+    pub fn set_author_person<V>(&mut self, author: V)
+    where
+        V: Into<Option<Person>>,
+    {
+        self.author = author.into().map(|person| person.to_string());
+    }
+
     /// Return the categories that this item belongs to.
     ///
     /// # Examples
@@ -368,17 +463,18 @@ impl Item {
     ///
     /// ## Using chrono::DateTime
     /// ```
-    /// # #[cfg(feature = "validation")]
+    /// # #[cfg(feature = "chrono")]
     /// # {
     /// use rss::Item;
     /// use chrono::{FixedOffset, TimeZone, Utc};
     ///
     /// let mut item = Item::default();
-    /// item.set_pub_date(Utc.ymd(2017, 1, 1).and_hms(12, 0, 0).to_rfc2822());
-    /// assert_eq!(item.pub_date(), Some("Sun, 01 Jan 2017 12:00:00 +0000"));
+    /// item.set_pub_date(Utc.with_ymd_and_hms(2017, 1, 1, 12, 0, 0).unwrap().to_rfc2822());
+    /// assert_eq!(item.pub_date(), Some("Sun, 1 Jan 2017 12:00:00 +0000"));
     ///
-    /// item.set_pub_date(FixedOffset::east(2 * 3600).ymd(2017, 1, 1).and_hms(12, 0, 0).to_rfc2822());
-    /// assert_eq!(item.pub_date(), Some("Sun, 01 Jan 2017 12:00:00 +0200"));
+    /// let offset = FixedOffset::east_opt(2 * 3600).unwrap();
+    /// item.set_pub_date(offset.with_ymd_and_hms(2017, 1, 1, 12, 0, 0).unwrap().to_rfc2822());
+    /// assert_eq!(item.pub_date(), Some("Sun, 1 Jan 2017 12:00:00 +0200"));
     /// # }
     /// ```
     pub fn set_pub_date<V>(&mut self, pub_date: V)
@@ -388,6 +484,46 @@ impl Item {
         self.pub_date = pub_date.into();
     }
 
+    /// Return the publication date of this item, parsed as a date.
+    ///
+    /// Besides RFC 2822 timestamps, the common variants of them and ISO 8601 dates are accepted.
+    /// Returns `None` if there is no publication date or it cannot be parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::Item;
+    /// use chrono::{TimeZone, Utc};
+    ///
+    /// let mut item = Item::default();
+    /// item.set_pub_date("Sun, 1 Jan 2017 04:00 PST".to_string());
+    /// assert_eq!(
+    ///     item.pub_date_parsed(),
+    ///     Some(Utc.with_ymd_and_hms(2017, 1, 1, 12, 0, 0).unwrap().into())
+    /// );
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn pub_date_parsed(&self) -> Option<DateTime<FixedOffset>> {
+        self.pub_date.as_deref().and_then(parse_date)
+    }
+
+    /// Set the publication date of this item to a date, formatted as an RFC 2822 timestamp.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::Item;
+    /// use chrono::{TimeZone, Utc};
+    ///
+    /// let mut item = Item::default();
+    /// item.set_pub_date_datetime(Utc.with_ymd_and_hms(2017, 1, 1, 12, 0, 0).unwrap());
+    /// assert_eq!(item.pub_date(), Some("Sun, 1 Jan 2017 12:00:00 +0000"));
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn set_pub_date_datetime<Tz: TimeZone>(&mut self, pub_date: DateTime<Tz>) {
+        self.pub_date = Some(format_date(&pub_date));
+    }
+
     /// Return the source URL for this item.
     ///
     /// # Examples
@@ -452,6 +588,37 @@ impl Item {
         self.content = content.into();
     }
 
+    /// Return the kind of text in the contents of this item.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::{Item, TextKind};
+    ///
+    /// let mut item = Item::default();
+    /// item.set_content_kind(TextKind::Html);
+    /// assert_eq!(item.content_kind(), TextKind::Html);
+    /// ```
+    pub fn content_kind(&self) -> TextKind {
+        self.content_kind
+    }
+
+    /// Set the kind of text in the contents of this item.
+    ///
+    /// Text of the kind `TextKind::Xhtml` is written as raw XML.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::{Item, TextKind};
+    ///
+    /// let mut item = Item::default();
+    /// item.set_content_kind(TextKind::Xhtml);
+    /// ```
+    pub fn set_content_kind(&mut self, content_kind: TextKind) {
+        self.content_kind = content_kind;
+    }
+
     /// Return the Atom extension for this item.
     ///
     /// # Examples
@@ -602,6 +769,112 @@ impl Item {
     {
         self.extensions = extensions.into();
     }
+
+    /// Return the URI that identifies this item in an RSS 1.0 feed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::Item;
+    ///
+    /// let mut item = Item::default();
+    /// item.set_about("http://example.com/item".to_string());
+    /// assert_eq!(item.about(), Some("http://example.com/item"));
+    /// ```
+    pub fn about(&self) -> Option<&str> {
+        self.about.as_deref()
+    }
+
+    /// Set the URI that identifies this item in an RSS 1.0 feed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::Item;
+    ///
+    /// let mut item = Item::default();
+    /// item.set_about("http://example.com/item".to_string());
+    /// ```
+    pub fn set_about<V>(&mut self, about: V)
+    where
+        V: Into<Option<String>>,
+    {
+        self.about = about.into();
+    }
+
+    /// Return the elements without a namespace prefix that are not part of RSS in this item.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::{Item, UnknownElement};
+    ///
+    /// let mut item = Item::default();
+    /// item.set_unknown_elements(vec![UnknownElement::default()]);
+    /// assert_eq!(item.unknown_elements().len(), 1);
+    /// ```
+    pub fn unknown_elements(&self) -> &[UnknownElement] {
+        &self.unknown_elements
+    }
+
+    /// Return a mutable slice of the unknown elements in this item.
+    pub fn unknown_elements_mut(&mut self) -> &mut [UnknownElement] {
+        &mut self.unknown_elements
+    }
+
+    /// Set the elements without a namespace prefix that are not part of RSS in this item.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::{Item, UnknownElement};
+    ///
+    /// let mut item = Item::default();
+    /// item.set_unknown_elements(vec![UnknownElement::default()]);
+    /// ```
+    pub fn set_unknown_elements<V>(&mut self, unknown_elements: V)
+    where
+        V: Into<Vec<UnknownElement>>,
+    {
+        self.unknown_elements = unknown_elements.into();
+    }
+
+    /// Return the attributes of this item that are not part of RSS.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use rss::Item;
+    ///
+    /// let mut extra_attrs = BTreeMap::new();
+    /// extra_attrs.insert("xml:lang".to_string(), "en".to_string());
+    ///
+    /// let mut item = Item::default();
+    /// item.set_extra_attrs(extra_attrs);
+    /// assert_eq!(item.extra_attrs()["xml:lang"], "en");
+    /// ```
+    pub fn extra_attrs(&self) -> &BTreeMap<String, String> {
+        &self.extra_attrs
+    }
+
+    /// Set the attributes of this item that are not part of RSS.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use rss::Item;
+    ///
+    /// let mut item = Item::default();
+    /// item.set_extra_attrs(BTreeMap::new());
+    /// ```
+    pub fn set_extra_attrs<V>(&mut self, extra_attrs: V)
+    where
+        V: Into<BTreeMap<String, String>>,
+    {
+        self.extra_attrs = extra_attrs.into();
+    }
 }
 
 impl Item {
@@ -609,142 +882,130 @@ impl Item {
     pub fn from_xml<R: BufRead>(
         namespaces: &BTreeMap<String, String>,
         reader: &mut Reader<R>,
-        _: Attributes,
+        atts: Attributes,
     ) -> Result<Self, Error> {
-        let mut item = Item::default();
-        let mut buf = Vec::new();
-
-        loop {
-            match reader.read_event(&mut buf)? {
-                Event::Start(element) => match element.name() {
-                    b"category" => {
-                        let category = Category::from_xml(reader, element.attributes())?;
-                        item.categories.push(category);
-                    }
-                    b"guid" => {
-                        let guid = Guid::from_xml(reader, element.attributes())?;
-                        item.guid = Some(guid);
-                    }
-                    b"enclosure" => {
-                        let enclosure = Enclosure::from_xml(reader, element.attributes())?;
-                        item.enclosure = Some(enclosure);
-                    }
-                    b"source" => {
-                        let source = Source::from_xml(reader, element.attributes())?;
-                        item.source = Some(source);
-                    }
-                    b"title" => item.title = element_text(reader)?,
-                    b"link" => item.link = element_text(reader)?,
-                    b"description" => item.description = element_text(reader)?,
-                    b"author" => item.author = element_text(reader)?,
-                    b"comments" => item.comments = element_text(reader)?,
-                    b"pubDate" => item.pub_date = element_text(reader)?,
-                    b"content:encoded" => item.content = element_text(reader)?,
-                    n => {
-                        if let Some((ns, name)) = extension_name(n) {
-                            parse_extension(
-                                reader,
-                                element.attributes(),
-                                ns,
-                                name,
-                                &mut item.extensions,
-                            )?;
-                        } else {
-                            reader.read_to_end(n, &mut Vec::new())?;
-                        }
-                    }
-                },
-                Event::End(_) => break,
-                Event::Eof => return Err(Error::Eof),
-                _ => {}
-            }
-            buf.clear();
+        read_from_reader(reader, |parser| parser.read_item(namespaces, atts))
+            .map(ItemRef::into_owned)
+    }
+
+    /// Moves the extensions with a known namespace into their typed fields.
+    ///
+    /// `extension_namespaces` maps the prefixes of the extensions to the namespaces they were
+    /// bound to when they were read.
+    pub(crate) fn parse_extensions(&mut self, extension_namespaces: &BTreeMap<String, String>) {
+        if self.extensions.is_empty() {
+            return;
         }
 
-        if !item.extensions.is_empty() {
-            // Process each of the namespaces we know (note that the values are not removed prior and reused to support pass-through of unknown extensions)
-            for (prefix, namespace) in namespaces {
-                match namespace.as_ref() {
-                    #[cfg(feature = "atom")]
-                    atom::NAMESPACE => item
-                        .extensions
-                        .remove(prefix)
-                        .map(|v| item.atom_ext = Some(atom::AtomExtension::from_map(v))),
-                    itunes::NAMESPACE => item
-                        .extensions
-                        .remove(prefix)
-                        .map(|v| item.itunes_ext = Some(itunes::ITunesItemExtension::from_map(v))),
-                    dublincore::NAMESPACE => item.extensions.remove(prefix).map(|v| {
-                        item.dublin_core_ext = Some(dublincore::DublinCoreExtension::from_map(v))
-                    }),
-                    _ => None,
-                };
-            }
+        // Extensions in other namespaces are left in place to support pass-through of unknown
+        // extensions
+        #[cfg(feature = "atom")]
+        if let Some(map) =
+            take_extensions(&mut self.extensions, extension_namespaces, atom::NAMESPACE)
+        {
+            self.atom_ext = Some(atom::AtomExtension::from_map(map));
         }
 
-        Ok(item)
+        if let Some(map) = take_extensions(
+            &mut self.extensions,
+            extension_namespaces,
+            itunes::NAMESPACE,
+        ) {
+            self.itunes_ext = Some(itunes::ITunesItemExtension::from_map(map));
+        }
+
+        if let Some(map) = take_extensions(
+            &mut self.extensions,
+            extension_namespaces,
+            dublincore::NAMESPACE,
+        ) {
+            self.dublin_core_ext = Some(dublincore::DublinCoreExtension::from_map(map));
+        }
     }
-}
 
-impl ToXml for Item {
-    fn to_xml<W: Write>(&self, writer: &mut Writer<W>) -> Result<(), XmlError> {
-        let name = b"item";
+    /// Writes this item as an RSS 1.0 item, leaving out the elements RSS 1.0 does not have.
+    pub(crate) fn write_rdf<W: Write>(
+        &self,
+        namespaces: &Namespaces,
+        options: &WriteOptions,
+        writer: &mut XmlWriter<W>,
+    ) -> Result<(), XmlError> {
+        let name = "item";
+        let mut element = BytesStart::new(name);
+        element.push_attribute(("rdf:about", self.rdf_about()));
+        element.extend_attributes(
+            self.extra_attrs
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str())),
+        );
+        writer.write_event(Event::Start(element))?;
 
-        writer.write_event(Event::Start(BytesStart::borrowed(name, name.len())))?;
+        let mut unknown = UnknownWriter::new(&self.unknown_elements);
+        unknown.write_after(writer, None)?;
 
         if let Some(title) = self.title.as_ref() {
-            writer.write_text_element(b"title", title)?;
+            writer.write_text_element("title", title)?;
         }
+        unknown.write_after(writer, Some("title"))?;
 
         if let Some(link) = self.link.as_ref() {
-            writer.write_text_element(b"link", link)?;
+            writer.write_text_element("link", link)?;
         }
+        unknown.write_after(writer, Some("link"))?;
 
         if let Some(description) = self.description.as_ref() {
-            writer.write_cdata_element(b"description", description)?;
-        }
-
-        if let Some(author) = self.author.as_ref() {
-            writer.write_text_element(b"author", author)?;
-        }
-
-        writer.write_objects(&self.categories)?;
-
-        if let Some(comments) = self.comments.as_ref() {
-            writer.write_text_element(b"comments", comments)?;
+            match self.description_kind {
+                TextKind::Xhtml => writer.write_raw_element("description", description)?,
+                _ => writer.write_styled_element(
+                    "description",
+                    description,
+                    options.item_description,
+                )?,
+            }
         }
+        unknown.write_after(writer, Some("description"))?;
 
-        if let Some(enclosure) = self.enclosure.as_ref() {
-            writer.write_object(enclosure)?;
+        if let Some(content) = self.content.as_ref() {
+            match self.content_kind {
+                TextKind::Xhtml => writer.write_raw_element("content:encoded", content)?,
+                _ => writer.write_styled_element("content:encoded", content, options.content)?,
+            }
         }
 
-        if let Some(guid) = self.guid.as_ref() {
-            writer.write_object(guid)?;
-        }
+        unknown.write_rest(writer)?;
 
-        if let Some(pub_date) = self.pub_date.as_ref() {
-            writer.write_text_element(b"pubDate", pub_date)?;
-        }
+        self.write_extensions(namespaces, writer)?;
 
-        if let Some(source) = self.source.as_ref() {
-            writer.write_object(source)?;
-        }
+        writer.write_event(Event::End(BytesEnd::new(name)))?;
+        Ok(())
+    }
 
-        if let Some(content) = self.content.as_ref() {
-            writer.write_cdata_element(b"content:encoded", content)?;
-        }
+    /// The URI that identifies this item in an RSS 1.0 feed, which defaults to its link.
+    pub(crate) fn rdf_about(&self) -> &str {
+        self.about
+            .as_deref()
+            .or(self.link.as_deref())
+            .unwrap_or_default()
+    }
 
+    /// Writes the extensions of this item. `namespaces` are the namespaces declared for the
+    /// document, which decide the prefixes the extensions are written with.
+    fn write_extensions<W: Write>(
+        &self,
+        namespaces: &Namespaces,
+        writer: &mut XmlWriter<W>,
+    ) -> Result<(), XmlError> {
         for map in self.extensions.values() {
             for extensions in map.values() {
                 for extension in extensions {
-                    extension.to_xml(writer)?;
+                    extension.write_xml(namespaces, writer)?;
                 }
             }
         }
 
         #[cfg(feature = "atom")]
         if let Some(ext) = self.atom_ext.as_ref() {
-            ext.to_xml(writer)?;
+            ext.to_xml(namespaces.declared(), writer)?;
         }
 
         if let Some(ext) = self.itunes_ext.as_ref() {
@@ -755,17 +1016,26 @@ impl ToXml for Item {
             ext.to_xml(writer)?;
         }
 
-        writer.write_event(Event::End(BytesEnd::borrowed(name)))?;
         Ok(())
     }
+}
+
+impl ToXml for Item {
+    fn to_xml<W: Write>(&self, writer: &mut XmlWriter<W>) -> Result<(), XmlError> {
+        self.write_xml(&Namespaces::default(), &WriteOptions::default(), writer)
+    }
 
     fn used_namespaces(&self) -> BTreeMap<String, String> {
         let mut namespaces = BTreeMap::new();
         if self.content.is_some() {
-            namespaces.insert(
-                "content".to_owned(),
-                "http://purl.org/rss/1.0/modules/content/".to_owned(),
-            );
+            namespaces.insert("content".to_owned(), CONTENT_NAMESPACE.to_owned());
+        }
+        if self.about.is_some() {
+            namespaces.insert("rdf".to_owned(), RDF_NAMESPACE.to_owned());
+        }
+        #[cfg(feature = "atom")]
+        if self.atom_ext.is_some() {
+            namespaces.insert("atom".to_owned(), atom::NAMESPACE.to_owned());
         }
         if let Some(ext) = self.itunes_ext() {
             namespaces.extend(ext.used_namespaces());
@@ -777,6 +1047,103 @@ impl ToXml for Item {
     }
 }
 
+impl Item {
+    /// Writes this item, using the namespaces declared for the document to decide the prefixes
+    /// of its extensions.
+    pub(crate) fn write_xml<W: Write>(
+        &self,
+        namespaces: &Namespaces,
+        options: &WriteOptions,
+        writer: &mut XmlWriter<W>,
+    ) -> Result<(), XmlError> {
+        let name = "item";
+        let mut element = BytesStart::new(name);
+        if let Some(about) = self.about.as_ref() {
+            element.push_attribute(("rdf:about", about.as_str()));
+        }
+
+        element.extend_attributes(
+            self.extra_attrs
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str())),
+        );
+
+        writer.write_event(Event::Start(element))?;
+
+        let mut unknown = UnknownWriter::new(&self.unknown_elements);
+        unknown.write_after(writer, None)?;
+
+        if let Some(title) = self.title.as_ref() {
+            writer.write_text_element("title", title)?;
+        }
+        unknown.write_after(writer, Some("title"))?;
+
+        if let Some(link) = self.link.as_ref() {
+            writer.write_text_element("link", link)?;
+        }
+        unknown.write_after(writer, Some("link"))?;
+
+        if let Some(description) = self.description.as_ref() {
+            match self.description_kind {
+                TextKind::Xhtml => writer.write_raw_element("description", description)?,
+                _ => writer.write_styled_element(
+                    "description",
+                    description,
+                    options.item_description,
+                )?,
+            }
+        }
+        unknown.write_after(writer, Some("description"))?;
+
+        if let Some(author) = self.author.as_ref() {
+            writer.write_text_element("author", author)?;
+        }
+        unknown.write_after(writer, Some("author"))?;
+
+        writer.write_objects(&self.categories)?;
+        unknown.write_after(writer, Some("category"))?;
+
+        if let Some(comments) = self.comments.as_ref() {
+            writer.write_text_element("comments", comments)?;
+        }
+        unknown.write_after(writer, Some("comments"))?;
+
+        if let Some(enclosure) = self.enclosure.as_ref() {
+            writer.write_object(enclosure)?;
+        }
+        unknown.write_after(writer, Some("enclosure"))?;
+
+        if let Some(guid) = self.guid.as_ref() {
+            writer.write_object(guid)?;
+        }
+        unknown.write_after(writer, Some("guid"))?;
+
+        if let Some(pub_date) = self.pub_date.as_ref() {
+            writer.write_text_element("pubDate", pub_date)?;
+        }
+        unknown.write_after(writer, Some("pubDate"))?;
+
+        if let Some(source) = self.source.as_ref() {
+            writer.write_object(source)?;
+        }
+        unknown.write_after(writer, Some("source"))?;
+
+        if let Some(content) = self.content.as_ref() {
+            match self.content_kind {
+                TextKind::Xhtml => writer.write_raw_element("content:encoded", content)?,
+                _ => writer.write_styled_element("content:encoded", content, options.content)?,
+            }
+        }
+
+        unknown.write_rest(writer)?;
+
+        self.write_extensions(namespaces, writer)?;
+
+        writer.write_event(Event::End(BytesEnd::new(name)))?;
+        Ok(())
+    }
+}
+
 #[cfg(feature = "builders")]
 impl ItemBuilder {
     /// Builds a new `Item`.
@@ -43,6 +43,117 @@
 //! }
 //! ```
 //!
+//! ## Item by item
+//!
+//! A `ChannelReader` reads the channel header up front and then returns the items one at a time,
+//! so large feeds can be processed without holding every item in memory.
+//!
+//! ```rust,no_run
+//! use std::fs::File;
+//! use std::io::BufReader;
+//! use rss::ChannelReader;
+//!
+//! let file = File::open("example.xml").unwrap();
+//! let mut reader = ChannelReader::new(BufReader::new(file)).unwrap();
+//! println!("{}", reader.channel().title());
+//! for item in reader {
+//!     let item = item.unwrap();
+//!     println!("{:?}", item.title());
+//! }
+//! ```
+//!
+//! ## Borrowing
+//!
+//! `ChannelRef::parse` reads a channel from a string without copying text that contains no
+//! entities. The returned `ChannelRef` and its `ItemRef`s borrow from the input, and
+//! `into_owned` converts them to a `Channel` and its `Item`s.
+//!
+//! ```rust
+//! use rss::ChannelRef;
+//!
+//! let input = "<rss><channel><title>Title</title></channel></rss>";
+//! let channel = ChannelRef::parse(input).unwrap();
+//! assert_eq!(channel.title, "Title");
+//! let channel = channel.into_owned();
+//! ```
+//!
+//! ## Lenient parsing
+//!
+//! By default, reading fails at the first malformed element. With `ParseOptions::lenient`
+//! enabled, mismatched end tags, unclosed elements, truncated input and invalid entities are
+//! repaired instead, and each repair is reported as a `ParseWarning`.
+//!
+//! ```rust,no_run
+//! use std::fs::File;
+//! use std::io::BufReader;
+//! use rss::{Channel, ParseOptions};
+//!
+//! let file = File::open("example.xml").unwrap();
+//! let options = ParseOptions {
+//!     lenient: true,
+//!     ..Default::default()
+//! };
+//! let parsed = Channel::read_from_with_options(BufReader::new(file), options).unwrap();
+//! for warning in &parsed.warnings {
+//!     println!("{}", warning);
+//! }
+//! let channel = parsed.channel;
+//! ```
+//!
+//! ## Limits
+//!
+//! When reading feeds from untrusted sources, `ParseOptions::limits` bounds the nesting depth,
+//! the number of items, the length of text, the size of extension elements and the length of
+//! the input. Exceeding a limit fails with `Error::LimitExceeded`.
+//!
+//! ## Encodings
+//!
+//! The input is converted to UTF-8 from the encoding named by its byte order mark, the charset
+//! in `ParseOptions::charset`, or its XML declaration, in that order. Without any of these, it
+//! is read as UTF-8 if it is valid UTF-8 and as Windows-1252 otherwise. The encoding that was
+//! used is returned in `ParsedChannel::encoding`.
+//!
+//! ```rust
+//! use rss::{Channel, ParseOptions};
+//!
+//! let input = b"<rss><channel><title>Caf\xE9</title></channel></rss>";
+//! let options = ParseOptions {
+//!     charset: Some("iso-8859-1".to_string()),
+//!     ..Default::default()
+//! };
+//! let parsed = Channel::read_from_with_options(&input[..], options).unwrap();
+//! assert_eq!(parsed.channel.title(), "Café");
+//! assert_eq!(parsed.encoding, "windows-1252");
+//! ```
+//!
+//! ## Markup
+//!
+//! Only the text of a `description` or `content:encoded` element is read by default, so inline
+//! XHTML such as `<description><p>Hello <b>world</b></p></description>` is lost. With
+//! `ParseOptions::preserve_markup` enabled, these elements and extension elements that mix text
+//! and elements are read as their raw inner XML, and the kind of their text is recorded as a
+//! `TextKind`. Text of the kind `TextKind::Xhtml` is written back as it was read.
+//!
+//! ```rust
+//! use rss::{Channel, ParseOptions, TextKind};
+//!
+//! let input = "<rss><channel><item><description><p>Hello <b>world</b></p></description></item></channel></rss>";
+//! let options = ParseOptions {
+//!     preserve_markup: true,
+//!     ..Default::default()
+//! };
+//! let channel = Channel::read_from_with_options(input.as_bytes(), options).unwrap().channel;
+//! let item = &channel.items()[0];
+//! assert_eq!(item.description(), Some("<p>Hello <b>world</b></p>"));
+//! assert_eq!(item.description_kind(), TextKind::Xhtml);
+//! ```
+//!
+//! ## Asynchronously
+//!
+//! With the `async` feature enabled, a channel can also be read from any object that
+//! implements tokio's `AsyncBufRead` trait using `Channel::read_from_async`, or item by item
+//! using `AsyncChannelReader`. The input is parsed as it arrives.
+//!
 //! # Writing
 //!
 //! A channel can be written to any object that implements the `Write` trait or converted to an
@@ -58,6 +169,110 @@
 //! let string = channel.to_string(); // convert the channel to a string
 //! ```
 //!
+//! ## RSS 1.0
+//!
+//! Channels are written as RSS 2.0 by default. `Channel::write_rdf_to` writes an RSS 1.0
+//! document instead, which lists the items in an `rdf:Seq` and identifies the channel, the items,
+//! the image and the text input by their `rdf:about` URIs. The `about` URIs of an RSS 1.0 document
+//! are kept when it is read, and its items are ordered as in its `rdf:Seq`.
+//!
+//! ```rust
+//! use rss::Channel;
+//!
+//! let input = include_str!("../tests/data/rss1.xml");
+//! let channel = Channel::read_from(input.as_bytes()).unwrap();
+//! assert_eq!(channel.about(), Some("http://www.xml.com/xml/news.rss"));
+//!
+//! let output = channel.write_rdf_to(Vec::new()).unwrap();
+//! assert_eq!(Channel::read_from(&output[..]).unwrap(), channel);
+//! ```
+//!
+//! ## Versions
+//!
+//! `Channel::write_with_options` writes a channel as RSS 0.91, 0.92, 1.0 or 2.0. Elements that
+//! the version does not support are left out, or written as an equivalent element where there is
+//! one, and each element that was left out is reported as a `WriteWarning`.
+//!
+//! ```rust
+//! use rss::{Channel, RssVersion, WriteOptions};
+//!
+//! let input = include_str!("../tests/data/rss2sample.xml");
+//! let channel = Channel::read_from(input.as_bytes()).unwrap();
+//!
+//! let options = WriteOptions {
+//!     version: RssVersion::V0_91,
+//!     ..Default::default()
+//! };
+//! let (output, warnings) = channel.write_with_options(Vec::new(), &options).unwrap();
+//! for warning in warnings {
+//!     println!("{}", warning);
+//! }
+//! ```
+//!
+//! ## Output
+//!
+//! `WriteOptions` also control how the document is written: its indentation, the XML declaration
+//! and encoding, `<?xml-stylesheet?>` processing instructions, whether descriptions and
+//! `content:encoded` are written as CDATA sections or escaped text, and whether elements without
+//! content are self-closing.
+//!
+//! ```rust
+//! use rss::{Channel, EmptyElements, Stylesheet, TextStyle, WriteOptions};
+//!
+//! let input = include_str!("../tests/data/rss2sample.xml");
+//! let channel = Channel::read_from(input.as_bytes()).unwrap();
+//!
+//! let options = WriteOptions {
+//!     indent: Some((b' ', 2)),
+//!     encoding: Some("iso-8859-1".to_string()),
+//!     stylesheets: vec![Stylesheet::xsl("/feed.xsl")],
+//!     item_description: TextStyle::Escaped,
+//!     empty_elements: Some(EmptyElements::Expanded),
+//!     ..Default::default()
+//! };
+//! let (output, _) = channel.write_with_options(Vec::new(), &options).unwrap();
+//! assert!(output.starts_with(b"<?xml version=\"1.0\" encoding=\"windows-1252\"?>"));
+//! ```
+//!
+//! ## Writing item by item
+//!
+//! A `ChannelWriter` writes the channel header and then the items one at a time, so large feeds
+//! can be written without holding every item in memory. The namespaces that the items use are
+//! declared up front with `register_namespace` or `register_item`, or else by the items that use
+//! them.
+//!
+//! ```rust
+//! use rss::extension::dublincore::NAMESPACE;
+//! use rss::{ChannelBuilder, ChannelWriter, ItemBuilder};
+//!
+//! let channel = ChannelBuilder::default().title("Title").build();
+//! let mut writer = ChannelWriter::new(Vec::new(), &channel);
+//! writer.register_namespace("dc", NAMESPACE).unwrap();
+//! for title in &["First", "Second"] {
+//!     let item = ItemBuilder::default().title(title.to_string()).build();
+//!     writer.write_item(&item).unwrap();
+//! }
+//! let output = writer.finish().unwrap();
+//! ```
+//!
+//! ## Canonical form
+//!
+//! `Channel::write_canonical` writes a normalised form of a channel that is the same, byte for
+//! byte, for channels that differ only in formatting: attributes are sorted, text is escaped the
+//! same way, whitespace is normalised and, with the `chrono` feature, dates are written in UTC in a
+//! single format. It is meant for stable diffs, and `Channel::content_hash` hashes it to detect
+//! when a feed has changed.
+//!
+//! ```rust
+//! use rss::Channel;
+//!
+//! let input = include_str!("../tests/data/rss2sample.xml");
+//! let channel = Channel::read_from(input.as_bytes()).unwrap();
+//!
+//! let canonical = channel.write_canonical(Vec::new()).unwrap();
+//! let hash = channel.content_hash().unwrap();
+//! ```
+//!
 //! # Creation
 //!
 //! Builder methods are provided to assist in the creation of channels.
@@ -74,6 +289,32 @@
 //!     .build();
 //! ```
 //!
+//! ## Dates
+//!
+//! With the `chrono` feature enabled, which the `validation` feature enables as well, dates can be
+//! read and written as `chrono::DateTime` values. `Channel::pub_date_parsed`,
+//! `Channel::last_build_date_parsed` and `Item::pub_date_parsed` accept RFC 2822 timestamps along
+//! with their common variants, such as named time zones, missing seconds and two-digit years, and
+//! ISO 8601 dates. `set_pub_date_datetime` and `set_last_build_date_datetime` format a date as an
+//! RFC 2822 timestamp.
+//!
+//! The scheduling hints of a channel can be read as typed values as well: `Channel::ttl_duration`
+//! returns the time to live as a `Duration` and `Channel::skip_hours_set` returns the hours in
+//! `skipHours` as an `HourSet`. With the `chrono` feature, `Channel::skip_days_set` returns the
+//! days in `skipDays` as a `DaySet` of weekdays, and `Channel::is_skipped_at` tells whether a
+//! refresh at a given time can be skipped.
+//!
+//! Enclosures have typed accessors too: `Enclosure::length_parsed` returns the length as a number
+//! of bytes and `Enclosure::media_class` tells whether the enclosure is audio, video or an image.
+//! With the `mime` feature, `Enclosure::mime_type_parsed` returns the MIME type as a `mime::Mime`,
+//! and with the `url` feature, `Enclosure::url_resolved` resolves the URL against a base such as
+//! the link of the channel. Both features are enabled by the `validation` feature.
+//!
+//! `Channel::managing_editor_person`, `Channel::webmaster_person` and `Item::author_person`
+//! return these values as a `Person` with a name and an email address, reading forms such as
+//! `email (name)`, `name <email>` and a bare name. The matching setters write the RSS 2.0 form
+//! `email (name)`.
+//!
 //! ## Validation
 //!
 //! Validation methods are provided to validate the contents of a channel against the
@@ -106,15 +347,29 @@ extern crate serde;
 #[cfg(feature = "validation")]
 extern crate url;
 
+mod borrowed;
+mod canonical;
 mod category;
 mod channel;
 mod cloud;
+#[cfg(feature = "chrono")]
+mod date;
 mod enclosure;
+mod encoding;
 mod guid;
 mod image;
+mod input;
 mod item;
+mod markup;
+mod namespaces;
+mod parser;
+mod person;
+mod reader;
+mod schedule;
 mod source;
 mod textinput;
+mod unknown;
+mod writer;
 
 mod error;
 mod toxml;
@@ -127,6 +382,10 @@ pub mod extension;
 #[cfg(feature = "validation")]
 pub mod validation;
 
+pub use crate::borrowed::{
+    CategoryRef, ChannelRef, CloudRef, EnclosureRef, ExtensionMapRef, ExtensionRef, GuidRef,
+    ImageRef, ItemRef, SourceRef, TextInputRef, UnknownElementRef,
+};
 pub use crate::category::Category;
 #[cfg(feature = "builders")]
 pub use crate::category::CategoryBuilder;
@@ -136,9 +395,9 @@ pub use crate::channel::ChannelBuilder;
 pub use crate::cloud::Cloud;
 #[cfg(feature = "builders")]
 pub use crate::cloud::CloudBuilder;
-pub use crate::enclosure::Enclosure;
 #[cfg(feature = "builders")]
 pub use crate::enclosure::EnclosureBuilder;
+pub use crate::enclosure::{Enclosure, MediaClass};
 pub use crate::guid::Guid;
 #[cfg(feature = "builders")]
 pub use crate::guid::GuidBuilder;
@@ -148,11 +407,26 @@ pub use crate::image::ImageBuilder;
 pub use crate::item::Item;
 #[cfg(feature = "builders")]
 pub use crate::item::ItemBuilder;
+pub use crate::markup::TextKind;
+pub use crate::parser::{Limits, ParseOptions, ParseWarning, ParsedChannel};
+pub use crate::person::Person;
+#[cfg(feature = "async")]
+pub use crate::reader::AsyncChannelReader;
+pub use crate::reader::ChannelReader;
+#[cfg(feature = "chrono")]
+pub use crate::schedule::DaySet;
+pub use crate::schedule::HourSet;
 pub use crate::source::Source;
 #[cfg(feature = "builders")]
 pub use crate::source::SourceBuilder;
 pub use crate::textinput::TextInput;
 #[cfg(feature = "builders")]
 pub use crate::textinput::TextInputBuilder;
+pub use crate::unknown::UnknownElement;
+#[cfg(feature = "builders")]
+pub use crate::unknown::UnknownElementBuilder;
+pub use crate::writer::{
+    ChannelWriter, EmptyElements, RssVersion, Stylesheet, TextStyle, WriteOptions, WriteWarning,
+};
 
-pub use crate::error::Error;
+pub use crate::error::{Error, LimitKind, Location, ParseError};
@@ -0,0 +1,130 @@
+// This file is part of rss.
+//
+// Copyright © 2015-2021 The rust-syndication Developers
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the MIT License and/or Apache 2.0 License.
+
+use std::str;
+
+use quick_xml::events::{BytesStart, Event};
+
+use crate::error::Error;
+
+/// The kind of text in an element that may contain markup, such as a `description`.
+///
+/// Text is only classified when it is read with `ParseOptions::preserve_markup`, and is `Plain`
+/// otherwise.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextKind {
+    /// Text without markup.
+    #[default]
+    Plain,
+    /// Escaped HTML, or HTML in a CDATA section. The text is the HTML.
+    Html,
+    /// Markup that is part of the XML document. The text is the raw XML inside the element, and
+    /// is written back without escaping.
+    Xhtml,
+}
+
+/// The contents of an element that is read with `ParseOptions::preserve_markup`, collected as
+/// raw XML as well as text.
+#[derive(Debug, Default)]
+pub(crate) struct Markup {
+    raw: Vec<u8>,
+    text: String,
+    has_text: bool,
+    has_elements: bool,
+}
+
+impl Markup {
+    /// Appends an event that was read inside the element as raw XML.
+    pub fn push_event(&mut self, event: &Event) {
+        match event {
+            Event::Start(element) => {
+                self.has_elements = true;
+                self.push_tag(element, b">");
+            }
+            Event::Empty(element) => {
+                self.has_elements = true;
+                self.push_tag(element, b"/>");
+            }
+            Event::End(element) => {
+                self.raw.extend_from_slice(b"</");
+                self.raw.extend_from_slice(element.name().into_inner());
+                self.raw.push(b'>');
+            }
+            Event::Text(text) => self.raw.extend_from_slice(text),
+            Event::CData(text) => self.push_wrapped(b"<![CDATA[", text, b"]]>"),
+            Event::Comment(text) => self.push_wrapped(b"<!--", text, b"-->"),
+            Event::PI(text) => self.push_wrapped(b"<?", text, b"?>"),
+            _ => {}
+        }
+    }
+
+    /// Appends the unescaped contents of a text or CDATA event that was read directly inside the
+    /// element.
+    pub fn push_text(&mut self, text: &str) {
+        self.has_text = self.has_text || !text.trim().is_empty();
+        self.text.push_str(text);
+    }
+
+    /// Appends a child element whose contents were collected in `inner`.
+    pub fn push_child(&mut self, element: &BytesStart, inner: &Markup) {
+        self.has_elements = true;
+        self.push_tag(element, b">");
+        self.raw.extend_from_slice(&inner.raw);
+        self.raw.extend_from_slice(b"</");
+        self.raw.extend_from_slice(element.name().into_inner());
+        self.raw.push(b'>');
+    }
+
+    fn push_tag(&mut self, element: &BytesStart, close: &[u8]) {
+        self.raw.push(b'<');
+        self.raw.extend_from_slice(element);
+        self.raw.extend_from_slice(close);
+    }
+
+    fn push_wrapped(&mut self, open: &[u8], text: &[u8], close: &[u8]) {
+        self.raw.extend_from_slice(open);
+        self.raw.extend_from_slice(text);
+        self.raw.extend_from_slice(close);
+    }
+
+    /// Returns the contents of an element that holds text, such as a `description`: its raw XML
+    /// if it contains elements, and its text otherwise.
+    pub fn text(&self) -> Result<(Option<String>, TextKind), Error> {
+        if self.has_elements {
+            self.raw()
+        } else {
+            Ok(self.plain())
+        }
+    }
+
+    /// Returns the contents of an extension element: its raw XML if it contains both text and
+    /// elements, and its text otherwise.
+    pub fn value(&self) -> Result<(Option<String>, TextKind), Error> {
+        if self.has_elements && self.has_text {
+            self.raw()
+        } else {
+            Ok(self.plain())
+        }
+    }
+
+    fn raw(&self) -> Result<(Option<String>, TextKind), Error> {
+        let raw = str::from_utf8(&self.raw)?;
+        Ok((Some(raw.trim().to_string()), TextKind::Xhtml))
+    }
+
+    fn plain(&self) -> (Option<String>, TextKind) {
+        let text = self.text.trim();
+        if text.is_empty() {
+            (None, TextKind::Plain)
+        } else if text.contains('<') {
+            (Some(text.to_string()), TextKind::Html)
+        } else {
+            (Some(text.to_string()), TextKind::Plain)
+        }
+    }
+}
@@ -0,0 +1,501 @@
+// This file is part of rss.
+//
+// Copyright © 2015-2021 The rust-syndication Developers
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the MIT License and/or Apache 2.0 License.
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::channel::Channel;
+use crate::extension::util::known_namespace;
+use crate::extension::{syndication, Extension, ExtensionMap};
+use crate::item::Item;
+use crate::toxml::ToXml;
+use crate::writer::{item_path, WriteWarning};
+
+/// The namespace a prefix is bound to when it is not bound to a namespace anywhere in the channel
+/// and is not the prefix of a known extension. The prefix is appended to it.
+const UNDECLARED_NAMESPACE: &str = "urn:x-rss:undeclared:";
+
+/// The namespaces of a document that is being written.
+///
+/// Every prefix that is used by an element or attribute of the document is declared, so that the
+/// document is namespace-well-formed:
+///
+/// - the typed extensions that are always written with the same prefix, such as `itunes` and
+///   `dc`, are declared with that prefix;
+/// - the namespaces of `Channel::namespaces` are declared with their own prefix, unless it is
+///   one of the prefixes above and is bound to another namespace, in which case the namespace is
+///   declared with a new prefix that the generic extensions with that prefix are written with;
+/// - the typed extensions that can be written with any prefix, Atom and Syndication, use a prefix
+///   that is already bound to their namespace, or their conventional prefix if it is free;
+/// - the other prefixes of generic extensions and attributes are declared with the namespace
+///   conventionally bound to them, unless the element that uses them declares them itself;
+/// - the prefixes that are left are not bound to a namespace anywhere, and are declared with a
+///   placeholder namespace, which is reported as a `WriteWarning`.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct Namespaces {
+    /// The namespaces to declare on the root element, keyed by prefix.
+    declared: BTreeMap<String, String>,
+    /// The prefixes of `Channel::namespaces` that were replaced to avoid a conflict, keyed by
+    /// their original prefix.
+    renamed: BTreeMap<String, String>,
+    /// The namespaces that an item declares itself because they are not declared on the root
+    /// element, keyed by prefix.
+    local: BTreeMap<String, String>,
+    /// The warnings for the prefixes that were declared with a placeholder namespace.
+    warnings: Vec<WriteWarning>,
+}
+
+impl Namespaces {
+    /// Works out the namespaces to declare for `channel`.
+    pub fn for_channel(channel: &Channel) -> Self {
+        Namespaces::for_channel_with(channel, BTreeMap::new())
+    }
+
+    /// Works out the namespaces to declare for `channel` and for the typed extensions of the
+    /// items that are written after it, whose namespaces are `registered`, keyed by prefix.
+    pub fn for_channel_with(channel: &Channel, registered: BTreeMap<String, String>) -> Self {
+        let mut typed = channel.used_namespaces();
+        for (prefix, namespace) in registered {
+            typed.entry(prefix).or_insert(namespace);
+        }
+        let used = used_prefixes(channel);
+        let prefixes = used.keys().cloned().collect();
+        let mut namespaces = Namespaces::default();
+
+        let (flexible, fixed): (Vec<_>, Vec<_>) = typed
+            .into_iter()
+            .partition(|(_, namespace)| has_any_prefix(namespace));
+        namespaces.declared.extend(fixed);
+
+        for (prefix, namespace) in &channel.namespaces {
+            if prefix.is_empty() {
+                continue;
+            }
+            match namespaces.declared.get(prefix) {
+                Some(bound) if bound != namespace => {
+                    let renamed = namespaces.free_prefix(prefix, &prefixes);
+                    namespaces.renamed.insert(prefix.clone(), renamed.clone());
+                    namespaces.declared.insert(renamed, namespace.clone());
+                }
+                _ => {
+                    namespaces
+                        .declared
+                        .insert(prefix.clone(), namespace.clone());
+                }
+            }
+        }
+
+        for (prefix, namespace) in flexible {
+            if namespaces.prefix_of(&namespace).is_none() {
+                let prefix = namespaces.free_prefix(&prefix, &prefixes);
+                namespaces.declared.insert(prefix, namespace);
+            }
+        }
+
+        for (prefix, path) in used {
+            let prefix = namespaces.rename(&prefix).to_string();
+            if namespaces.declared.contains_key(&prefix) {
+                continue;
+            }
+            let namespace = match known_namespace(&prefix) {
+                Some(namespace) => namespace.to_string(),
+                None => {
+                    let namespace = format!("{}{}", UNDECLARED_NAMESPACE, prefix);
+                    namespaces.warnings.push(WriteWarning {
+                        message: format!(
+                            "the prefix `{}` is not bound to a namespace and was declared as `{}`",
+                            prefix, namespace
+                        ),
+                        path,
+                    });
+                    namespace
+                }
+            };
+            namespaces.declared.insert(prefix, namespace);
+        }
+
+        namespaces
+    }
+
+    /// Works out the namespaces inside `item`, which is written at `path` after the root element
+    /// was declared with these namespaces.
+    ///
+    /// The namespaces that the item uses but that are not declared on the root element, or that
+    /// are bound to another namespace there, are declared by the item itself and returned by
+    /// `local`. The warnings are only those of the prefixes that the item declares.
+    pub fn for_item(&self, item: &Item, path: &str) -> Cow<'_, Namespaces> {
+        let mut prefixes = Prefixes::default();
+        prefixes.add_item(path, item);
+        let used = prefixes.used;
+        let used_prefixes = used.keys().cloned().collect();
+        let mut namespaces = Namespaces {
+            declared: self.declared.clone(),
+            renamed: self.renamed.clone(),
+            local: BTreeMap::new(),
+            warnings: Vec::new(),
+        };
+
+        for (prefix, namespace) in item.used_namespaces() {
+            if has_any_prefix(&namespace) {
+                if namespaces.prefix_of(&namespace).is_none() {
+                    let prefix = namespaces.free_prefix(&prefix, &used_prefixes);
+                    namespaces.declare_local(prefix, namespace);
+                }
+                continue;
+            }
+            match namespaces.declared.get(&prefix).cloned() {
+                Some(bound) if bound == namespace => {}
+                Some(bound) => {
+                    // the generic extensions of the item keep the namespace of the root element
+                    if used_prefixes.contains(&prefix) {
+                        let renamed = namespaces.free_prefix(&prefix, &used_prefixes);
+                        namespaces.renamed.insert(prefix.clone(), renamed.clone());
+                        namespaces.declare_local(renamed, bound);
+                    }
+                    namespaces.declare_local(prefix, namespace);
+                }
+                None => namespaces.declare_local(prefix, namespace),
+            }
+        }
+
+        for (prefix, path) in used {
+            let prefix = namespaces.rename(&prefix).to_string();
+            if namespaces.declared.contains_key(&prefix) {
+                continue;
+            }
+            let namespace = match known_namespace(&prefix) {
+                Some(namespace) => namespace.to_string(),
+                None => {
+                    let namespace = format!("{}{}", UNDECLARED_NAMESPACE, prefix);
+                    namespaces.warnings.push(WriteWarning {
+                        message: format!(
+                            "the prefix `{}` is not bound to a namespace and was declared as `{}`",
+                            prefix, namespace
+                        ),
+                        path,
+                    });
+                    namespace
+                }
+            };
+            namespaces.declare_local(prefix, namespace);
+        }
+
+        if namespaces.local.is_empty() {
+            Cow::Borrowed(self)
+        } else {
+            Cow::Owned(namespaces)
+        }
+    }
+
+    /// Returns the namespaces to declare on the root element, keyed by prefix.
+    pub fn declared(&self) -> &BTreeMap<String, String> {
+        &self.declared
+    }
+
+    /// Returns the namespaces that an item declares itself, keyed by prefix.
+    pub fn local(&self) -> &BTreeMap<String, String> {
+        &self.local
+    }
+
+    /// Returns the warnings for the prefixes that were declared with a placeholder namespace.
+    pub fn warnings(&self) -> &[WriteWarning] {
+        &self.warnings
+    }
+
+    /// Returns the namespaces inside an element with the attributes `attrs`, in which the
+    /// prefixes that the element declares itself are not replaced.
+    pub fn scoped(&self, attrs: &BTreeMap<String, String>) -> Cow<'_, Namespaces> {
+        let declares = |prefix: &String| attrs.contains_key(&format!("xmlns:{}", prefix));
+        if !self.renamed.keys().any(declares) {
+            return Cow::Borrowed(self);
+        }
+
+        let mut namespaces = self.clone();
+        namespaces.renamed.retain(|prefix, _| !declares(prefix));
+        Cow::Owned(namespaces)
+    }
+
+    /// Returns the prefix that is written instead of `prefix`.
+    pub fn rename<'a>(&'a self, prefix: &'a str) -> &'a str {
+        self.renamed.get(prefix).map_or(prefix, String::as_str)
+    }
+
+    /// Returns the qualified name that is written instead of `name`.
+    pub fn rename_name(&self, name: &str) -> String {
+        match name.split_once(':') {
+            Some((prefix, local_name)) if self.renamed.contains_key(prefix) => {
+                format!("{}:{}", self.rename(prefix), local_name)
+            }
+            _ => name.to_string(),
+        }
+    }
+
+    /// Declares `namespace` with `prefix` on an item.
+    fn declare_local(&mut self, prefix: String, namespace: String) {
+        self.declared.insert(prefix.clone(), namespace.clone());
+        self.local.insert(prefix, namespace);
+    }
+
+    /// Returns a prefix that is bound to `namespace`.
+    fn prefix_of(&self, namespace: &str) -> Option<&str> {
+        self.declared
+            .iter()
+            .find(|(_, bound)| *bound == namespace)
+            .map(|(prefix, _)| prefix.as_str())
+    }
+
+    /// Returns `prefix`, or `prefix` followed by the lowest number that makes it a prefix that is
+    /// neither declared nor used by an element or attribute.
+    fn free_prefix(&self, prefix: &str, used: &BTreeSet<String>) -> String {
+        let is_free = |candidate: &String| {
+            !self.declared.contains_key(candidate) && !used.contains(candidate)
+        };
+        let prefix = prefix.to_string();
+        if is_free(&prefix) && !self.renamed.contains_key(&prefix) {
+            return prefix;
+        }
+        (1..)
+            .map(|n| format!("{}{}", prefix, n))
+            .find(is_free)
+            .unwrap_or_default()
+    }
+}
+
+/// Whether the typed extension in `namespace` can be written with any prefix.
+fn has_any_prefix(namespace: &str) -> bool {
+    #[cfg(feature = "atom")]
+    {
+        if namespace == crate::extension::atom::NAMESPACE {
+            return true;
+        }
+    }
+    namespace == syndication::NAMESPACE
+}
+
+/// Returns the prefixes of the generic extensions and of the attributes of `channel` that are
+/// not declared by the element that uses them or one of its ancestors, along with the path of
+/// the first element or attribute that uses each of them.
+fn used_prefixes(channel: &Channel) -> BTreeMap<String, String> {
+    let mut prefixes = Prefixes::default();
+    let path = "channel";
+
+    prefixes.add_extensions(path, &channel.extensions);
+    prefixes.add_attributes(path, &channel.extra_attrs);
+    if let Some(cloud) = &channel.cloud {
+        prefixes.add_attributes("channel/cloud", &cloud.extra_attrs);
+    }
+    if let Some(image) = &channel.image {
+        prefixes.add_attributes("channel/image", &image.extra_attrs);
+    }
+    if let Some(text_input) = &channel.text_input {
+        prefixes.add_attributes("channel/textInput", &text_input.extra_attrs);
+    }
+    for category in &channel.categories {
+        prefixes.add_attributes("channel/category", &category.extra_attrs);
+    }
+    for element in &channel.unknown_elements {
+        prefixes.add_element(path, &element.element);
+    }
+    for (index, item) in channel.items.iter().enumerate() {
+        prefixes.add_item(&item_path(index), item);
+    }
+
+    prefixes.used
+}
+
+/// The prefixes that are used in a channel, and the prefixes declared by the elements around the
+/// element whose prefixes are added.
+#[derive(Default)]
+struct Prefixes {
+    used: BTreeMap<String, String>,
+    bound: Vec<String>,
+}
+
+impl Prefixes {
+    fn add(&mut self, prefix: &str, path: impl FnOnce() -> String) {
+        if !self.bound.iter().any(|bound| bound == prefix) && !self.used.contains_key(prefix) {
+            self.used.insert(prefix.to_string(), path());
+        }
+    }
+
+    fn add_item(&mut self, path: &str, item: &Item) {
+        // the declarations of the item are in scope for its children
+        let bound = self.bind(&item.extra_attrs);
+        self.add_extensions(path, &item.extensions);
+        self.add_attributes(path, &item.extra_attrs);
+        for category in &item.categories {
+            self.add_attributes(&format!("{}/category", path), &category.extra_attrs);
+        }
+        if let Some(enclosure) = &item.enclosure {
+            self.add_attributes(&format!("{}/enclosure", path), &enclosure.extra_attrs);
+        }
+        if let Some(guid) = &item.guid {
+            self.add_attributes(&format!("{}/guid", path), &guid.extra_attrs);
+        }
+        if let Some(source) = &item.source {
+            self.add_attributes(&format!("{}/source", path), &source.extra_attrs);
+        }
+        for element in &item.unknown_elements {
+            self.add_element(path, &element.element);
+        }
+        self.bound.truncate(bound);
+    }
+
+    fn add_extensions(&mut self, path: &str, extensions: &ExtensionMap) {
+        for extension in extensions.values().flat_map(BTreeMap::values).flatten() {
+            self.add_element(path, extension);
+        }
+    }
+
+    fn add_element(&mut self, parent: &str, extension: &Extension) {
+        let bound = self.bind(&extension.attrs);
+        let path = format!("{}/{}", parent, extension.name);
+        if let Some((prefix, _)) = extension.name.split_once(':') {
+            self.add(prefix, || path.clone());
+        }
+        self.add_attributes(&path, &extension.attrs);
+        for child in extension.children.values().flatten() {
+            self.add_element(&path, child);
+        }
+        self.bound.truncate(bound);
+    }
+
+    fn add_attributes(&mut self, path: &str, attrs: &BTreeMap<String, String>) {
+        let bound = self.bind(attrs);
+        for key in attrs.keys() {
+            match key.split_once(':') {
+                Some(("xml", _)) | Some(("xmlns", _)) => {}
+                Some((prefix, _)) => self.add(prefix, || format!("{}/@{}", path, key)),
+                None => {}
+            }
+        }
+        self.bound.truncate(bound);
+    }
+
+    /// Adds the prefixes that the attributes `attrs` declare to the bound prefixes, returning the
+    /// number of bound prefixes before.
+    fn bind(&mut self, attrs: &BTreeMap<String, String>) -> usize {
+        let len = self.bound.len();
+        let declared = attrs.keys().filter_map(|key| key.strip_prefix("xmlns:"));
+        self.bound.extend(declared.map(str::to_string));
+        len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::extension::dublincore::{self, DublinCoreExtension};
+    use crate::extension::syndication::SyndicationExtension;
+
+    fn extension(name: &str) -> ExtensionMap {
+        let (prefix, local_name) = name.split_once(':').unwrap();
+        let mut extension = Extension::default();
+        extension.set_name(name);
+        let mut map = BTreeMap::new();
+        map.insert(local_name.to_string(), vec![extension]);
+        let mut extensions = ExtensionMap::new();
+        extensions.insert(prefix.to_string(), map);
+        extensions
+    }
+
+    #[test]
+    fn test_typed_extensions() {
+        let mut channel = Channel::default();
+        channel.set_dublin_core_ext(DublinCoreExtension::default());
+        channel.set_syndication_ext(SyndicationExtension::default());
+
+        let namespaces = Namespaces::for_channel(&channel);
+        assert_eq!(
+            namespaces.declared().get("dc").map(String::as_str),
+            Some(dublincore::NAMESPACE)
+        );
+        assert_eq!(
+            namespaces.declared().get("sy").map(String::as_str),
+            Some(syndication::NAMESPACE)
+        );
+    }
+
+    #[test]
+    fn test_flexible_prefix() {
+        let mut channel = Channel::default();
+        channel.set_syndication_ext(SyndicationExtension::default());
+        let mut declared = BTreeMap::new();
+        declared.insert("syn".to_string(), syndication::NAMESPACE.to_string());
+        declared.insert("sy".to_string(), "http://example.com/sy".to_string());
+        channel.set_namespaces(declared.clone());
+
+        let namespaces = Namespaces::for_channel(&channel);
+        assert_eq!(namespaces.declared(), &declared);
+    }
+
+    #[test]
+    fn test_conflict() {
+        let mut channel = Channel::default();
+        channel.set_dublin_core_ext(DublinCoreExtension::default());
+        channel.set_extensions(extension("dc:thing"));
+        let mut declared = BTreeMap::new();
+        declared.insert("dc".to_string(), "http://example.com/dc".to_string());
+        channel.set_namespaces(declared);
+
+        let namespaces = Namespaces::for_channel(&channel);
+        assert_eq!(
+            namespaces.declared().get("dc").map(String::as_str),
+            Some(dublincore::NAMESPACE)
+        );
+        assert_eq!(
+            namespaces.declared().get("dc1").map(String::as_str),
+            Some("http://example.com/dc")
+        );
+        assert_eq!(namespaces.rename_name("dc:thing"), "dc1:thing");
+        assert_eq!(namespaces.rename_name("dc:title"), "dc1:title");
+    }
+
+    #[test]
+    fn test_undeclared_prefixes() {
+        let mut channel = Channel::default();
+        channel.set_extensions(extension("content:encoded"));
+        let mut item = Item::default();
+        item.set_extensions(extension("ext:thing"));
+        channel.set_items(vec![item]);
+
+        let namespaces = Namespaces::for_channel(&channel);
+        assert_eq!(
+            namespaces.declared().get("content").map(String::as_str),
+            Some("http://purl.org/rss/1.0/modules/content/")
+        );
+        assert_eq!(
+            namespaces.declared().get("ext").map(String::as_str),
+            Some("urn:x-rss:undeclared:ext")
+        );
+        assert_eq!(namespaces.warnings().len(), 1);
+        assert_eq!(namespaces.warnings()[0].path, "channel/item/ext:thing");
+    }
+
+    #[test]
+    fn test_prefixes_declared_by_elements() {
+        let mut extensions = extension("ext:thing");
+        let thing = extensions.get_mut("ext").unwrap().get_mut("thing").unwrap();
+        thing[0].attrs.insert(
+            "xmlns:ext".to_string(),
+            "http://example.com/ext".to_string(),
+        );
+        thing[0].children.insert(
+            "child".to_string(),
+            vec![extension("ext:child")["ext"]["child"][0].clone()],
+        );
+        let mut item = Item::default();
+        item.set_extensions(extensions);
+        let mut channel = Channel::default();
+        channel.set_items(vec![item]);
+
+        let namespaces = Namespaces::for_channel(&channel);
+        assert!(namespaces.declared().is_empty());
+        assert!(namespaces.warnings().is_empty());
+    }
+}
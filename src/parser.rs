@@ -0,0 +1,626 @@
+// This file is part of rss.
+//
+// Copyright © 2015-2021 The rust-syndication Developers
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the MIT License and/or Apache 2.0 License.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt;
+
+use quick_xml::escape::unescape;
+use quick_xml::events::{BytesEnd, Event};
+use quick_xml::Error as XmlError;
+
+use crate::channel::Channel;
+use crate::error::{Error, LimitKind, Location, ParseError};
+use crate::input::{Position, XmlSource};
+
+/// The nesting depth that elements are never read beyond, whatever `Limits::max_depth` is.
+const MAX_DEPTH: usize = 256;
+
+/// Options that control how a channel is read.
+///
+/// # Examples
+///
+/// ```
+/// use rss::ParseOptions;
+///
+/// let options = ParseOptions {
+///     lenient: true,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ParseOptions {
+    /// Recover from malformed XML instead of failing.
+    ///
+    /// Mismatched end tags, unclosed elements, truncated input and invalid entities are
+    /// repaired as well as possible, and each repair is reported as a `ParseWarning`.
+    pub lenient: bool,
+    /// The limits on the size of the input.
+    pub limits: Limits,
+    /// The charset of the input as reported externally, for example by the `Content-Type` header
+    /// of an HTTP response.
+    ///
+    /// The encoding of the input is taken from the first of these that is present and names a
+    /// known encoding:
+    ///
+    /// 1. a byte order mark,
+    /// 2. this charset,
+    /// 3. the `encoding` in the XML declaration,
+    /// 4. UTF-8 if the first few kilobytes of the input are valid UTF-8, and Windows-1252
+    ///    otherwise.
+    ///
+    /// Charsets are matched as in the [WHATWG Encoding Standard](https://encoding.spec.whatwg.org/),
+    /// so that, for example, `ISO-8859-1` is read as Windows-1252. Unknown charsets and
+    /// declarations that disagree with the detected encoding are reported as a `ParseWarning`.
+    pub charset: Option<String>,
+    /// Keep the markup inside `description`, `content:encoded` and extension elements.
+    ///
+    /// By default only the text of these elements is read, so that inline XHTML such as
+    /// `<description><p>Hello <b>world</b></p></description>` is lost. With this option, an
+    /// element that contains elements is read as its raw inner XML, and the kind of its text is
+    /// recorded as a `TextKind`. Extension elements are only read as raw XML when they mix text
+    /// and elements, and their children are read as well.
+    pub preserve_markup: bool,
+}
+
+/// Limits on the size of the input, to protect against feeds that would exhaust memory or the
+/// stack.
+///
+/// Exceeding a limit fails with `Error::LimitExceeded`, even in lenient mode. A limit of `None`
+/// is not checked, except for the nesting depth, which is never more than 256. By default, only
+/// the nesting depth is limited.
+///
+/// # Examples
+///
+/// ```
+/// use rss::{Limits, ParseOptions};
+///
+/// let options = ParseOptions {
+///     limits: Limits {
+///         max_items: Some(500),
+///         max_input_len: Some(10 * 1024 * 1024),
+///         ..Default::default()
+///     },
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limits {
+    /// The maximum nesting depth of the elements, including the root element.
+    ///
+    /// Elements are never read more than 256 levels deep, even if this is `None` or larger,
+    /// because extension elements are kept as trees that are converted, written and dropped
+    /// recursively, which would overflow the stack for deeper elements.
+    pub max_depth: Option<usize>,
+    /// The maximum number of items in the channel.
+    pub max_items: Option<usize>,
+    /// The maximum length in bytes of a single text node, comment or attribute value.
+    pub max_text_len: Option<usize>,
+    /// The maximum number of attributes of a single element.
+    pub max_attributes: Option<usize>,
+    /// The maximum number of elements nested in a single extension element.
+    pub max_extension_children: Option<usize>,
+    /// The maximum length in bytes of the input.
+    pub max_input_len: Option<usize>,
+}
+
+impl Limits {
+    fn get(&self, kind: LimitKind) -> Option<usize> {
+        match kind {
+            LimitKind::Depth => Some(self.max_depth.map_or(MAX_DEPTH, |max| max.min(MAX_DEPTH))),
+            LimitKind::Items => self.max_items,
+            LimitKind::TextLength => self.max_text_len,
+            LimitKind::Attributes => self.max_attributes,
+            LimitKind::ExtensionChildren => self.max_extension_children,
+            LimitKind::InputLength => self.max_input_len,
+        }
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_depth: Some(MAX_DEPTH),
+            max_items: None,
+            max_text_len: None,
+            max_attributes: None,
+            max_extension_children: None,
+            max_input_len: None,
+        }
+    }
+}
+
+/// A problem in the input that was skipped or repaired while reading a channel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseWarning {
+    /// A description of the problem.
+    pub message: String,
+    /// The location in the input at which the problem was found.
+    pub location: Location,
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at {}", self.message, self.location)
+    }
+}
+
+/// A channel read with `Channel::read_from_with_options`, along with the problems that were
+/// found in the input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedChannel {
+    /// The channel that was read.
+    pub channel: Channel,
+    /// The problems in the input that were skipped or repaired.
+    pub warnings: Vec<ParseWarning>,
+    /// The name of the encoding the input was read as, for example `windows-1252`.
+    pub encoding: &'static str,
+}
+
+/// An element that has been opened but not closed yet.
+#[derive(Debug)]
+struct OpenElement {
+    /// The qualified name of the element, or `None` if it was opened outside of the context.
+    name: Option<Vec<u8>>,
+    /// The position of the element among the elements with the same name in its parent.
+    index: usize,
+    /// The number of child elements with each name.
+    children: HashMap<Vec<u8>, usize>,
+}
+
+/// The state shared by everything that reads a single document.
+///
+/// All events are read through the context so that it can keep track of the open elements,
+/// repair malformed input in lenient mode and report where problems occur. The context also
+/// trims text and expands empty elements, unless markup is kept.
+#[derive(Debug, Default)]
+pub(crate) struct Context {
+    options: ParseOptions,
+    warnings: Vec<ParseWarning>,
+    open: Vec<OpenElement>,
+    top_level: HashMap<Vec<u8>, usize>,
+    items: usize,
+    /// The number of open elements that have to be closed before reading further input.
+    unclosed: usize,
+    eof: bool,
+    /// Whether whitespace and empty elements are reported as they are.
+    keep_markup: bool,
+    /// Whether whitespace is reported as it is.
+    keep_whitespace: bool,
+    /// Whether the document is an RSS 1.0 document, with an `<rdf:RDF>` root element.
+    rss1: bool,
+    /// The position in the input after the event that was read last.
+    position: Position,
+}
+
+impl Context {
+    pub fn new(options: ParseOptions) -> Self {
+        Context {
+            options,
+            ..Default::default()
+        }
+    }
+
+    /// Creates a context for reading the contents of an element whose start tag has already been
+    /// read.
+    pub fn for_element() -> Self {
+        let mut context = Context::default();
+        context.open.push(OpenElement {
+            name: None,
+            index: 1,
+            children: HashMap::new(),
+        });
+        context
+    }
+
+    pub fn options(&self) -> &ParseOptions {
+        &self.options
+    }
+
+    pub fn limits(&self) -> &Limits {
+        &self.options.limits
+    }
+
+    /// Fails if `value` is greater than the limit of the given kind.
+    pub fn check_limit(&self, kind: LimitKind, value: usize) -> Result<(), Error> {
+        match self.options.limits.get(kind) {
+            Some(limit) if value > limit => Err(Error::LimitExceeded { kind, limit }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Counts an item of the channel, failing if there are too many.
+    pub fn count_item(&mut self) -> Result<(), Error> {
+        self.items += 1;
+        self.check_limit(LimitKind::Items, self.items)
+    }
+
+    pub fn warnings(&self) -> &[ParseWarning] {
+        &self.warnings
+    }
+
+    pub fn into_warnings(self) -> Vec<ParseWarning> {
+        self.warnings
+    }
+
+    /// Returns the position in the input after the event that was read last.
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    /// Records that the document is an RSS 1.0 document.
+    pub fn set_rss1(&mut self) {
+        self.rss1 = true;
+    }
+
+    /// Whether the document is an RSS 1.0 document, in which RDF attributes may be unprefixed.
+    pub fn is_rss1(&self) -> bool {
+        self.rss1
+    }
+
+    /// Reports whitespace and empty elements as they are, which is needed to keep markup, or
+    /// trims whitespace and expands empty elements as usual.
+    pub fn keep_markup(&mut self, keep: bool) {
+        self.keep_markup = keep;
+    }
+
+    /// Reports whitespace as it is while still expanding empty elements, which is needed to keep
+    /// the whitespace between text nodes, or trims whitespace as usual.
+    pub fn keep_whitespace(&mut self, keep: bool) {
+        self.keep_whitespace = keep;
+    }
+
+    /// Records a problem at the current position.
+    pub fn warn<S: Into<String>>(&mut self, message: S) {
+        self.warn_at(self.open.len(), message);
+    }
+
+    /// Records a problem in the open element at `depth` at the current position.
+    fn warn_at<S: Into<String>>(&mut self, depth: usize, message: S) {
+        let location = self.location(depth);
+        self.warnings.push(ParseWarning {
+            message: message.into(),
+            location,
+        });
+    }
+
+    /// Returns the current location, at which a problem that is only found later can be reported
+    /// with `warn_in`.
+    pub fn here(&self) -> Location {
+        self.location(self.open.len())
+    }
+
+    /// Records a problem at `location`.
+    pub fn warn_in<S: Into<String>>(&mut self, location: Location, message: S) {
+        self.warnings.push(ParseWarning {
+            message: message.into(),
+            location,
+        });
+    }
+
+    /// Adds the current location to an error that occurred while reading.
+    pub fn locate(&self, error: Error) -> ParseError {
+        ParseError {
+            error,
+            location: self.location(self.open.len()),
+        }
+    }
+
+    /// The current position in the open element at `depth`.
+    fn location(&self, depth: usize) -> Location {
+        Location {
+            offset: self.position.offset,
+            line: self.position.line,
+            column: self.position.column,
+            path: self.path(depth),
+        }
+    }
+
+    /// The path of the first `depth` open elements.
+    fn path(&self, depth: usize) -> String {
+        let mut path = String::new();
+
+        for element in &self.open[..depth] {
+            let name = match element.name {
+                Some(ref name) => name,
+                None => continue,
+            };
+
+            if !path.is_empty() {
+                path.push('/');
+            }
+            path.push_str(&String::from_utf8_lossy(name));
+            if element.index > 1 {
+                path.push_str(&format!("[{}]", element.index));
+            }
+        }
+
+        path
+    }
+
+    /// Reads the next event from `source`.
+    ///
+    /// Every start event is matched by an end event. In lenient mode, unclosed elements are
+    /// closed implicitly, end tags that do not match the open element close it anyway, and the
+    /// input is treated as ending at the first error that cannot be recovered from.
+    pub fn read_event<'i, S: XmlSource<'i>>(&mut self, source: &mut S) -> Result<Event<'i>, Error> {
+        if self.unclosed > 0 {
+            return Ok(self.close());
+        }
+
+        if self.eof {
+            return Ok(Event::Eof);
+        }
+
+        // text is trimmed, and skipped if it is only whitespace, unless markup is kept
+        let event = loop {
+            let event = source.read_event();
+            self.position = source.position();
+            match event {
+                Ok(Event::Text(mut text)) if !self.keep_markup && !self.keep_whitespace => {
+                    text.inplace_trim_start();
+                    if !text.inplace_trim_end() {
+                        break Ok(Event::Text(text));
+                    }
+                }
+                event => break event,
+            }
+        };
+
+        if let Ok(Event::Start(ref element)) | Ok(Event::Empty(ref element)) = event {
+            if self.options.limits.max_attributes.is_some() {
+                let attributes = element.attributes().with_checks(false).count();
+                self.check_limit(LimitKind::Attributes, attributes)?;
+            }
+        }
+
+        match event {
+            Ok(Event::Start(element)) => {
+                self.open(element.name().into_inner())?;
+                Ok(Event::Start(element))
+            }
+            // an empty element is read as a start tag that is closed right away
+            Ok(Event::Empty(element)) if !self.keep_markup => {
+                self.open(element.name().into_inner())?;
+                self.unclosed = 1;
+                Ok(Event::Start(element))
+            }
+            Ok(Event::Text(text)) => {
+                self.check_limit(LimitKind::TextLength, text.len())?;
+                Ok(Event::Text(text))
+            }
+            Ok(Event::CData(text)) => {
+                self.check_limit(LimitKind::TextLength, text.len())?;
+                Ok(Event::CData(text))
+            }
+            Ok(Event::End(element)) => {
+                self.end(element.name().into_inner())?;
+                if self.unclosed > 0 {
+                    Ok(self.close())
+                } else {
+                    Ok(Event::End(element))
+                }
+            }
+            Ok(Event::Eof) if self.options.lenient && !self.open.is_empty() => {
+                self.warn("unexpected end of input");
+                Ok(self.end_of_input())
+            }
+            Ok(event) => Ok(event),
+            Err(err @ Error::Xml(XmlError::Io(_))) => Err(err),
+            Err(Error::Xml(err)) if self.options.lenient => {
+                self.warn(err.to_string());
+                Ok(self.end_of_input())
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn open(&mut self, name: &[u8]) -> Result<(), Error> {
+        self.check_limit(LimitKind::Depth, self.open.len() + 1)?;
+
+        let counts = match self.open.last_mut() {
+            Some(parent) => &mut parent.children,
+            None => &mut self.top_level,
+        };
+        let count = counts.entry(name.to_vec()).or_insert(0);
+        *count += 1;
+        let index = *count;
+
+        self.open.push(OpenElement {
+            name: Some(name.to_vec()),
+            index,
+            children: HashMap::new(),
+        });
+
+        Ok(())
+    }
+
+    /// Closes the innermost of the elements that have to be closed, returning its end event.
+    fn close(&mut self) -> Event<'static> {
+        self.unclosed -= 1;
+        let element = self.open.pop();
+        let name = element.and_then(|element| element.name).unwrap_or_default();
+        Event::End(BytesEnd::new(String::from_utf8_lossy(&name).into_owned()))
+    }
+
+    /// Closes every open element, after which no more input is read.
+    fn end_of_input(&mut self) -> Event<'static> {
+        self.eof = true;
+        self.unclosed = self.open.len();
+        if self.unclosed > 0 {
+            self.close()
+        } else {
+            Event::Eof
+        }
+    }
+
+    /// Handles an end tag.
+    ///
+    /// If the end tag does not close the innermost open element, the open elements that it
+    /// closes are recorded in `unclosed`.
+    fn end(&mut self, name: &[u8]) -> Result<(), Error> {
+        let position = self.open.iter().rposition(|element| match element.name {
+            Some(ref open) => open == name,
+            None => true,
+        });
+
+        match position {
+            Some(index) if index + 1 == self.open.len() => {
+                self.open.pop();
+            }
+            _ if !self.options.lenient => {
+                let expected = self
+                    .open
+                    .last()
+                    .and_then(|element| element.name.as_ref())
+                    .map(|name| String::from_utf8_lossy(name).into_owned())
+                    .unwrap_or_default();
+                let found = String::from_utf8_lossy(name).into_owned();
+                return Err(XmlError::EndEventMismatch { expected, found }.into());
+            }
+            Some(index) => {
+                for depth in (index + 2..=self.open.len()).rev() {
+                    self.warn_at(depth, "element is not closed");
+                }
+                self.unclosed = self.open.len() - index;
+            }
+            None if self.open.is_empty() => {
+                let message = format!("unexpected end tag </{}>", String::from_utf8_lossy(name));
+                self.warn(message);
+            }
+            None => {
+                let message = format!(
+                    "mismatched end tag </{}> is treated as the end of the element",
+                    String::from_utf8_lossy(name)
+                );
+                self.warn(message);
+                self.unclosed = 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The number of open elements.
+    #[cfg(feature = "async")]
+    pub fn depth(&self) -> usize {
+        self.open.len()
+    }
+
+    /// Whether the next event is read from the source, rather than being an end event of an
+    /// element that is closed implicitly or the end of the input.
+    #[cfg(feature = "async")]
+    pub fn reads_source(&self) -> bool {
+        self.unclosed == 0 && !self.eof
+    }
+
+    /// Reads `event` as if it came from a source, returning the number of open elements after
+    /// it, or `None` if it is whitespace that `read_event` skips.
+    ///
+    /// This lets a context follow events that are read ahead of the one that parses them, to
+    /// find out where the elements they contain end.
+    #[cfg(feature = "async")]
+    pub fn follow(&mut self, event: Event<'static>) -> Result<Option<usize>, Error> {
+        match event {
+            Event::Text(ref text) if text.iter().all(|b| b" \t\r\n".contains(b)) => {
+                return Ok(None)
+            }
+            Event::Eof => return Ok(Some(0)),
+            _ => {}
+        }
+
+        self.read_event(&mut Single(Some(event)))?;
+        while self.unclosed > 0 {
+            self.close();
+        }
+        self.warnings.clear();
+        Ok(Some(self.open.len()))
+    }
+
+    /// Skips the rest of the element whose start tag was read last.
+    pub fn skip_element<'i, S: XmlSource<'i>>(&mut self, source: &mut S) -> Result<(), Error> {
+        let depth = self.open.len();
+
+        loop {
+            match self.read_event(source)? {
+                Event::End(_) if self.open.len() < depth => return Ok(()),
+                Event::Eof => {
+                    let name = self
+                        .open
+                        .last()
+                        .and_then(|element| element.name.as_ref())
+                        .map(|name| String::from_utf8_lossy(name).into_owned())
+                        .unwrap_or_default();
+                    return Err(XmlError::UnexpectedEof(format!("</{}>", name)).into());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Records a warning for text or CDATA that appears where only elements are expected.
+    pub fn stray_text(&mut self) {
+        self.warn("skipped text outside of an element");
+    }
+
+    /// Unescapes UTF-8 text, keeping it borrowed if it contains no entities.
+    pub fn unescape<'a>(&mut self, escaped: Cow<'a, str>) -> Result<Cow<'a, str>, Error> {
+        if !escaped.contains('&') {
+            return Ok(escaped);
+        }
+
+        match unescape(&escaped) {
+            Ok(text) => Ok(Cow::Owned(text.into_owned())),
+            Err(err) if self.options.lenient => {
+                self.warn(XmlError::EscapeError(err).to_string());
+                Ok(Cow::Owned(unescape_lenient(&escaped)))
+            }
+            Err(err) => Err(XmlError::EscapeError(err).into()),
+        }
+    }
+}
+
+/// A source of a single event.
+#[cfg(feature = "async")]
+struct Single(Option<Event<'static>>);
+
+#[cfg(feature = "async")]
+impl XmlSource<'static> for Single {
+    fn read_event(&mut self) -> Result<Event<'static>, Error> {
+        Ok(self.0.take().unwrap_or(Event::Eof))
+    }
+
+    fn position(&self) -> Position {
+        Position::default()
+    }
+}
+
+/// Unescapes the entities in `text`, keeping the ones that are not valid.
+fn unescape_lenient(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let entity = rest.find(';').map(|end| &rest[..=end]);
+        match entity.and_then(|entity| unescape(entity).ok().map(|value| (entity, value))) {
+            Some((entity, value)) => {
+                result.push_str(&value);
+                rest = &rest[entity.len()..];
+            }
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
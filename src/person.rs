@@ -0,0 +1,238 @@
+// This file is part of rss.
+//
+// Copyright © 2015-2021 The rust-syndication Developers
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the MIT License and/or Apache 2.0 License.
+
+use std::fmt;
+
+/// A person named in a feed, such as the managing editor of a channel or the author of an item.
+///
+/// RSS 2.0 writes people as `email (name)`, but feeds use other forms too, such as
+/// `name <email>` or just a name. `Person::parse` reads all of these.
+///
+/// # Examples
+///
+/// ```
+/// use rss::Person;
+///
+/// let person = Person::parse("Jane Editor <jane@example.com>");
+/// assert_eq!(person.name(), Some("Jane Editor"));
+/// assert_eq!(person.email(), Some("jane@example.com"));
+/// assert_eq!(person.to_string(), "jane@example.com (Jane Editor)");
+/// ```
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Person {
+    /// The name of the person.
+    pub name: Option<String>,
+    /// The email address of the person.
+    pub email: Option<String>,
+}
+
+impl Person {
+    /// Read a person from text such as `email (name)`, `name <email>`, `email` or `name`.
+    ///
+    /// Whichever part contains an `@` is taken to be the email address, and a `mailto:` prefix
+    /// and quotes around the name are removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::Person;
+    ///
+    /// let person = Person::parse("editor@example.com (Jane Editor)");
+    /// assert_eq!(person.name(), Some("Jane Editor"));
+    /// assert_eq!(person.email(), Some("editor@example.com"));
+    ///
+    /// let person = Person::parse("Jane Editor");
+    /// assert_eq!(person.name(), Some("Jane Editor"));
+    /// assert_eq!(person.email(), None);
+    /// ```
+    pub fn parse(text: &str) -> Person {
+        let text = text.trim();
+
+        // `name <email>`
+        if let Some(start) = text.find('<') {
+            if let Some(end) = text[start..].find('>').map(|end| start + end) {
+                let name = format!("{} {}", &text[..start], &text[end + 1..]);
+                return Person::from_parts(&name, &text[start + 1..end]);
+            }
+        }
+
+        // `email (name)`, or `name (email)`
+        if let (Some(start), Some(end)) = (text.find('('), text.rfind(')')) {
+            if start < end {
+                let inner = &text[start + 1..end];
+                let outer = format!("{} {}", &text[..start], &text[end + 1..]);
+                if inner.contains('@') && !outer.contains('@') {
+                    return Person::from_parts(&outer, inner);
+                } else if outer.contains('@') {
+                    return Person::from_parts(inner, &outer);
+                }
+            }
+        }
+
+        // `email`, `name`, or a name and an email address separated by spaces
+        let (emails, names): (Vec<&str>, Vec<&str>) =
+            text.split_whitespace().partition(|word| word.contains('@'));
+        Person::from_parts(&names.join(" "), &emails.join(" "))
+    }
+
+    fn from_parts(name: &str, email: &str) -> Person {
+        let name = name.trim().trim_matches(|c| c == '"' || c == '\'').trim();
+        let email = email.trim();
+        let email = match email.get(..7) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("mailto:") => &email[7..],
+            _ => email,
+        };
+        Person {
+            name: Some(name)
+                .filter(|name| !name.is_empty())
+                .map(str::to_string),
+            email: Some(email)
+                .filter(|email| !email.is_empty())
+                .map(str::to_string),
+        }
+    }
+
+    /// Return the name of this person.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::Person;
+    ///
+    /// let mut person = Person::default();
+    /// person.set_name("Jane Editor".to_string());
+    /// assert_eq!(person.name(), Some("Jane Editor"));
+    /// ```
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Set the name of this person.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::Person;
+    ///
+    /// let mut person = Person::default();
+    /// person.set_name("Jane Editor".to_string());
+    /// ```
+    pub fn set_name<V>(&mut self, name: V)
+    where
+        V: Into<Option<String>>,
+    {
+        self.name = name.into();
+    }
+
+    /// Return the email address of this person.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::Person;
+    ///
+    /// let mut person = Person::default();
+    /// person.set_email("jane@example.com".to_string());
+    /// assert_eq!(person.email(), Some("jane@example.com"));
+    /// ```
+    pub fn email(&self) -> Option<&str> {
+        self.email.as_deref()
+    }
+
+    /// Set the email address of this person.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::Person;
+    ///
+    /// let mut person = Person::default();
+    /// person.set_email("jane@example.com".to_string());
+    /// ```
+    pub fn set_email<V>(&mut self, email: V)
+    where
+        V: Into<Option<String>>,
+    {
+        self.email = email.into();
+    }
+
+    /// Whether the person has neither a name nor an email address.
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.email.is_none()
+    }
+}
+
+/// Writes the person in the RSS 2.0 form `email (name)`, or just the email address or name if
+/// the other is missing.
+impl fmt::Display for Person {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (&self.email, &self.name) {
+            (Some(email), Some(name)) => write!(f, "{} ({})", email, name),
+            (Some(email), None) => f.write_str(email),
+            (None, Some(name)) => f.write_str(name),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn person(name: Option<&str>, email: Option<&str>) -> Person {
+        Person {
+            name: name.map(str::to_string),
+            email: email.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let jane = person(Some("Jane Editor"), Some("jane@example.com"));
+        assert_eq!(Person::parse("jane@example.com (Jane Editor)"), jane);
+        assert_eq!(Person::parse("Jane Editor <jane@example.com>"), jane);
+        assert_eq!(Person::parse("\"Jane Editor\" <jane@example.com>"), jane);
+        assert_eq!(Person::parse("Jane Editor (jane@example.com)"), jane);
+        assert_eq!(Person::parse("mailto:jane@example.com (Jane Editor)"), jane);
+        assert_eq!(Person::parse("Jane Editor jane@example.com"), jane);
+        assert_eq!(Person::parse("  jane@example.com  ( Jane Editor ) "), jane);
+    }
+
+    #[test]
+    fn test_parse_partial() {
+        assert_eq!(
+            Person::parse("jane@example.com"),
+            person(None, Some("jane@example.com"))
+        );
+        assert_eq!(
+            Person::parse("<jane@example.com>"),
+            person(None, Some("jane@example.com"))
+        );
+        assert_eq!(
+            Person::parse("Jane Editor"),
+            person(Some("Jane Editor"), None)
+        );
+        assert_eq!(
+            Person::parse("Jane (Editor)"),
+            person(Some("Jane (Editor)"), None)
+        );
+        assert_eq!(Person::parse(" "), Person::default());
+    }
+
+    #[test]
+    fn test_display() {
+        let jane = person(Some("Jane Editor"), Some("jane@example.com"));
+        assert_eq!(jane.to_string(), "jane@example.com (Jane Editor)");
+        assert_eq!(Person::parse(&jane.to_string()), jane);
+        assert_eq!(
+            person(None, Some("jane@example.com")).to_string(),
+            "jane@example.com"
+        );
+        assert_eq!(person(Some("Jane"), None).to_string(), "Jane");
+    }
+}
@@ -0,0 +1,545 @@
+// This file is part of rss.
+//
+// Copyright © 2015-2021 The rust-syndication Developers
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the MIT License and/or Apache 2.0 License.
+
+#[cfg(feature = "async")]
+use std::collections::VecDeque;
+use std::io::BufRead;
+#[cfg(feature = "async")]
+use std::sync::Arc;
+use std::sync::OnceLock;
+
+#[cfg(feature = "async")]
+use quick_xml::events::Event;
+use quick_xml::Error as XmlError;
+use quick_xml::Reader;
+#[cfg(feature = "async")]
+use tokio::io::AsyncBufRead;
+
+use crate::borrowed::{ChannelParser, ItemRef, Parser};
+use crate::channel::Channel;
+use crate::encoding::Decode;
+use crate::error::{Error, ParseError};
+#[cfg(feature = "async")]
+use crate::input::{read_error, Lines, Position, XmlSource};
+use crate::input::{BufSource, Input};
+use crate::item::Item;
+use crate::parser::{Context, ParseOptions, ParseWarning, ParsedChannel};
+use crate::util::sort_items;
+
+/// A streaming reader for RSS channels.
+///
+/// The channel header (everything that appears before the first item) is read when the reader
+/// is created, after which the items are returned one at a time by the `Iterator`
+/// implementation. This allows large feeds to be processed without keeping every item in memory.
+///
+/// Each item is returned as a `Result<Item, ParseError>` rather than a `Result<Item, Error>`, so
+/// that an error carries the location in the input at which it occurred, as with
+/// `Channel::read_from`. The `Error` itself is `ParseError::error`, and `?` converts a
+/// `ParseError` into an `Error`.
+///
+/// # Examples
+///
+/// ```
+/// use rss::ChannelReader;
+///
+/// let input = r#"
+/// <rss version="2.0">
+///   <channel>
+///     <title>Title</title>
+///     <item><title>First</title></item>
+///     <item><title>Second</title></item>
+///   </channel>
+/// </rss>
+/// "#;
+///
+/// let mut reader = ChannelReader::new(input.as_bytes()).unwrap();
+/// assert_eq!(reader.channel().title(), "Title");
+///
+/// let item = reader.next().unwrap().unwrap();
+/// assert_eq!(item.title(), Some("First"));
+/// ```
+pub struct ChannelReader<R: BufRead> {
+    reader: Reader<Input<Decode<R>>>,
+    context: Context,
+    encoding: &'static str,
+    /// Reads the channel one element at a time, keeping the header as it was read.
+    parser: ChannelParser<'static>,
+    /// The channel header, converted from the one kept by `parser` when it is first asked for
+    /// after it changed.
+    channel: OnceLock<Channel>,
+    /// The item that was read along with the channel header.
+    pending: Option<Item>,
+}
+
+impl<R: BufRead> ChannelReader<R> {
+    /// Create a new reader and read the channel header.
+    ///
+    /// Fails if the input does not begin with an `<rss>` or `<rdf:RDF>` element, or if it ends
+    /// before a channel was found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::ChannelReader;
+    ///
+    /// let input = "<rss><channel><title>Title</title></channel></rss>";
+    /// let reader = ChannelReader::new(input.as_bytes()).unwrap();
+    /// assert_eq!(reader.channel().title(), "Title");
+    /// ```
+    pub fn new(reader: R) -> Result<Self, ParseError> {
+        ChannelReader::with_options(reader, ParseOptions::default())
+    }
+
+    /// Create a new reader that uses the given options and read the channel header.
+    ///
+    /// The input is converted to UTF-8 from the encoding that is detected as described for
+    /// `ParseOptions::charset`. Locations in the input are byte offsets in the converted input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::{ChannelReader, ParseOptions};
+    ///
+    /// let input = "<rss><channel><title>Title &nbsp;</title></channel></rss>";
+    /// let options = ParseOptions {
+    ///     lenient: true,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// let reader = ChannelReader::with_options(input.as_bytes(), options).unwrap();
+    /// assert_eq!(reader.channel().title(), "Title &nbsp;");
+    /// assert_eq!(reader.warnings().len(), 1);
+    /// ```
+    pub fn with_options(reader: R, options: ParseOptions) -> Result<Self, ParseError> {
+        let mut context = Context::new(options);
+        let (reader, detection) = Decode::new(reader, context.options().charset.as_deref())
+            .map_err(|err| context.locate(Error::from(XmlError::from(err))))?;
+        let input = Input::new(reader, context.limits());
+
+        let mut reader = Reader::from_reader(input);
+        reader.check_end_names(false);
+
+        for warning in detection.warnings {
+            context.warn(warning);
+        }
+
+        let mut channel_reader = ChannelReader {
+            reader,
+            context,
+            encoding: detection.encoding.name(),
+            parser: ChannelParser::default(),
+            channel: OnceLock::new(),
+            pending: None,
+        };
+
+        channel_reader.pending = channel_reader.next_item()?;
+        if channel_reader.pending.is_none() && !channel_reader.parser.has_channel() {
+            return Err(channel_reader.context.locate(Error::Eof));
+        }
+
+        Ok(channel_reader)
+    }
+
+    /// Return the name of the encoding the input is read as, for example `windows-1252`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::ChannelReader;
+    ///
+    /// let input = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?><rss><channel/></rss>";
+    /// let reader = ChannelReader::new(&input[..]).unwrap();
+    /// assert_eq!(reader.encoding(), "windows-1252");
+    /// ```
+    pub fn encoding(&self) -> &'static str {
+        self.encoding
+    }
+
+    /// Return the channel header.
+    ///
+    /// The returned channel never contains items. Elements of the channel that appear after the
+    /// first item are added to it as the items are read.
+    pub fn channel(&self) -> &Channel {
+        header(&self.parser, &self.channel)
+    }
+
+    /// Consume the reader and return the channel header.
+    pub fn into_channel(self) -> Channel {
+        into_header(self.parser, self.channel)
+    }
+
+    /// Return the problems in the input that were skipped or repaired so far.
+    pub fn warnings(&self) -> &[ParseWarning] {
+        self.context.warnings()
+    }
+
+    /// Consume the reader and return the channel with `items` along with the warnings, failing
+    /// if the input never contained a channel element.
+    ///
+    /// The items of an RSS 1.0 channel are sorted in the order given by the channel.
+    pub(crate) fn finish(self, items: Vec<Item>) -> Result<ParsedChannel, ParseError> {
+        finish(
+            self.parser,
+            self.channel,
+            self.context,
+            self.encoding,
+            items,
+        )
+    }
+
+    /// Read until the next item, adding any other elements to the channel header and the
+    /// location to errors.
+    fn next_item(&mut self) -> Result<Option<Item>, ParseError> {
+        let lines = self.reader.get_ref().line_tracker();
+        let mut source = BufSource::new(&mut self.reader, Some(lines));
+        let mut parser = Parser::new(&mut source, &mut self.context);
+
+        let mut result = Ok(None);
+        while let Ok(None) = result {
+            if self.parser.is_done() {
+                break;
+            }
+            result = self.parser.step(&mut parser);
+        }
+        let item = match result {
+            Ok(item) => item.map(ItemRef::into_owned),
+            Err(err) => return Err(self.context.locate(err)),
+        };
+
+        if self.parser.take_changed() {
+            self.channel.take();
+        }
+
+        Ok(item)
+    }
+}
+
+/// Returns the channel header read by `parser`, converting it if `channel` holds no conversion
+/// of its current state.
+fn header<'c>(parser: &ChannelParser<'static>, channel: &'c OnceLock<Channel>) -> &'c Channel {
+    channel.get_or_init(|| parser.channel().clone().into_owned())
+}
+
+/// Returns the channel header read by `parser`, moving it out of `parser` rather than copying it
+/// if `channel` holds no conversion of its current state.
+fn into_header(parser: ChannelParser<'static>, channel: OnceLock<Channel>) -> Channel {
+    match channel.into_inner() {
+        Some(channel) => channel,
+        None => parser.into_header().into_owned(),
+    }
+}
+
+/// Returns the channel header read by `parser` with `items` along with the warnings, failing if
+/// the input never contained a channel element.
+fn finish(
+    parser: ChannelParser<'static>,
+    channel: OnceLock<Channel>,
+    context: Context,
+    encoding: &'static str,
+    mut items: Vec<Item>,
+) -> Result<ParsedChannel, ParseError> {
+    if !parser.has_channel() {
+        return Err(context.locate(Error::Eof));
+    }
+
+    sort_items(&mut items, parser.item_order(), Item::about);
+    let mut channel = into_header(parser, channel);
+    channel.items = items;
+    Ok(ParsedChannel {
+        channel,
+        warnings: context.into_warnings(),
+        encoding,
+    })
+}
+
+/// A streaming reader for RSS channels that reads from tokio's `AsyncBufRead`.
+///
+/// The input is parsed as it arrives: the channel header is read when the reader is created, and
+/// each item is returned by `next_item` as soon as its end tag was read.
+///
+/// **Note**: This requires the `async` feature.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let reader: AsyncBufRead + Unpin = ...;
+/// let mut channel_reader = AsyncChannelReader::new(reader).await.unwrap();
+/// while let Some(item) = channel_reader.next_item().await {
+///     let item = item.unwrap();
+/// }
+/// ```
+#[cfg(feature = "async")]
+pub struct AsyncChannelReader<A: AsyncBufRead + Unpin> {
+    reader: Reader<Input<Decode<A>>>,
+    lines: Arc<Lines>,
+    buf: Vec<u8>,
+    context: Context,
+    /// Follows the events that were read ahead to find out where the elements they contain end.
+    lookahead: Context,
+    /// The events that were read but not parsed yet.
+    queue: VecDeque<QueuedEvent>,
+    encoding: &'static str,
+    parser: ChannelParser<'static>,
+    channel: OnceLock<Channel>,
+    pending: Option<Item>,
+}
+
+/// An event that was read ahead of the parser.
+#[cfg(feature = "async")]
+struct QueuedEvent {
+    event: Result<Event<'static>, Error>,
+    /// The position in the input after the event.
+    position: Position,
+    /// The number of open elements after the event, or `None` if the parser skips it.
+    depth: Option<usize>,
+}
+
+/// A source of the events that were read ahead of the parser.
+#[cfg(feature = "async")]
+struct QueueSource<'q> {
+    queue: &'q mut VecDeque<QueuedEvent>,
+    position: Position,
+}
+
+#[cfg(feature = "async")]
+impl<'q> XmlSource<'static> for QueueSource<'q> {
+    fn read_event(&mut self) -> Result<Event<'static>, Error> {
+        match self.queue.pop_front() {
+            Some(queued) => {
+                self.position = queued.position;
+                queued.event
+            }
+            // the events of an element are only parsed once all of them were read
+            None => Err(Error::Eof),
+        }
+    }
+
+    fn position(&self) -> Position {
+        self.position
+    }
+}
+
+#[cfg(feature = "async")]
+impl<A: AsyncBufRead + Unpin> AsyncChannelReader<A> {
+    /// Create a new reader and read the channel header.
+    ///
+    /// Fails if the input does not begin with an `<rss>` or `<rdf:RDF>` element, or if it ends
+    /// before a channel was found.
+    pub async fn new(reader: A) -> Result<Self, ParseError> {
+        AsyncChannelReader::with_options(reader, ParseOptions::default()).await
+    }
+
+    /// Create a new reader that uses the given options and read the channel header.
+    ///
+    /// The input is converted to UTF-8 as it is for `ChannelReader::with_options`.
+    pub async fn with_options(reader: A, options: ParseOptions) -> Result<Self, ParseError> {
+        let mut context = Context::new(options);
+        let (reader, detection) = Decode::new_async(reader, context.options().charset.as_deref())
+            .await
+            .map_err(|err| context.locate(Error::from(XmlError::from(err))))?;
+        let input = Input::new(reader, context.limits());
+        let lines = input.line_tracker();
+
+        let mut reader = Reader::from_reader(input);
+        reader.check_end_names(false);
+
+        for warning in detection.warnings {
+            context.warn(warning);
+        }
+
+        let mut channel_reader = AsyncChannelReader {
+            reader,
+            lines,
+            buf: Vec::new(),
+            lookahead: Context::new(context.options().clone()),
+            context,
+            queue: VecDeque::new(),
+            encoding: detection.encoding.name(),
+            parser: ChannelParser::default(),
+            channel: OnceLock::new(),
+            pending: None,
+        };
+
+        channel_reader.pending = channel_reader.read_item().await?;
+        if channel_reader.pending.is_none() && !channel_reader.parser.has_channel() {
+            return Err(channel_reader.context.locate(Error::Eof));
+        }
+
+        Ok(channel_reader)
+    }
+
+    /// Return the name of the encoding the input is read as, for example `windows-1252`.
+    pub fn encoding(&self) -> &'static str {
+        self.encoding
+    }
+
+    /// Return the channel header.
+    ///
+    /// The returned channel never contains items. Elements of the channel that appear after the
+    /// first item are added to it as the items are read.
+    pub fn channel(&self) -> &Channel {
+        header(&self.parser, &self.channel)
+    }
+
+    /// Consume the reader and return the channel header.
+    pub fn into_channel(self) -> Channel {
+        into_header(self.parser, self.channel)
+    }
+
+    /// Return the problems in the input that were skipped or repaired so far.
+    pub fn warnings(&self) -> &[ParseWarning] {
+        self.context.warnings()
+    }
+
+    /// Read the next item, returning `None` once the end of the channel was reached.
+    pub async fn next_item(&mut self) -> Option<Result<Item, ParseError>> {
+        if let Some(item) = self.pending.take() {
+            return Some(Ok(item));
+        }
+
+        if self.parser.is_done() {
+            return None;
+        }
+
+        match self.read_item().await {
+            Ok(item) => item.map(Ok),
+            Err(err) => {
+                self.parser.stop();
+                Some(Err(err))
+            }
+        }
+    }
+
+    /// Consume the reader and return the channel with `items` along with the warnings.
+    pub(crate) fn finish(self, items: Vec<Item>) -> Result<ParsedChannel, ParseError> {
+        finish(
+            self.parser,
+            self.channel,
+            self.context,
+            self.encoding,
+            items,
+        )
+    }
+
+    /// Read until the next item, adding any other elements to the channel header and the
+    /// location to errors.
+    ///
+    /// Events are read ahead of the parser until it can read an element with all of its
+    /// contents, so that the parser never waits for input.
+    async fn read_item(&mut self) -> Result<Option<Item>, ParseError> {
+        let mut item = None;
+
+        while item.is_none() && !self.parser.is_done() {
+            if !self.can_step() {
+                self.read_ahead().await;
+                continue;
+            }
+
+            let mut source = QueueSource {
+                queue: &mut self.queue,
+                position: self.context.position(),
+            };
+            let mut parser = Parser::new(&mut source, &mut self.context);
+            item = match self.parser.step(&mut parser) {
+                Ok(item) => item,
+                Err(err) => return Err(self.context.locate(err)),
+            };
+        }
+
+        if self.parser.take_changed() {
+            self.channel.take();
+        }
+
+        Ok(item.map(ItemRef::into_owned))
+    }
+
+    /// Whether the events that were read ahead contain everything the next step of the parser
+    /// reads: the next event, and the rest of the element if it is a start tag.
+    fn can_step(&self) -> bool {
+        if !self.context.reads_source() {
+            return true;
+        }
+
+        let mut queued = self.queue.iter().filter(|queued| queued.depth.is_some());
+        match queued.next() {
+            Some(first) if self.completes_step(first, true) => true,
+            Some(_) => queued.any(|queued| self.completes_step(queued, false)),
+            None => false,
+        }
+    }
+
+    /// Whether the parser can take its next step once `queued` was read. `first` is whether it
+    /// is the first event that the step reads.
+    fn completes_step(&self, queued: &QueuedEvent, first: bool) -> bool {
+        match (queued.depth, &queued.event) {
+            (Some(_), Ok(Event::Start(element))) if first => self.parser.reads_start_only(element),
+            (Some(after), _) => after <= self.context.depth(),
+            (None, _) => false,
+        }
+    }
+
+    /// Reads events until the parser can take its next step.
+    async fn read_ahead(&mut self) {
+        let mut first = !self.queue.iter().any(|queued| queued.depth.is_some());
+
+        loop {
+            self.buf.clear();
+            let event = match self.reader.read_event_into_async(&mut self.buf).await {
+                Ok(event) => Ok(event.into_owned()),
+                Err(err) => Err(read_error(err)),
+            };
+            let offset = self.reader.buffer_position();
+            let (line, column) = self.lines.position(offset);
+            let position = Position {
+                offset,
+                line,
+                column,
+            };
+
+            // the parser stops at errors, and at errors that the lookahead finds, which are
+            // found by the parser at the same event
+            let after = match event {
+                Ok(ref event) => self.lookahead.follow(event.clone()).unwrap_or(Some(0)),
+                Err(_) => Some(0),
+            };
+            let queued = QueuedEvent {
+                event,
+                position,
+                depth: after,
+            };
+            let complete = self.completes_step(&queued, first);
+            first = first && after.is_none();
+            self.queue.push_back(queued);
+
+            if complete {
+                return;
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for ChannelReader<R> {
+    type Item = Result<Item, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(item) = self.pending.take() {
+            return Some(Ok(item));
+        }
+
+        if self.parser.is_done() {
+            return None;
+        }
+
+        match self.next_item() {
+            Ok(item) => item.map(Ok),
+            Err(err) => {
+                self.parser.stop();
+                Some(Err(err))
+            }
+        }
+    }
+}
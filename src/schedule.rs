@@ -0,0 +1,214 @@
+// This file is part of rss.
+//
+// Copyright © 2015-2021 The rust-syndication Developers
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the MIT License and/or Apache 2.0 License.
+
+use std::iter::FromIterator;
+use std::time::Duration;
+
+#[cfg(feature = "chrono")]
+use chrono::Weekday;
+
+/// A set of hours of the day, from 0 to 23, such as the hours in the `skipHours` of a channel.
+///
+/// # Examples
+///
+/// ```
+/// use rss::HourSet;
+///
+/// let mut hours = HourSet::new();
+/// hours.insert(6);
+/// hours.insert(22);
+/// assert!(hours.contains(22));
+/// assert_eq!(hours.iter().collect::<Vec<_>>(), vec![6, 22]);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HourSet(u32);
+
+impl HourSet {
+    /// Create an empty set.
+    pub fn new() -> Self {
+        HourSet(0)
+    }
+
+    /// Whether the set contains `hour`.
+    pub fn contains(self, hour: u32) -> bool {
+        hour < 24 && self.0 & (1 << hour) != 0
+    }
+
+    /// Add `hour` to the set, returning whether it was added. Hours after 23 are not added.
+    pub fn insert(&mut self, hour: u32) -> bool {
+        if hour >= 24 || self.contains(hour) {
+            return false;
+        }
+        self.0 |= 1 << hour;
+        true
+    }
+
+    /// Remove `hour` from the set, returning whether it was in the set.
+    pub fn remove(&mut self, hour: u32) -> bool {
+        let contained = self.contains(hour);
+        if contained {
+            self.0 &= !(1 << hour);
+        }
+        contained
+    }
+
+    /// Whether the set is empty.
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Return the number of hours in the set.
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Return the hours in the set in ascending order.
+    pub fn iter(self) -> impl Iterator<Item = u32> {
+        (0..24).filter(move |hour| self.contains(*hour))
+    }
+}
+
+impl FromIterator<u32> for HourSet {
+    fn from_iter<I: IntoIterator<Item = u32>>(iter: I) -> Self {
+        let mut hours = HourSet::new();
+        for hour in iter {
+            hours.insert(hour);
+        }
+        hours
+    }
+}
+
+/// A set of days of the week, such as the days in the `skipDays` of a channel.
+///
+/// # Examples
+///
+/// ```
+/// use chrono::Weekday;
+/// use rss::DaySet;
+///
+/// let days = [Weekday::Sat, Weekday::Sun].iter().copied().collect::<DaySet>();
+/// assert!(days.contains(Weekday::Sun));
+/// assert!(!days.contains(Weekday::Mon));
+/// ```
+#[cfg(feature = "chrono")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DaySet(u8);
+
+#[cfg(feature = "chrono")]
+impl DaySet {
+    /// Create an empty set.
+    pub fn new() -> Self {
+        DaySet(0)
+    }
+
+    /// Whether the set contains `day`.
+    pub fn contains(self, day: Weekday) -> bool {
+        self.0 & day_bit(day) != 0
+    }
+
+    /// Add `day` to the set, returning whether it was added.
+    pub fn insert(&mut self, day: Weekday) -> bool {
+        let contained = self.contains(day);
+        self.0 |= day_bit(day);
+        !contained
+    }
+
+    /// Remove `day` from the set, returning whether it was in the set.
+    pub fn remove(&mut self, day: Weekday) -> bool {
+        let contained = self.contains(day);
+        self.0 &= !day_bit(day);
+        contained
+    }
+
+    /// Whether the set is empty.
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Return the number of days in the set.
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Return the days in the set, starting with Monday.
+    pub fn iter(self) -> impl Iterator<Item = Weekday> {
+        const DAYS: [Weekday; 7] = [
+            Weekday::Mon,
+            Weekday::Tue,
+            Weekday::Wed,
+            Weekday::Thu,
+            Weekday::Fri,
+            Weekday::Sat,
+            Weekday::Sun,
+        ];
+        DAYS.iter().copied().filter(move |day| self.contains(*day))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl FromIterator<Weekday> for DaySet {
+    fn from_iter<I: IntoIterator<Item = Weekday>>(iter: I) -> Self {
+        let mut days = DaySet::new();
+        for day in iter {
+            days.insert(day);
+        }
+        days
+    }
+}
+
+#[cfg(feature = "chrono")]
+fn day_bit(day: Weekday) -> u8 {
+    1 << day.num_days_from_monday()
+}
+
+/// Parses the number of minutes in a `ttl` element.
+pub(crate) fn parse_ttl(ttl: &str) -> Option<Duration> {
+    let minutes = ttl.trim().parse::<u64>().ok()?;
+    Some(Duration::from_secs(minutes.checked_mul(60)?))
+}
+
+/// Formats a duration as the number of minutes in a `ttl` element, rounding up to whole minutes.
+pub(crate) fn format_ttl(ttl: Duration) -> String {
+    let minutes = ttl.as_secs() / 60;
+    if ttl > Duration::from_secs(minutes * 60) {
+        (minutes + 1).to_string()
+    } else {
+        minutes.to_string()
+    }
+}
+
+/// Parses the hours in `skipHours`, ignoring the ones that are not valid. Hour 24, which some
+/// feeds use for midnight, is read as hour 0.
+pub(crate) fn parse_hours(hours: &[String]) -> HourSet {
+    hours
+        .iter()
+        .filter_map(|hour| hour.trim().parse::<u32>().ok())
+        .map(|hour| if hour == 24 { 0 } else { hour })
+        .collect()
+}
+
+/// Parses the days in `skipDays` case-insensitively, ignoring the ones that are not valid.
+#[cfg(feature = "chrono")]
+pub(crate) fn parse_days(days: &[String]) -> DaySet {
+    days.iter()
+        .filter_map(|day| day.trim().parse::<Weekday>().ok())
+        .collect()
+}
+
+/// Returns the name of a day as it is written in `skipDays`.
+#[cfg(feature = "chrono")]
+pub(crate) fn day_name(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "Monday",
+        Weekday::Tue => "Tuesday",
+        Weekday::Wed => "Wednesday",
+        Weekday::Thu => "Thursday",
+        Weekday::Fri => "Friday",
+        Weekday::Sat => "Saturday",
+        Weekday::Sun => "Sunday",
+    }
+}
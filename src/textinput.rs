@@ -5,17 +5,17 @@
 // This program is free software; you can redistribute it and/or modify
 // it under the terms of the MIT License and/or Apache 2.0 License.
 
+use std::collections::BTreeMap;
 use std::io::{BufRead, Write};
 
 use quick_xml::events::attributes::Attributes;
 use quick_xml::events::{BytesEnd, BytesStart, Event};
 use quick_xml::Error as XmlError;
 use quick_xml::Reader;
-use quick_xml::Writer;
 
+use crate::borrowed::{read_from_reader, TextInputRef};
 use crate::error::Error;
-use crate::toxml::{ToXml, WriterExt};
-use crate::util::element_text;
+use crate::toxml::{ToXml, WriterExt, XmlWriter};
 
 /// Represents a text input for an RSS channel.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -38,6 +38,12 @@ pub struct TextInput {
     pub name: String,
     /// The URL of the CGI script that processes the text input request.
     pub link: String,
+    /// The URI that identifies the text input in an RSS 1.0 feed, from its `rdf:about` attribute.
+    pub about: Option<String>,
+    /// The attributes of the text input element that are not part of RSS, such as `xml:lang`, keyed
+    /// by qualified name.
+    #[cfg_attr(feature = "builders", builder(setter(each = "extra_attr")))]
+    pub extra_attrs: BTreeMap<String, String>,
 }
 
 impl TextInput {
@@ -168,49 +174,135 @@ impl TextInput {
     {
         self.link = link.into();
     }
+
+    /// Return the URI that identifies this text input in an RSS 1.0 feed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::TextInput;
+    ///
+    /// let mut text_input = TextInput::default();
+    /// text_input.set_about("http://example.com/search".to_string());
+    /// assert_eq!(text_input.about(), Some("http://example.com/search"));
+    /// ```
+    pub fn about(&self) -> Option<&str> {
+        self.about.as_deref()
+    }
+
+    /// Set the URI that identifies this text input in an RSS 1.0 feed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::TextInput;
+    ///
+    /// let mut text_input = TextInput::default();
+    /// text_input.set_about("http://example.com/search".to_string());
+    /// ```
+    pub fn set_about<V>(&mut self, about: V)
+    where
+        V: Into<Option<String>>,
+    {
+        self.about = about.into();
+    }
+
+    /// Return the attributes of this text input that are not part of RSS.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use rss::TextInput;
+    ///
+    /// let mut extra_attrs = BTreeMap::new();
+    /// extra_attrs.insert("xml:lang".to_string(), "en".to_string());
+    ///
+    /// let mut text_input = TextInput::default();
+    /// text_input.set_extra_attrs(extra_attrs);
+    /// assert_eq!(text_input.extra_attrs()["xml:lang"], "en");
+    /// ```
+    pub fn extra_attrs(&self) -> &BTreeMap<String, String> {
+        &self.extra_attrs
+    }
+
+    /// Set the attributes of this text input that are not part of RSS.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use rss::TextInput;
+    ///
+    /// let mut text_input = TextInput::default();
+    /// text_input.set_extra_attrs(BTreeMap::new());
+    /// ```
+    pub fn set_extra_attrs<V>(&mut self, extra_attrs: V)
+    where
+        V: Into<BTreeMap<String, String>>,
+    {
+        self.extra_attrs = extra_attrs.into();
+    }
 }
 
 impl TextInput {
     /// Builds a TextInput from source XML
-    pub fn from_xml<R: BufRead>(reader: &mut Reader<R>, _: Attributes) -> Result<Self, Error> {
-        let mut text_input = TextInput::default();
-        let mut buf = Vec::new();
-
-        loop {
-            match reader.read_event(&mut buf)? {
-                Event::Start(element) => match element.name() {
-                    b"title" => text_input.title = element_text(reader)?.unwrap_or_default(),
-                    b"description" => {
-                        text_input.description = element_text(reader)?.unwrap_or_default()
-                    }
-                    b"name" => text_input.name = element_text(reader)?.unwrap_or_default(),
-                    b"link" => text_input.link = element_text(reader)?.unwrap_or_default(),
-                    n => reader.read_to_end(n, &mut Vec::new())?,
-                },
-                Event::End(_) => break,
-                Event::Eof => return Err(Error::Eof),
-                _ => {}
-            }
-
-            buf.clear();
-        }
+    pub fn from_xml<R: BufRead>(reader: &mut Reader<R>, atts: Attributes) -> Result<Self, Error> {
+        read_from_reader(reader, |parser| {
+            parser.read_text_input(&BTreeMap::new(), atts)
+        })
+        .map(TextInputRef::into_owned)
+    }
+
+    /// Writes this text input as an RSS 1.0 text input.
+    pub(crate) fn write_rdf<W: Write>(&self, writer: &mut XmlWriter<W>) -> Result<(), XmlError> {
+        let name = "textinput";
+        let mut element = BytesStart::new(name);
+        element.push_attribute(("rdf:about", self.rdf_about()));
+        element.extend_attributes(
+            self.extra_attrs
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str())),
+        );
+        writer.write_event(Event::Start(element))?;
 
-        Ok(text_input)
+        writer.write_text_element("title", &self.title)?;
+        writer.write_text_element("description", &self.description)?;
+        writer.write_text_element("name", &self.name)?;
+        writer.write_text_element("link", &self.link)?;
+
+        writer.write_event(Event::End(BytesEnd::new(name)))?;
+        Ok(())
+    }
+
+    /// The URI that identifies this text input in an RSS 1.0 feed, which defaults to its link.
+    pub(crate) fn rdf_about(&self) -> &str {
+        self.about.as_deref().unwrap_or(&self.link)
     }
 }
 
 impl ToXml for TextInput {
-    fn to_xml<W: Write>(&self, writer: &mut Writer<W>) -> Result<(), XmlError> {
-        let name = b"textInput";
+    fn to_xml<W: Write>(&self, writer: &mut XmlWriter<W>) -> Result<(), XmlError> {
+        let name = "textInput";
+        let mut element = BytesStart::new(name);
+        if let Some(about) = self.about.as_ref() {
+            element.push_attribute(("rdf:about", about.as_str()));
+        }
+
+        element.extend_attributes(
+            self.extra_attrs
+                .iter()
+                .map(|(k, v)| (k.as_str(), v.as_str())),
+        );
 
-        writer.write_event(Event::Start(BytesStart::borrowed(name, name.len())))?;
+        writer.write_event(Event::Start(element))?;
 
-        writer.write_text_element(b"title", &self.title)?;
-        writer.write_text_element(b"description", &self.description)?;
-        writer.write_text_element(b"name", &self.name)?;
-        writer.write_text_element(b"link", &self.link)?;
+        writer.write_text_element("title", &self.title)?;
+        writer.write_text_element("description", &self.description)?;
+        writer.write_text_element("name", &self.name)?;
+        writer.write_text_element("link", &self.link)?;
 
-        writer.write_event(Event::End(BytesEnd::borrowed(name)))?;
+        writer.write_event(Event::End(BytesEnd::new(name)))?;
         Ok(())
     }
 }
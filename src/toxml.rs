@@ -8,20 +8,84 @@
 use std::collections::BTreeMap;
 use std::io::Write;
 
-use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+use quick_xml::events::{BytesCData, BytesEnd, BytesStart, BytesText, Event};
 use quick_xml::Error as XmlError;
 use quick_xml::Writer;
 
+use crate::writer::{EmptyElements, TextStyle, WriteOptions};
+
+/// Writes XML events, writing the elements without content in the form that
+/// `WriteOptions::empty_elements` asks for.
+///
+/// Markup that is written as raw XML is written as it is.
+pub struct XmlWriter<W: Write> {
+    writer: Writer<W>,
+    empty_elements: Option<EmptyElements>,
+    /// A start tag that is not written until it is known whether the element has content.
+    start: Option<BytesStart<'static>>,
+}
+
+impl<W: Write> XmlWriter<W> {
+    /// Creates a writer that indents and writes elements without content as `options` ask.
+    pub fn new(inner: W, options: &WriteOptions) -> Self {
+        let writer = match options.indent {
+            Some((indent_char, indent_size)) => {
+                Writer::new_with_indent(inner, indent_char, indent_size)
+            }
+            None => Writer::new(inner),
+        };
+        XmlWriter {
+            writer,
+            empty_elements: options.empty_elements,
+            start: None,
+        }
+    }
+
+    pub fn write_event<'a, E: AsRef<Event<'a>>>(&mut self, event: E) -> Result<(), XmlError> {
+        let event = event.as_ref();
+        if let Some(start) = self.start.take() {
+            match *event {
+                Event::End(_) => return self.writer.write_event(Event::Empty(start)),
+                Event::Text(ref text) if text.is_empty() => {
+                    self.start = Some(start);
+                    return Ok(());
+                }
+                _ => self.writer.write_event(Event::Start(start))?,
+            }
+        }
+
+        match (self.empty_elements, event) {
+            (Some(EmptyElements::SelfClosing), Event::Start(element)) => {
+                self.start = Some(element.clone().into_owned());
+                Ok(())
+            }
+            (Some(EmptyElements::Expanded), Event::Empty(element)) => {
+                self.writer.write_event(Event::Start(element.borrow()))?;
+                // the empty text keeps an indenting writer from breaking the line before the
+                // end tag
+                self.writer.write_event(Event::Text(BytesText::new("")))?;
+                self.writer.write_event(Event::End(element.to_end()))
+            }
+            _ => self.writer.write_event(event),
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner()
+    }
+}
+
 pub trait ToXml {
-    fn to_xml<W: Write>(&self, writer: &mut Writer<W>) -> Result<(), XmlError>;
+    fn to_xml<W: Write>(&self, writer: &mut XmlWriter<W>) -> Result<(), XmlError>;
 
     fn used_namespaces(&self) -> BTreeMap<String, String> {
         BTreeMap::new()
     }
 }
 
+#[allow(clippy::needless_lifetimes)]
 impl<'a, T: ToXml> ToXml for &'a T {
-    fn to_xml<W: Write>(&self, writer: &mut Writer<W>) -> Result<(), XmlError> {
+    fn to_xml<W: Write>(&self, writer: &mut XmlWriter<W>) -> Result<(), XmlError> {
         (*self).to_xml(writer)
     }
 }
@@ -29,19 +93,39 @@ impl<'a, T: ToXml> ToXml for &'a T {
 pub trait WriterExt {
     fn write_text_element<N, T>(&mut self, name: N, text: T) -> Result<(), XmlError>
     where
-        N: AsRef<[u8]>,
+        N: AsRef<str>,
         T: AsRef<str>;
 
     fn write_text_elements<N, T, I>(&mut self, name: N, values: I) -> Result<(), XmlError>
     where
-        N: AsRef<[u8]>,
+        N: AsRef<str>,
         T: AsRef<str>,
         I: IntoIterator<Item = T>;
 
     fn write_cdata_element<N, T>(&mut self, name: N, text: T) -> Result<(), XmlError>
     where
-        N: AsRef<[u8]>,
-        T: AsRef<[u8]>;
+        N: AsRef<str>,
+        T: AsRef<str>;
+
+    fn write_styled_element<N, T>(
+        &mut self,
+        name: N,
+        text: T,
+        style: TextStyle,
+    ) -> Result<(), XmlError>
+    where
+        N: AsRef<str>,
+        T: AsRef<str>;
+
+    fn write_raw_element<N, T>(&mut self, name: N, markup: T) -> Result<(), XmlError>
+    where
+        N: AsRef<str>,
+        T: AsRef<str>;
+
+    fn write_resource_element<N, T>(&mut self, name: N, resource: T) -> Result<(), XmlError>
+    where
+        N: AsRef<str>,
+        T: AsRef<str>;
 
     fn write_object<T>(&mut self, object: T) -> Result<(), XmlError>
     where
@@ -53,22 +137,22 @@ pub trait WriterExt {
         I: IntoIterator<Item = T>;
 }
 
-impl<W: Write> WriterExt for Writer<W> {
+impl<W: Write> WriterExt for XmlWriter<W> {
     fn write_text_element<N, T>(&mut self, name: N, text: T) -> Result<(), XmlError>
     where
-        N: AsRef<[u8]>,
+        N: AsRef<str>,
         T: AsRef<str>,
     {
         let name = name.as_ref();
-        self.write_event(Event::Start(BytesStart::borrowed(name, name.len())))?;
-        self.write_event(Event::Text(BytesText::from_plain_str(text.as_ref())))?;
-        self.write_event(Event::End(BytesEnd::borrowed(name)))?;
+        self.write_event(Event::Start(BytesStart::new(name)))?;
+        self.write_event(Event::Text(BytesText::new(text.as_ref())))?;
+        self.write_event(Event::End(BytesEnd::new(name)))?;
         Ok(())
     }
 
     fn write_text_elements<N, T, I>(&mut self, name: N, values: I) -> Result<(), XmlError>
     where
-        N: AsRef<[u8]>,
+        N: AsRef<str>,
         T: AsRef<str>,
         I: IntoIterator<Item = T>,
     {
@@ -81,13 +165,55 @@ impl<W: Write> WriterExt for Writer<W> {
 
     fn write_cdata_element<N, T>(&mut self, name: N, text: T) -> Result<(), XmlError>
     where
-        N: AsRef<[u8]>,
-        T: AsRef<[u8]>,
+        N: AsRef<str>,
+        T: AsRef<str>,
+    {
+        let name = name.as_ref();
+        // a CDATA section cannot contain `]]>`, so the text is split into two sections there
+        let text = text.as_ref().replace("]]>", "]]]]><![CDATA[>");
+        self.write_event(Event::Start(BytesStart::new(name)))?;
+        self.write_event(Event::CData(BytesCData::new(text)))?;
+        self.write_event(Event::End(BytesEnd::new(name)))?;
+        Ok(())
+    }
+
+    fn write_styled_element<N, T>(
+        &mut self,
+        name: N,
+        text: T,
+        style: TextStyle,
+    ) -> Result<(), XmlError>
+    where
+        N: AsRef<str>,
+        T: AsRef<str>,
+    {
+        match style {
+            TextStyle::Escaped => self.write_text_element(name, text),
+            TextStyle::CData => self.write_cdata_element(name, text),
+        }
+    }
+
+    fn write_raw_element<N, T>(&mut self, name: N, markup: T) -> Result<(), XmlError>
+    where
+        N: AsRef<str>,
+        T: AsRef<str>,
+    {
+        let name = name.as_ref();
+        self.write_event(Event::Start(BytesStart::new(name)))?;
+        self.write_event(Event::Text(BytesText::from_escaped(markup.as_ref())))?;
+        self.write_event(Event::End(BytesEnd::new(name)))?;
+        Ok(())
+    }
+
+    fn write_resource_element<N, T>(&mut self, name: N, resource: T) -> Result<(), XmlError>
+    where
+        N: AsRef<str>,
+        T: AsRef<str>,
     {
         let name = name.as_ref();
-        self.write_event(Event::Start(BytesStart::borrowed(name, name.len())))?;
-        self.write_event(Event::CData(BytesText::from_escaped(text.as_ref())))?;
-        self.write_event(Event::End(BytesEnd::borrowed(name)))?;
+        let mut element = BytesStart::new(name);
+        element.push_attribute(("rdf:resource", resource.as_ref()));
+        self.write_event(Event::Empty(element))?;
         Ok(())
     }
 
@@ -0,0 +1,168 @@
+// This file is part of rss.
+//
+// Copyright © 2015-2021 The rust-syndication Developers
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the MIT License and/or Apache 2.0 License.
+
+use std::io::Write;
+
+use quick_xml::Error as XmlError;
+
+use crate::extension::Extension;
+use crate::toxml::{ToXml, XmlWriter};
+
+/// Represents an element without a namespace prefix that is not part of RSS, such as
+/// `<channel><foo>`.
+///
+/// Unknown elements are kept so that reading and writing a channel does not lose them. They are
+/// written after the RSS element they followed when they were read.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "builders", derive(Builder))]
+#[cfg_attr(
+    feature = "builders",
+    builder(
+        setter(into),
+        default,
+        build_fn(name = "build_impl", private, error = "never::Never")
+    )
+)]
+pub struct UnknownElement {
+    /// The element, along with its attributes and children.
+    pub element: Extension,
+    /// The local name of the RSS element that came before this element in its parent, or `None`
+    /// if it came before all of them.
+    pub after: Option<String>,
+}
+
+impl UnknownElement {
+    /// Return the element, along with its attributes and children.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::extension::Extension;
+    /// use rss::UnknownElement;
+    ///
+    /// let mut extension = Extension::default();
+    /// extension.set_name("foo");
+    ///
+    /// let mut unknown = UnknownElement::default();
+    /// unknown.set_element(extension);
+    /// assert_eq!(unknown.element().name(), "foo");
+    /// ```
+    pub fn element(&self) -> &Extension {
+        &self.element
+    }
+
+    /// Set the element, along with its attributes and children.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::extension::Extension;
+    /// use rss::UnknownElement;
+    ///
+    /// let mut unknown = UnknownElement::default();
+    /// unknown.set_element(Extension::default());
+    /// ```
+    pub fn set_element<V>(&mut self, element: V)
+    where
+        V: Into<Extension>,
+    {
+        self.element = element.into();
+    }
+
+    /// Return the local name of the RSS element that came before this element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::UnknownElement;
+    ///
+    /// let mut unknown = UnknownElement::default();
+    /// unknown.set_after("title".to_string());
+    /// assert_eq!(unknown.after(), Some("title"));
+    /// ```
+    pub fn after(&self) -> Option<&str> {
+        self.after.as_deref()
+    }
+
+    /// Set the local name of the RSS element that came before this element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::UnknownElement;
+    ///
+    /// let mut unknown = UnknownElement::default();
+    /// unknown.set_after("title".to_string());
+    /// ```
+    pub fn set_after<V>(&mut self, after: V)
+    where
+        V: Into<Option<String>>,
+    {
+        self.after = after.into();
+    }
+}
+
+/// Writes the unknown elements of a channel or item next to the RSS elements they followed.
+pub(crate) struct UnknownWriter<'a> {
+    elements: &'a [UnknownElement],
+    written: Vec<bool>,
+}
+
+impl<'a> UnknownWriter<'a> {
+    pub fn new(elements: &'a [UnknownElement]) -> Self {
+        UnknownWriter {
+            elements,
+            written: vec![false; elements.len()],
+        }
+    }
+
+    /// Writes the unknown elements that came after the RSS elements named `after`, or before all
+    /// of them if `after` is `None`.
+    pub fn write_after<W: Write>(
+        &mut self,
+        writer: &mut XmlWriter<W>,
+        after: Option<&str>,
+    ) -> Result<(), XmlError> {
+        for (element, written) in self.elements.iter().zip(&mut self.written) {
+            if !*written && element.after.as_deref() == after {
+                element.element.to_xml(writer)?;
+                *written = true;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the unknown elements that were not written yet.
+    pub fn remaining(&self) -> Vec<UnknownElement> {
+        self.elements
+            .iter()
+            .zip(&self.written)
+            .filter(|(_, written)| !**written)
+            .map(|(element, _)| element.clone())
+            .collect()
+    }
+
+    /// Writes the unknown elements that were not written yet.
+    pub fn write_rest<W: Write>(&mut self, writer: &mut XmlWriter<W>) -> Result<(), XmlError> {
+        for (element, written) in self.elements.iter().zip(&mut self.written) {
+            if !*written {
+                element.element.to_xml(writer)?;
+                *written = true;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "builders")]
+impl UnknownElementBuilder {
+    /// Builds a new `UnknownElement`.
+    pub fn build(&self) -> UnknownElement {
+        self.build_impl().unwrap()
+    }
+}
@@ -5,32 +5,141 @@
 // This program is free software; you can redistribute it and/or modify
 // it under the terms of the MIT License and/or Apache 2.0 License.
 
-use std::io::BufRead;
-
-use quick_xml::events::Event;
-use quick_xml::Reader;
-
-use crate::error::Error;
-
-pub fn element_text<R: BufRead>(reader: &mut Reader<R>) -> Result<Option<String>, Error> {
-    let mut content: Option<String> = None;
-    let mut buf = Vec::new();
-    let mut skip_buf = Vec::new();
-
-    loop {
-        match reader.read_event(&mut buf)? {
-            Event::Start(element) => {
-                reader.read_to_end(element.name(), &mut skip_buf)?;
-            }
-            Event::Text(element) | Event::CData(element) => {
-                let text = element.unescape_and_decode(reader)?;
-                content = Some(text);
-            }
-            Event::End(_) | Event::Eof => break,
-            _ => {}
+use std::collections::{BTreeMap, HashMap};
+use std::str;
+
+use crate::extension::util::known_prefix;
+
+/// The namespaces used for the core elements of RSS 0.90, RSS 1.0 and some RSS 2.0 feeds.
+const RSS_NAMESPACES: &[&str] = &[
+    RSS1_NAMESPACE,
+    "http://channel.netscape.com/rdf/simple/0.9/",
+    "http://my.netscape.com/rdf/simple/0.9/",
+    "http://backend.userland.com/rss2",
+    "http://blogs.law.harvard.edu/tech/rss",
+];
+
+/// The RSS 1.0 XML namespace.
+pub(crate) const RSS1_NAMESPACE: &str = "http://purl.org/rss/1.0/";
+
+/// The RDF XML namespace.
+pub(crate) const RDF_NAMESPACE: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+
+/// The Content XML namespace.
+pub(crate) const CONTENT_NAMESPACE: &str = "http://purl.org/rss/1.0/modules/content/";
+
+/// Whether the attribute named `key` is the RDF attribute named `local_name`, such as
+/// `rdf:about`.
+///
+/// Unprefixed attributes are accepted as well if `rss1` is set, because many RSS 1.0 feeds leave
+/// out the prefix. In other documents they are not RDF attributes.
+pub fn is_rdf_attribute(
+    namespaces: &BTreeMap<String, String>,
+    key: &[u8],
+    local_name: &str,
+    rss1: bool,
+) -> bool {
+    let name = QName::resolve(namespaces, key);
+    match name.prefix {
+        Some(_) => name.matches(RDF_NAMESPACE, "rdf", local_name),
+        None => rss1 && name.local_name == local_name.as_bytes(),
+    }
+}
+
+/// Whether the attribute named `key` declares a namespace.
+pub fn is_namespace_declaration(key: &[u8]) -> bool {
+    key == b"xmlns" || key.starts_with(b"xmlns:")
+}
+
+/// Sorts `items` by the position of their `rdf:about` URI in `order`, keeping the items that
+/// are not in `order` at the end.
+pub fn sort_items<T, F>(items: &mut [T], order: &[String], about: F)
+where
+    F: Fn(&T) -> Option<&str>,
+{
+    if order.is_empty() {
+        return;
+    }
+
+    let positions = order
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(position, resource)| (resource.as_str(), position))
+        .collect::<HashMap<_, _>>();
+    items.sort_by_key(|item| {
+        about(item)
+            .and_then(|about| positions.get(about).copied())
+            .unwrap_or(order.len())
+    });
+}
+
+/// An element name resolved against the namespaces in scope.
+#[derive(Debug, Clone, Copy)]
+pub struct QName<'n, 'e> {
+    /// The prefix of the name, if any.
+    pub prefix: Option<&'e [u8]>,
+    /// The namespace the name is bound to, if it is declared.
+    pub namespace: Option<&'n str>,
+    /// The local part of the name.
+    pub local_name: &'e [u8],
+}
+
+impl<'n, 'e> QName<'n, 'e> {
+    /// Resolve a qualified element name using the namespaces in scope.
+    pub fn resolve(namespaces: &'n BTreeMap<String, String>, name: &'e [u8]) -> Self {
+        let (prefix, local_name) = match name.iter().position(|b| *b == b':') {
+            Some(index) if index > 0 => (Some(&name[..index]), &name[index + 1..]),
+            _ => (None, name),
+        };
+
+        let namespace = str::from_utf8(prefix.unwrap_or(b""))
+            .ok()
+            .and_then(|prefix| namespaces.get(prefix))
+            .map(String::as_str);
+
+        QName {
+            prefix,
+            namespace,
+            local_name,
+        }
+    }
+
+    /// Whether this is the name of a core RSS element.
+    ///
+    /// Unprefixed names without a default namespace and names in one of the RSS namespaces are
+    /// core elements.
+    pub fn is_rss(&self) -> bool {
+        match self.namespace {
+            Some(namespace) => RSS_NAMESPACES.contains(&namespace),
+            None => self.prefix.is_none(),
         }
-        buf.clear();
     }
 
-    Ok(content)
+    /// Whether this name has the given namespace and local name.
+    ///
+    /// An undeclared prefix matches when it is equal to `prefix`, for compatibility with feeds
+    /// that do not declare the namespaces they use.
+    pub fn matches(&self, namespace: &str, prefix: &str, local_name: &str) -> bool {
+        if self.local_name != local_name.as_bytes() {
+            return false;
+        }
+
+        match self.namespace {
+            Some(ns) => ns == namespace,
+            None => self.prefix == Some(prefix.as_bytes()),
+        }
+    }
+
+    /// The prefix an extension element with this name is stored under.
+    ///
+    /// Unprefixed elements in a known extension namespace use the conventional prefix of that
+    /// namespace, other unprefixed elements are not extensions.
+    pub fn extension_prefix(&self) -> Option<&'e [u8]> {
+        self.prefix.or_else(|| {
+            self.namespace
+                .and_then(known_prefix)
+                .map(|prefix| prefix.as_bytes())
+        })
+    }
 }
@@ -176,6 +176,7 @@ impl Validate for Category {
     }
 }
 
+#[allow(clippy::useless_vec)]
 impl Validate for Cloud {
     fn validate(&self) -> Result<(), ValidationError> {
         let port = self.port().parse::<i64>()?;
@@ -0,0 +1,901 @@
+// This file is part of rss.
+//
+// Copyright © 2015-2021 The rust-syndication Developers
+//
+// This program is free software; you can redistribute it and/or modify
+// it under the terms of the MIT License and/or Apache 2.0 License.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{self, Write};
+use std::str;
+
+use encoding_rs::{CoderResult, Encoder, Encoding, UTF_8};
+use quick_xml::escape::escape;
+use quick_xml::events::{BytesDecl, BytesText, Event};
+
+use quick_xml::Error as XmlError;
+
+use crate::channel::{write_rss_end, write_rss_start, Channel};
+use crate::error::Error;
+use crate::extension::dublincore::DublinCoreExtension;
+use crate::extension::ExtensionMap;
+use crate::item::Item;
+use crate::namespaces::Namespaces;
+use crate::toxml::{ToXml, XmlWriter};
+use crate::unknown::{UnknownElement, UnknownWriter};
+
+/// The maximum number of items in an RSS 0.91 channel.
+const RSS_0_91_MAX_ITEMS: usize = 15;
+
+/// Options that control how a channel is written.
+///
+/// # Examples
+///
+/// ```
+/// use rss::{RssVersion, Stylesheet, TextStyle, WriteOptions};
+///
+/// let options = WriteOptions {
+///     version: RssVersion::V0_92,
+///     item_description: TextStyle::Escaped,
+///     stylesheets: vec![Stylesheet::xsl("/feed.xsl")],
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct WriteOptions {
+    /// The version of RSS to write.
+    ///
+    /// Elements that the version does not support are left out, or written as an equivalent
+    /// element where there is one, and everything that is left out is reported as a
+    /// `WriteWarning`.
+    pub version: RssVersion,
+    /// The character to indent with and the number of them for each level of nesting, or `None`
+    /// to write the document without indentation. Defaults to `None`.
+    pub indent: Option<(u8, usize)>,
+    /// Whether to start the document with an XML declaration. Defaults to `true`.
+    pub xml_declaration: bool,
+    /// The label of the encoding to write the document in, such as `iso-8859-1`, or `None` for
+    /// UTF-8.
+    ///
+    /// Characters that the encoding cannot represent are written as character references, which
+    /// are not recognised in CDATA sections. Writing fails with `Error::Encoding` if the encoding
+    /// is unknown or cannot be written, as is the case for UTF-16.
+    pub encoding: Option<String>,
+    /// The stylesheets to link with `<?xml-stylesheet?>` processing instructions, which
+    /// browsers use to render the feed. Defaults to none.
+    pub stylesheets: Vec<Stylesheet>,
+    /// How to write the description of the channel. Defaults to `TextStyle::Escaped`.
+    pub channel_description: TextStyle,
+    /// How to write the descriptions of items. Defaults to `TextStyle::CData`.
+    pub item_description: TextStyle,
+    /// How to write the `content:encoded` elements of items. Defaults to `TextStyle::CData`.
+    pub content: TextStyle,
+    /// How to write elements without content, or `None` to write each element the way it is
+    /// usually written. Defaults to `None`.
+    ///
+    /// Markup that is kept as raw XML, such as an XHTML description, is written as it was read.
+    pub empty_elements: Option<EmptyElements>,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        WriteOptions {
+            version: RssVersion::default(),
+            indent: None,
+            xml_declaration: true,
+            encoding: None,
+            stylesheets: Vec::new(),
+            channel_description: TextStyle::Escaped,
+            item_description: TextStyle::CData,
+            content: TextStyle::CData,
+            empty_elements: None,
+        }
+    }
+}
+
+/// How the text of an element is written.
+///
+/// Elements whose text is XHTML markup are always written as markup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TextStyle {
+    /// Text with the characters that are special in XML escaped, such as `a &amp; b`.
+    Escaped,
+    /// A CDATA section, such as `<![CDATA[a & b]]>`.
+    CData,
+}
+
+/// How elements without content are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EmptyElements {
+    /// As a self-closing tag, such as `<enclosure url="..."/>`.
+    SelfClosing,
+    /// As a start tag followed by an end tag, such as `<enclosure url="..."></enclosure>`.
+    Expanded,
+}
+
+/// A stylesheet linked with an `<?xml-stylesheet?>` processing instruction.
+///
+/// # Examples
+///
+/// ```
+/// use rss::Stylesheet;
+///
+/// let stylesheet = Stylesheet::css("/feed.css");
+/// assert_eq!(stylesheet.mime_type, "text/css");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Stylesheet {
+    /// The URL of the stylesheet.
+    pub href: String,
+    /// The MIME type of the stylesheet, such as `text/xsl` or `text/css`.
+    pub mime_type: String,
+}
+
+impl Stylesheet {
+    /// Create a link to an XSLT stylesheet.
+    pub fn xsl<V: Into<String>>(href: V) -> Self {
+        Stylesheet {
+            href: href.into(),
+            mime_type: "text/xsl".to_string(),
+        }
+    }
+
+    /// Create a link to a CSS stylesheet.
+    pub fn css<V: Into<String>>(href: V) -> Self {
+        Stylesheet {
+            href: href.into(),
+            mime_type: "text/css".to_string(),
+        }
+    }
+
+    /// Returns the content of the processing instruction that links this stylesheet.
+    fn instruction(&self) -> String {
+        format!(
+            "xml-stylesheet type=\"{}\" href=\"{}\"",
+            escape(&self.mime_type),
+            escape(&self.href)
+        )
+    }
+}
+
+/// A version of RSS that a channel can be written as.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RssVersion {
+    /// RSS 0.91, which has no namespaces and at most 15 items, and requires a `language`.
+    V0_91,
+    /// RSS 0.92, which adds enclosures, sources and categories to the items of RSS 0.91.
+    V0_92,
+    /// RSS 1.0, which is based on RDF and supports extensions as modules.
+    V1_0,
+    /// RSS 2.0.
+    #[default]
+    V2_0,
+}
+
+impl RssVersion {
+    /// Return the value of the `version` attribute for this version.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rss::RssVersion;
+    ///
+    /// assert_eq!(RssVersion::V0_91.as_str(), "0.91");
+    /// ```
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RssVersion::V0_91 => "0.91",
+            RssVersion::V0_92 => "0.92",
+            RssVersion::V1_0 => "1.0",
+            RssVersion::V2_0 => "2.0",
+        }
+    }
+}
+
+impl fmt::Display for RssVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Part of a channel that was left out when it was written as an older version of RSS, or a
+/// prefix that is not bound to any namespace and was declared with a placeholder namespace.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WriteWarning {
+    /// A description of what was left out.
+    pub message: String,
+    /// The path of the element or attribute in the channel, for example
+    /// `channel/item[12]/enclosure`.
+    ///
+    /// The index of an element is only included when it is not the first element with that
+    /// name in its parent.
+    pub path: String,
+}
+
+impl fmt::Display for WriteWarning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at {}", self.message, self.path)
+    }
+}
+
+/// A streaming writer for RSS channels.
+///
+/// The channel header (everything but the items) is written when the first item is written,
+/// after which the items are written one at a time with `write_item`, and `finish` closes the
+/// document. This allows large feeds to be written without keeping every item in memory.
+///
+/// Namespaces are declared on the root element, so the namespaces that the items use should be
+/// registered before the first item is written, with `register_namespace` or `register_item`.
+/// An item that uses a namespace that is not declared on the root element declares it itself.
+///
+/// # Examples
+///
+/// ```
+/// use rss::extension::itunes::{ITunesItemExtension, NAMESPACE};
+/// use rss::{ChannelBuilder, ChannelWriter, ItemBuilder};
+///
+/// let channel = ChannelBuilder::default().title("Title").build();
+/// let mut writer = ChannelWriter::new(Vec::new(), &channel);
+/// writer.register_namespace("itunes", NAMESPACE).unwrap();
+///
+/// for title in &["First", "Second"] {
+///     let mut itunes = ITunesItemExtension::default();
+///     itunes.set_duration("01:00".to_string());
+///     let item = ItemBuilder::default()
+///         .title(title.to_string())
+///         .itunes_ext(itunes)
+///         .build();
+///     writer.write_item(&item).unwrap();
+/// }
+///
+/// let output = String::from_utf8(writer.finish().unwrap()).unwrap();
+/// assert!(output.contains(r#"xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd""#));
+/// assert!(output.contains("<item><title>Second</title>"));
+/// ```
+pub struct ChannelWriter<W: Write> {
+    writer: XmlWriter<Encode<W>>,
+    options: WriteOptions,
+    /// The channel header, before it is written.
+    channel: Channel,
+    /// The namespaces of the typed extensions of the registered items, keyed by prefix.
+    registered: BTreeMap<String, String>,
+    /// The namespaces declared on the root element, once the header is written.
+    namespaces: Option<Namespaces>,
+    /// The unknown elements that come after the items.
+    trailing: Vec<UnknownElement>,
+    items: usize,
+    warnings: Vec<WriteWarning>,
+}
+
+impl<W: Write> ChannelWriter<W> {
+    /// Create a new writer for `channel` as RSS 2.0.
+    ///
+    /// The items of `channel` are not written, but the namespaces of their typed extensions are
+    /// declared.
+    pub fn new(writer: W, channel: &Channel) -> Self {
+        ChannelWriter::create(writer, channel, WriteOptions::default(), UTF_8)
+    }
+
+    /// Create a new writer for `channel` with the given options.
+    ///
+    /// Elements that the requested version of RSS does not support are left out, as with
+    /// `Channel::write_with_options`. Fails with `Error::Version` for RSS 1.0, which lists the
+    /// items before the first item is written, and with `Error::Encoding` if the encoding cannot
+    /// be written.
+    pub fn with_options(
+        writer: W,
+        channel: &Channel,
+        options: &WriteOptions,
+    ) -> Result<Self, Error> {
+        if options.version == RssVersion::V1_0 {
+            return Err(Error::Version {
+                version: options.version,
+                reason: "the items cannot be written one at a time".to_string(),
+            });
+        }
+        let encoding = output_encoding(options)?;
+        Ok(ChannelWriter::create(
+            writer,
+            channel,
+            options.clone(),
+            encoding,
+        ))
+    }
+
+    fn create(
+        output: W,
+        channel: &Channel,
+        options: WriteOptions,
+        encoding: &'static Encoding,
+    ) -> Self {
+        let writer = XmlWriter::new(Encode::new(output, encoding), &options);
+        let mut registered = BTreeMap::new();
+        for item in &channel.items {
+            registered.extend(item.used_namespaces());
+        }
+        let mut channel = channel.clone();
+        channel.items.clear();
+        ChannelWriter {
+            writer,
+            options,
+            channel,
+            registered,
+            namespaces: None,
+            trailing: Vec::new(),
+            items: 0,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Declare `namespace` with `prefix` on the root element.
+    ///
+    /// Fails with `Error::HeaderWritten` if an item has already been written.
+    pub fn register_namespace<P, N>(&mut self, prefix: P, namespace: N) -> Result<&mut Self, Error>
+    where
+        P: Into<String>,
+        N: Into<String>,
+    {
+        self.check_not_started()?;
+        self.channel
+            .namespaces
+            .insert(prefix.into(), namespace.into());
+        Ok(self)
+    }
+
+    /// Declare the namespaces of the typed extensions that `item` uses on the root element,
+    /// without writing it.
+    ///
+    /// Fails with `Error::HeaderWritten` if an item has already been written.
+    pub fn register_item(&mut self, item: &Item) -> Result<&mut Self, Error> {
+        self.check_not_started()?;
+        self.registered.extend(item.used_namespaces());
+        Ok(self)
+    }
+
+    fn check_not_started(&self) -> Result<(), Error> {
+        match self.namespaces {
+            Some(_) => Err(Error::HeaderWritten),
+            None => Ok(()),
+        }
+    }
+
+    /// Return the warnings for the elements that were left out so far.
+    pub fn warnings(&self) -> &[WriteWarning] {
+        &self.warnings
+    }
+
+    /// Write an item, writing the channel header first if this is the first item.
+    pub fn write_item(&mut self, item: &Item) -> Result<(), Error> {
+        self.write_header()?;
+
+        let index = self.items;
+        self.items += 1;
+        if self.options.version == RssVersion::V0_91 && index >= RSS_0_91_MAX_ITEMS {
+            return Ok(());
+        }
+
+        let (mut item, warnings) = convert_item_for_version(item, index, self.options.version);
+        self.warnings.extend(warnings);
+        if let Some(ref namespaces) = self.namespaces {
+            let namespaces = namespaces.for_item(&item, &item_path(index));
+            self.warnings.extend_from_slice(namespaces.warnings());
+            if !namespaces.local().is_empty() {
+                let attrs = &mut item.to_mut().extra_attrs;
+                for (prefix, namespace) in namespaces.local() {
+                    attrs.insert(format!("xmlns:{}", prefix), namespace.clone());
+                }
+            }
+            item.write_xml(&namespaces, &self.options, &mut self.writer)?;
+        }
+        Ok(())
+    }
+
+    /// Close the channel and the document, writing the channel header first if no item was
+    /// written, and return the writer.
+    pub fn finish(mut self) -> Result<W, Error> {
+        self.write_header()?;
+
+        if self.options.version == RssVersion::V0_91 && self.items > RSS_0_91_MAX_ITEMS {
+            self.warnings.push(too_many_items(self.items));
+        }
+
+        let mut unknown = UnknownWriter::new(&self.trailing);
+        Channel::write_end(&mut unknown, &mut self.writer)?;
+        write_rss_end(&mut self.writer)?;
+        let output = self.writer.into_inner().finish().map_err(XmlError::from)?;
+        Ok(output)
+    }
+
+    fn write_header(&mut self) -> Result<(), Error> {
+        if self.namespaces.is_some() {
+            return Ok(());
+        }
+
+        let (channel, warnings) = convert_for_version(&self.channel, self.options.version)?;
+        self.warnings.extend(warnings);
+        // older versions have no namespaces, and the items are converted as they are written
+        let registered = if self.options.version == RssVersion::V2_0 {
+            std::mem::take(&mut self.registered)
+        } else {
+            BTreeMap::new()
+        };
+        let namespaces = Namespaces::for_channel_with(&channel, registered);
+
+        self.warnings.extend_from_slice(namespaces.warnings());
+        write_rss_start(&namespaces, &self.options, &mut self.writer)?;
+        let unknown = channel.write_start(&namespaces, &self.options, &mut self.writer)?;
+        self.trailing = unknown.remaining();
+        self.namespaces = Some(namespaces);
+        Ok(())
+    }
+}
+
+/// Returns `channel` with the elements that `version` does not support removed or mapped to
+/// equivalent elements, along with a warning for each element that was removed.
+pub(crate) fn convert_for_version(
+    channel: &Channel,
+    version: RssVersion,
+) -> Result<(Cow<'_, Channel>, Vec<WriteWarning>), Error> {
+    if version == RssVersion::V2_0 {
+        return Ok((Cow::Borrowed(channel), Vec::new()));
+    }
+
+    if version == RssVersion::V0_91 && channel.language.is_none() {
+        return Err(Error::Version {
+            version,
+            reason: "the channel has no language".to_string(),
+        });
+    }
+
+    let mut converter = Converter {
+        version,
+        warnings: Vec::new(),
+    };
+    let mut channel = channel.clone();
+    match version {
+        RssVersion::V0_91 | RssVersion::V0_92 => converter.convert_channel(&mut channel),
+        RssVersion::V1_0 => converter.convert_rdf_channel(&mut channel),
+        RssVersion::V2_0 => {}
+    }
+    Ok((Cow::Owned(channel), converter.warnings))
+}
+
+/// Returns the item at `index` with the elements that `version` does not support removed, along
+/// with a warning for each element that was removed. RSS 1.0 is not supported.
+pub(crate) fn convert_item_for_version(
+    item: &Item,
+    index: usize,
+    version: RssVersion,
+) -> (Cow<'_, Item>, Vec<WriteWarning>) {
+    if version == RssVersion::V2_0 || version == RssVersion::V1_0 {
+        return (Cow::Borrowed(item), Vec::new());
+    }
+
+    let mut converter = Converter {
+        version,
+        warnings: Vec::new(),
+    };
+    let mut item = item.clone();
+    converter.convert_item(&item_path(index), &mut item);
+    (Cow::Owned(item), converter.warnings)
+}
+
+/// Returns the warning for the items of an RSS 0.91 channel with `count` items that are left out.
+fn too_many_items(count: usize) -> WriteWarning {
+    WriteWarning {
+        message: format!(
+            "RSS 0.91 allows at most {} items, {} items were left out",
+            RSS_0_91_MAX_ITEMS,
+            count - RSS_0_91_MAX_ITEMS
+        ),
+        path: format!("channel/item[{}]", RSS_0_91_MAX_ITEMS + 1),
+    }
+}
+
+struct Converter {
+    version: RssVersion,
+    warnings: Vec<WriteWarning>,
+}
+
+impl Converter {
+    fn lose(&mut self, path: &str, name: &str) {
+        self.warnings.push(WriteWarning {
+            message: format!("RSS {} does not support `{}`", self.version, name),
+            path: format!("{}/{}", path, name),
+        });
+    }
+
+    fn take<T>(&mut self, path: &str, name: &str, value: &mut Option<T>) {
+        if value.take().is_some() {
+            self.lose(path, name);
+        }
+    }
+
+    fn take_all<T>(&mut self, path: &str, name: &str, values: &mut Vec<T>) {
+        if !values.is_empty() {
+            values.clear();
+            self.lose(path, name);
+        }
+    }
+
+    /// Removes the elements of RSS 0.91 and 0.92 channels that are not part of those versions.
+    fn convert_channel(&mut self, channel: &mut Channel) {
+        let path = "channel";
+        self.take(path, "@rdf:about", &mut channel.about);
+        if self.version == RssVersion::V0_91 {
+            self.take(path, "cloud", &mut channel.cloud);
+        }
+        self.take_all(path, "category", &mut channel.categories);
+        self.take(path, "generator", &mut channel.generator);
+        self.take(path, "ttl", &mut channel.ttl);
+        if let Some(image) = channel.image.as_mut() {
+            self.take("channel/image", "@rdf:about", &mut image.about);
+        }
+        if let Some(text_input) = channel.text_input.as_mut() {
+            self.take("channel/textInput", "@rdf:about", &mut text_input.about);
+        }
+        self.take_attrs(path, &mut channel.extra_attrs);
+        for category in channel.categories.iter_mut() {
+            self.take_attrs("channel/category", &mut category.extra_attrs);
+        }
+        if let Some(cloud) = channel.cloud.as_mut() {
+            self.take_attrs("channel/cloud", &mut cloud.extra_attrs);
+        }
+        if let Some(image) = channel.image.as_mut() {
+            self.take_attrs("channel/image", &mut image.extra_attrs);
+        }
+        if let Some(text_input) = channel.text_input.as_mut() {
+            self.take_attrs("channel/textInput", &mut text_input.extra_attrs);
+        }
+        self.take_extensions(path, &mut channel.extensions);
+        #[cfg(feature = "atom")]
+        self.take(path, "atom:*", &mut channel.atom_ext);
+        self.take(path, "itunes:*", &mut channel.itunes_ext);
+        self.take(path, "dc:*", &mut channel.dublin_core_ext);
+        self.take(path, "sy:*", &mut channel.syndication_ext);
+        channel.namespaces.clear();
+
+        if self.version == RssVersion::V0_91 && channel.items.len() > RSS_0_91_MAX_ITEMS {
+            self.warnings.push(too_many_items(channel.items.len()));
+            channel.items.truncate(RSS_0_91_MAX_ITEMS);
+        }
+
+        for (index, item) in channel.items.iter_mut().enumerate() {
+            self.convert_item(&item_path(index), item);
+        }
+    }
+
+    fn convert_item(&mut self, path: &str, item: &mut Item) {
+        // a permalink is the closest thing to a link that older versions have
+        if let Some(guid) = item.guid.take() {
+            if item.link.is_none() && guid.permalink {
+                item.link = Some(guid.value);
+            } else {
+                self.lose(path, "guid");
+            }
+        }
+
+        self.take(path, "@rdf:about", &mut item.about);
+        self.take(path, "author", &mut item.author);
+        if self.version == RssVersion::V0_91 {
+            self.take_all(path, "category", &mut item.categories);
+            self.take(path, "enclosure", &mut item.enclosure);
+            self.take(path, "source", &mut item.source);
+        }
+        self.take(path, "comments", &mut item.comments);
+        self.take(path, "pubDate", &mut item.pub_date);
+        self.take(path, "content:encoded", &mut item.content);
+        self.take_attrs(path, &mut item.extra_attrs);
+        for category in item.categories.iter_mut() {
+            self.take_attrs(&format!("{}/category", path), &mut category.extra_attrs);
+        }
+        if let Some(enclosure) = item.enclosure.as_mut() {
+            self.take_attrs(&format!("{}/enclosure", path), &mut enclosure.extra_attrs);
+        }
+        if let Some(source) = item.source.as_mut() {
+            self.take_attrs(&format!("{}/source", path), &mut source.extra_attrs);
+        }
+        self.take_extensions(path, &mut item.extensions);
+        self.take(path, "itunes:*", &mut item.itunes_ext);
+        self.take(path, "dc:*", &mut item.dublin_core_ext);
+        #[cfg(feature = "atom")]
+        self.take(path, "atom:*", &mut item.atom_ext);
+    }
+
+    /// Removes the elements of an RSS 1.0 channel that are not part of RSS 1.0 or one of its
+    /// modules, and moves those that have a Dublin Core equivalent to the Dublin Core module.
+    fn convert_rdf_channel(&mut self, channel: &mut Channel) {
+        let path = "channel";
+        let dublin_core = channel.dublin_core_ext.get_or_insert_with(Default::default);
+        let languages = channel.language.take().into_iter().collect();
+        self.move_to(path, "language", languages, &mut dublin_core.languages);
+        let rights = channel.copyright.take().into_iter().collect();
+        self.move_to(path, "copyright", rights, &mut dublin_core.rights);
+        let subjects = channel.categories.drain(..).map(|c| c.name).collect();
+        self.move_to(path, "category", subjects, &mut dublin_core.subjects);
+        remove_if_empty(&mut channel.dublin_core_ext);
+
+        self.take(path, "managingEditor", &mut channel.managing_editor);
+        self.take(path, "webMaster", &mut channel.webmaster);
+        self.take(path, "pubDate", &mut channel.pub_date);
+        self.take(path, "lastBuildDate", &mut channel.last_build_date);
+        self.take(path, "generator", &mut channel.generator);
+        self.take(path, "docs", &mut channel.docs);
+        self.take(path, "cloud", &mut channel.cloud);
+        self.take(path, "rating", &mut channel.rating);
+        self.take(path, "ttl", &mut channel.ttl);
+        self.take_all(path, "skipHours", &mut channel.skip_hours);
+        self.take_all(path, "skipDays", &mut channel.skip_days);
+
+        if let Some(image) = channel.image.as_mut() {
+            let path = "channel/image";
+            self.take(path, "width", &mut image.width);
+            self.take(path, "height", &mut image.height);
+            self.take(path, "description", &mut image.description);
+        }
+
+        for (index, item) in channel.items.iter_mut().enumerate() {
+            self.convert_rdf_item(&item_path(index), item);
+        }
+    }
+
+    fn convert_rdf_item(&mut self, path: &str, item: &mut Item) {
+        let dublin_core = item.dublin_core_ext.get_or_insert_with(Default::default);
+        let creators = item.author.take().into_iter().collect();
+        self.move_to(path, "author", creators, &mut dublin_core.creators);
+        let subjects = item.categories.drain(..).map(|c| c.name).collect();
+        self.move_to(path, "category", subjects, &mut dublin_core.subjects);
+        remove_if_empty(&mut item.dublin_core_ext);
+
+        self.take(path, "comments", &mut item.comments);
+        self.take(path, "enclosure", &mut item.enclosure);
+        self.take(path, "guid", &mut item.guid);
+        self.take(path, "pubDate", &mut item.pub_date);
+        self.take(path, "source", &mut item.source);
+    }
+
+    /// Writes the values of the element `name` as the equivalent Dublin Core element, unless
+    /// that already has values.
+    fn move_to(
+        &mut self,
+        path: &str,
+        name: &str,
+        values: Vec<String>,
+        dublin_core: &mut Vec<String>,
+    ) {
+        if values.is_empty() {
+            return;
+        }
+        if dublin_core.is_empty() {
+            *dublin_core = values;
+        } else {
+            self.lose(path, name);
+        }
+    }
+
+    /// Removes the attributes that need a namespace declaration, since RSS 0.91 and 0.92 have no
+    /// namespaces. Attributes in the `xml` namespace are kept.
+    fn take_attrs(&mut self, path: &str, attrs: &mut BTreeMap<String, String>) {
+        let names = attrs
+            .keys()
+            .filter(|name| name.contains(':') && !name.starts_with("xml:"))
+            .cloned()
+            .collect::<Vec<_>>();
+        for name in names {
+            attrs.remove(&name);
+            self.lose(path, &format!("@{}", name));
+        }
+    }
+
+    fn take_extensions(&mut self, path: &str, extensions: &mut ExtensionMap) {
+        for (prefix, map) in std::mem::take(extensions) {
+            for name in map.keys() {
+                self.lose(path, &format!("{}:{}", prefix, name));
+            }
+        }
+    }
+}
+
+/// Returns the path of the item at `index`.
+pub(crate) fn item_path(index: usize) -> String {
+    if index == 0 {
+        "channel/item".to_string()
+    } else {
+        format!("channel/item[{}]", index + 1)
+    }
+}
+
+fn remove_if_empty(extension: &mut Option<DublinCoreExtension>) {
+    if extension.as_ref() == Some(&DublinCoreExtension::default()) {
+        *extension = None;
+    }
+}
+
+/// Writes the XML declaration and the stylesheet processing instructions that `options` ask for.
+pub(crate) fn write_prolog<W: Write>(
+    writer: &mut XmlWriter<W>,
+    options: &WriteOptions,
+) -> Result<(), Error> {
+    if options.xml_declaration {
+        let encoding = match options.encoding {
+            Some(_) => output_encoding(options)?.name().to_ascii_lowercase(),
+            None => "utf-8".to_string(),
+        };
+        writer.write_event(Event::Decl(BytesDecl::new("1.0", Some(&encoding), None)))?;
+    }
+    for stylesheet in &options.stylesheets {
+        writer.write_event(Event::PI(BytesText::from_escaped(stylesheet.instruction())))?;
+    }
+    Ok(())
+}
+
+/// Returns the encoding that `options` ask for.
+pub(crate) fn output_encoding(options: &WriteOptions) -> Result<&'static Encoding, Error> {
+    let label = match options.encoding {
+        Some(ref label) => label,
+        None => return Ok(UTF_8),
+    };
+    match Encoding::for_label(label.as_bytes()) {
+        // encodings such as UTF-16 are only decoded, and are encoded as UTF-8
+        Some(encoding) if encoding.output_encoding() == encoding => Ok(encoding),
+        _ => Err(Error::Encoding(label.clone())),
+    }
+}
+
+/// Writes a UTF-8 document to `W` in another encoding as it is written.
+pub(crate) struct Encode<W: Write> {
+    output: W,
+    /// The encoder, or `None` for UTF-8, which is written as it is.
+    encoder: Option<Encoder>,
+    /// The bytes that were written but not encoded yet, which are the start of a character that
+    /// is split between writes.
+    pending: Vec<u8>,
+    buf: Vec<u8>,
+}
+
+impl<W: Write> Encode<W> {
+    pub fn new(output: W, encoding: &'static Encoding) -> Self {
+        Encode {
+            output,
+            encoder: (encoding != UTF_8).then(|| encoding.new_encoder()),
+            pending: Vec::new(),
+            buf: Vec::new(),
+        }
+    }
+
+    /// Writes the end of the document and returns the output.
+    pub fn finish(mut self) -> io::Result<W> {
+        if !self.pending.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the document ends with an incomplete character",
+            ));
+        }
+        self.encode("", true)?;
+        Ok(self.output)
+    }
+
+    fn encode(&mut self, mut text: &str, last: bool) -> io::Result<()> {
+        let encoder = match self.encoder.as_mut() {
+            Some(encoder) => encoder,
+            None => return self.output.write_all(text.as_bytes()),
+        };
+        // large enough for the character references that unmappable characters are written as
+        self.buf.resize(1024, 0);
+
+        loop {
+            let (result, read, written, _) = encoder.encode_from_utf8(text, &mut self.buf, last);
+            self.output.write_all(&self.buf[..written])?;
+            text = &text[read..];
+            if result == CoderResult::InputEmpty {
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl<W: Write> Write for Encode<W> {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        if self.encoder.is_none() {
+            return self.output.write(bytes);
+        }
+
+        let mut input = std::mem::take(&mut self.pending);
+        input.extend_from_slice(bytes);
+        let valid = match str::from_utf8(&input) {
+            Ok(text) => text.len(),
+            Err(err) if err.error_len().is_none() => err.valid_up_to(),
+            Err(err) => return Err(io::Error::new(io::ErrorKind::InvalidData, err)),
+        };
+        let text = str::from_utf8(&input[..valid])
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        self.encode(text, false)?;
+        self.pending = input.split_off(valid);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.output.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use quick_xml::Reader;
+
+    /// Writes the events of an unindented `document` in the given style.
+    fn rewrite(document: &str, style: EmptyElements, indented: bool) -> String {
+        let options = WriteOptions {
+            indent: indented.then_some((b' ', 2)),
+            empty_elements: Some(style),
+            ..Default::default()
+        };
+        let mut reader = Reader::from_str(document);
+        let mut writer = XmlWriter::new(Vec::new(), &options);
+        loop {
+            match reader.read_event().unwrap() {
+                Event::Eof => break,
+                event => writer.write_event(event).unwrap(),
+            }
+        }
+        String::from_utf8(writer.into_inner()).unwrap()
+    }
+
+    #[test]
+    fn test_self_closing() {
+        assert_eq!(
+            rewrite(
+                "<a><b></b><c>text</c><d x=\"1\"></d></a>",
+                EmptyElements::SelfClosing,
+                false
+            ),
+            "<a><b/><c>text</c><d x=\"1\"/></a>"
+        );
+        assert_eq!(
+            rewrite("<a><b></b><c> </c></a>", EmptyElements::SelfClosing, true),
+            "<a>\n  <b/>\n  <c> </c>\n</a>"
+        );
+    }
+
+    #[test]
+    fn test_expanded() {
+        assert_eq!(
+            rewrite(
+                "<a><b/><c x=\"&amp;\"/><![CDATA[<d/>]]></a>",
+                EmptyElements::Expanded,
+                false
+            ),
+            "<a><b></b><c x=\"&amp;\"></c><![CDATA[<d/>]]></a>"
+        );
+        assert_eq!(
+            rewrite("<a><b/></a>", EmptyElements::Expanded, true),
+            "<a>\n  <b></b>\n</a>"
+        );
+    }
+
+    #[test]
+    fn test_encode() {
+        let mut output = Encode::new(Vec::new(), encoding_rs::WINDOWS_1252);
+        let text = "caf\u{e9} \u{2603}".as_bytes();
+        // the second character is split between writes
+        output.write_all(&text[..4]).unwrap();
+        output.write_all(&text[4..]).unwrap();
+        assert_eq!(output.finish().unwrap(), b"caf\xe9 &#9731;");
+    }
+
+    #[test]
+    fn test_output_encoding() {
+        let mut options = WriteOptions::default();
+        assert_eq!(output_encoding(&options).unwrap(), UTF_8);
+        options.encoding = Some("latin1".to_string());
+        assert_eq!(output_encoding(&options).unwrap().name(), "windows-1252");
+        options.encoding = Some("utf-16".to_string());
+        assert!(output_encoding(&options).is_err());
+        options.encoding = Some("unknown".to_string());
+        assert!(output_encoding(&options).is_err());
+    }
+}
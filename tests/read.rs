@@ -1,11 +1,20 @@
+// the tests take the first item with `get(0)`, which newer versions of clippy flag
+#![allow(clippy::get_first)]
+
 extern crate rss;
 
+use std::borrow::Cow;
 use std::collections::BTreeMap;
+use std::io::{self, BufReader, Read};
+use std::time::Duration;
 
 use rss::extension::dublincore::DublinCoreExtension;
 use rss::extension::syndication;
 use rss::extension::Extension;
-use rss::Channel;
+use rss::{
+    Channel, ChannelReader, ChannelRef, Error, LimitKind, Limits, MediaClass, ParseError,
+    ParseOptions, TextKind,
+};
 
 fn get_extension_values<'a>(
     map: &'a BTreeMap<String, Vec<Extension>>,
@@ -189,6 +198,205 @@ fn read_rss1() {
             "     information through SAX-based processing."
         ])
     );
+
+    assert_eq!(channel.about(), Some("http://www.xml.com/xml/news.rss"));
+    assert_eq!(
+        image.about(),
+        Some("http://xml.com/universal/images/xml_tiny.gif")
+    );
+    assert_eq!(text_input.about(), Some("http://search.xml.com"));
+    assert_eq!(
+        channel
+            .items()
+            .iter()
+            .map(|item| item.about())
+            .collect::<Vec<_>>(),
+        vec![
+            Some("http://xml.com/pub/2000/08/09/xslt/xslt.html"),
+            Some("http://xml.com/pub/2000/08/09/rdfdb/index.html"),
+        ]
+    );
+}
+
+#[test]
+fn read_rss1_item_order() {
+    let input = include_str!("data/rss1.xml")
+        .replacen(
+            r#"<rdf:li resource="http://xml.com/pub/2000/08/09/xslt/xslt.html" />"#,
+            "",
+            1,
+        )
+        .replacen(
+            "</rdf:Seq>",
+            r#"<rdf:li rdf:resource="http://xml.com/pub/2000/08/09/xslt/xslt.html" /></rdf:Seq>"#,
+            1,
+        );
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+    assert_eq!(
+        channel
+            .items()
+            .iter()
+            .map(|item| item.title())
+            .collect::<Vec<_>>(),
+        vec![
+            Some("Putting RDF to Work"),
+            Some("Processing Inclusions with XSLT")
+        ]
+    );
+
+    let channel = ChannelRef::parse(&input).expect("failed to parse xml");
+    assert_eq!(
+        channel.items[0].title.as_deref(),
+        Some("Putting RDF to Work")
+    );
+}
+
+#[test]
+fn read_unprefixed_about() {
+    let input = include_str!("data/rss1.xml").replace("rdf:about=", "about=");
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+    assert_eq!(channel.about(), Some("http://www.xml.com/xml/news.rss"));
+    assert!(channel.extra_attrs().is_empty());
+
+    // outside of RSS 1.0, an unprefixed `about` is an ordinary attribute
+    let input =
+        r#"<rss version="2.0"><channel><item about="http://example.com/"/></channel></rss>"#;
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+    let item = &channel.items()[0];
+    assert_eq!(item.about(), None);
+    assert_eq!(
+        item.extra_attrs().get("about").map(String::as_str),
+        Some("http://example.com/")
+    );
+    let output = channel.to_string();
+    assert!(output.contains(r#"<item about="http://example.com/">"#));
+    assert!(!output.contains("rdf:about"));
+}
+
+#[test]
+fn read_unknown_elements() {
+    let input = include_str!("data/unknown.xml");
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+
+    let unknown = channel.unknown_elements();
+    assert_eq!(unknown.len(), 3);
+    assert_eq!(unknown[0].element().name(), "feedId");
+    assert_eq!(unknown[0].element().value(), Some("42"));
+    assert_eq!(unknown[0].after(), None);
+
+    let publisher = unknown[1].element();
+    assert_eq!(publisher.name(), "publisher");
+    assert_eq!(
+        publisher.attrs().get("kind").map(String::as_str),
+        Some("person")
+    );
+    assert_eq!(
+        publisher.children().get("email").map(|v| v[0].value()),
+        Some(Some("publisher@example.com"))
+    );
+    assert_eq!(unknown[1].after(), Some("title"));
+
+    assert_eq!(unknown[2].element().name(), "status");
+    assert_eq!(unknown[2].after(), Some("item"));
+
+    let unknown = channel.items()[0].unknown_elements();
+    assert_eq!(unknown.len(), 1);
+    assert_eq!(unknown[0].element().name(), "rank");
+    assert_eq!(unknown[0].after(), Some("title"));
+
+    assert!(channel.extensions().contains_key("ext"));
+}
+
+#[test]
+fn read_extra_attributes() {
+    let input = include_str!("data/attributes.xml");
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+    let attr = |attrs: &BTreeMap<String, String>, key: &str| attrs.get(key).cloned();
+
+    assert_eq!(channel.extra_attrs().len(), 2);
+    assert_eq!(
+        attr(channel.extra_attrs(), "xml:lang").as_deref(),
+        Some("en")
+    );
+    assert_eq!(
+        attr(channel.extra_attrs(), "xml:base").as_deref(),
+        Some("http://example.com/")
+    );
+
+    let category = &channel.categories()[0];
+    assert_eq!(category.domain(), Some("http://example.com/categories"));
+    assert_eq!(
+        attr(category.extra_attrs(), "xml:lang").as_deref(),
+        Some("en")
+    );
+    assert_eq!(category.extra_attrs().len(), 1);
+
+    let cloud = channel.cloud().unwrap();
+    assert_eq!(cloud.protocol(), "xml-rpc");
+    assert_eq!(attr(cloud.extra_attrs(), "vendor:id").as_deref(), Some("1"));
+    let image = channel.image().unwrap();
+    assert_eq!(attr(image.extra_attrs(), "vendor:id").as_deref(), Some("2"));
+    let text_input = channel.text_input().unwrap();
+    assert_eq!(
+        attr(text_input.extra_attrs(), "vendor:id").as_deref(),
+        Some("3")
+    );
+
+    let item = &channel.items()[0];
+    assert_eq!(attr(item.extra_attrs(), "xml:lang").as_deref(), Some("fr"));
+    assert_eq!(attr(item.extra_attrs(), "vendor:id").as_deref(), Some("4"));
+    let enclosure = item.enclosure().unwrap();
+    assert_eq!(enclosure.length(), "1024");
+    assert_eq!(
+        attr(enclosure.extra_attrs(), "vendor:id").as_deref(),
+        Some("5")
+    );
+    let guid = item.guid().unwrap();
+    assert!(!guid.is_permalink());
+    assert_eq!(attr(guid.extra_attrs(), "vendor:id").as_deref(), Some("6"));
+    let source = item.source().unwrap();
+    assert_eq!(source.url(), "http://example.com/feed.xml");
+    assert_eq!(
+        attr(source.extra_attrs(), "vendor:id").as_deref(),
+        Some("7")
+    );
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn read_dates() {
+    use chrono::{TimeZone, Utc};
+
+    let input = r#"
+        <rss version="2.0">
+            <channel>
+                <pubDate>Tue, 10 Jun 2003 04:00:00 GMT</pubDate>
+                <lastBuildDate>Tue, 10 Jun 03 02:41 PDT</lastBuildDate>
+                <item>
+                    <pubDate>2003-06-03T09:39:21Z</pubDate>
+                </item>
+                <item>
+                    <pubDate>last week</pubDate>
+                </item>
+            </channel>
+        </rss>
+    "#;
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+
+    assert_eq!(
+        channel.pub_date_parsed(),
+        Some(Utc.with_ymd_and_hms(2003, 6, 10, 4, 0, 0).unwrap().into())
+    );
+    assert_eq!(
+        channel.last_build_date_parsed(),
+        Some(Utc.with_ymd_and_hms(2003, 6, 10, 9, 41, 0).unwrap().into())
+    );
+    assert_eq!(
+        channel.items()[0].pub_date_parsed(),
+        Some(Utc.with_ymd_and_hms(2003, 6, 3, 9, 39, 21).unwrap().into())
+    );
+    assert_eq!(channel.items()[1].pub_date(), Some("last week"));
+    assert_eq!(channel.items()[1].pub_date_parsed(), None);
 }
 
 #[test]
@@ -219,6 +427,73 @@ fn read_channel() {
     assert_eq!(channel.skip_days().get(1).unwrap().as_str(), "Thursday");
 }
 
+#[test]
+fn read_people() {
+    let input = include_str!("data/channel.xml");
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+    let editor = channel.managing_editor_person().unwrap();
+    assert_eq!(editor.name(), None);
+    assert_eq!(editor.email(), Some("editor@example.com"));
+    let webmaster = channel.webmaster_person().unwrap();
+    assert_eq!(webmaster.email(), Some("webmaster@example.com"));
+
+    let input = r#"
+        <rss version="2.0">
+            <channel>
+                <managingEditor>editor@example.com (Jane Editor)</managingEditor>
+                <webMaster>Web Master</webMaster>
+                <item><author>John Doe &lt;john@example.com&gt;</author></item>
+                <item><author></author></item>
+            </channel>
+        </rss>
+    "#;
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+    let editor = channel.managing_editor_person().unwrap();
+    assert_eq!(editor.name(), Some("Jane Editor"));
+    assert_eq!(editor.email(), Some("editor@example.com"));
+    let webmaster = channel.webmaster_person().unwrap();
+    assert_eq!(webmaster.name(), Some("Web Master"));
+    assert_eq!(webmaster.email(), None);
+    let author = channel.items()[0].author_person().unwrap();
+    assert_eq!(author.to_string(), "john@example.com (John Doe)");
+    assert_eq!(channel.items()[1].author_person(), None);
+}
+
+#[test]
+fn read_schedule() {
+    let input = include_str!("data/channel.xml");
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+
+    assert_eq!(channel.ttl_duration(), Some(Duration::from_secs(60 * 60)));
+    assert_eq!(
+        channel.skip_hours_set().iter().collect::<Vec<_>>(),
+        vec![6, 8]
+    );
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn read_skip_days() {
+    use chrono::{TimeZone, Utc, Weekday};
+
+    let input = include_str!("data/channel.xml");
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+
+    let days = channel.skip_days_set();
+    assert_eq!(
+        days.iter().collect::<Vec<_>>(),
+        vec![Weekday::Tue, Weekday::Thu]
+    );
+
+    // 2016-08-30 was a Tuesday
+    let tuesday = Utc.with_ymd_and_hms(2016, 8, 30, 12, 0, 0).unwrap();
+    assert!(channel.is_skipped_at(&tuesday));
+    let monday = Utc.with_ymd_and_hms(2016, 8, 29, 12, 0, 0).unwrap();
+    assert!(!channel.is_skipped_at(&monday));
+    let monday_morning = Utc.with_ymd_and_hms(2016, 8, 29, 6, 59, 59).unwrap();
+    assert!(channel.is_skipped_at(&monday_morning));
+}
+
 #[test]
 fn read_item() {
     let input = include_str!("data/item.xml");
@@ -367,6 +642,54 @@ fn read_enclosure() {
     );
 }
 
+#[test]
+fn read_enclosure_typed() {
+    let input = include_str!("data/enclosure.xml");
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+    let enclosure = channel.items()[0].enclosure().unwrap();
+
+    assert_eq!(enclosure.length_parsed(), Some(4992349));
+    assert_eq!(enclosure.media_class(), MediaClass::Audio);
+
+    let mut enclosure = enclosure.clone();
+    enclosure.set_length("unknown");
+    assert_eq!(enclosure.length_parsed(), None);
+    enclosure.set_mime_type("application/pdf");
+    assert_eq!(enclosure.media_class(), MediaClass::Other);
+    enclosure.set_mime_type("");
+    assert_eq!(enclosure.media_class(), MediaClass::Other);
+}
+
+#[cfg(feature = "mime")]
+#[test]
+fn read_enclosure_mime_type() {
+    let input = include_str!("data/enclosure.xml");
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+    let mut enclosure = channel.items()[0].enclosure().unwrap().clone();
+
+    let mime_type = enclosure.mime_type_parsed().unwrap();
+    assert_eq!(mime_type.type_(), mime::AUDIO);
+    assert_eq!(mime_type.subtype(), "mpeg");
+    enclosure.set_mime_type("audio");
+    assert_eq!(enclosure.mime_type_parsed(), None);
+}
+
+#[cfg(feature = "url")]
+#[test]
+fn read_enclosure_url() {
+    let input = include_str!("data/enclosure.xml");
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+    let mut enclosure = channel.items()[0].enclosure().unwrap().clone();
+
+    let url = enclosure.url_resolved("http://example.org/").unwrap();
+    assert_eq!(url.as_str(), "http://example.com/media.mp3");
+
+    enclosure.set_url("media/2.mp3");
+    let url = enclosure.url_resolved("http://example.org/feed/").unwrap();
+    assert_eq!(url.as_str(), "http://example.org/feed/media/2.mp3");
+    assert_eq!(enclosure.url_resolved(""), None);
+}
+
 #[test]
 fn read_category() {
     let input = include_str!("data/category.xml");
@@ -449,6 +772,135 @@ fn read_mixed_content() {
     assert_eq!(channel.title(), "Title");
 }
 
+fn preserve_markup() -> ParseOptions {
+    ParseOptions {
+        preserve_markup: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn read_markup() {
+    let input = include_str!("data/markup.xml");
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+
+    assert_eq!(channel.description_kind(), TextKind::Plain);
+    assert_eq!(channel.items()[0].description(), None);
+    assert_eq!(channel.items()[0].description_kind(), TextKind::Plain);
+    assert_eq!(channel.items()[1].description(), Some("<p>Escaped</p>"));
+    assert_eq!(channel.items()[1].description_kind(), TextKind::Plain);
+}
+
+#[test]
+fn read_split_cdata() {
+    let input = r#"<rss version="2.0" xmlns:ext="http://example.com/ext"><channel>
+        <description><![CDATA[a ]]]]><![CDATA[> b]]></description>
+        <ext:tag><![CDATA[c ]]]]><![CDATA[> d]]></ext:tag>
+    </channel></rss>"#;
+
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+    assert_eq!(channel.description(), "a ]]> b");
+    assert_eq!(
+        channel.extensions()["ext"]["tag"][0].value(),
+        Some("c ]]> d")
+    );
+
+    let channel = ChannelRef::parse(input).expect("failed to parse xml");
+    assert_eq!(channel.description, "a ]]> b");
+    assert_eq!(
+        channel.extensions["ext"]["tag"][0].value.as_deref(),
+        Some("c ]]> d")
+    );
+}
+
+#[test]
+fn read_mixed_text() {
+    let input = r#"<rss version="2.0" xmlns:ext="http://example.com/ext"><channel>
+        <title> A <b>B</b> C </title>
+        <description>Hello<br/>
+            world</description>
+        <ext:tag> one <i>two</i> <i>three</i>four </ext:tag>
+    </channel></rss>"#;
+
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+    assert_eq!(channel.title(), "A  C");
+    assert_eq!(channel.description(), "Hello\n            world");
+    assert_eq!(
+        channel.extensions()["ext"]["tag"][0].value(),
+        Some("one  four")
+    );
+
+    let channel = ChannelRef::parse(input).expect("failed to parse xml");
+    assert_eq!(channel.title, "A  C");
+    assert_eq!(
+        channel.extensions["ext"]["tag"][0].value.as_deref(),
+        Some("one  four")
+    );
+}
+
+#[test]
+fn read_preserve_markup() {
+    let input = include_str!("data/markup.xml");
+    let channel = Channel::read_from_with_options(input.as_bytes(), preserve_markup())
+        .expect("failed to parse xml")
+        .channel;
+
+    assert_eq!(channel.title(), "Title");
+    assert_eq!(channel.description(), "Channel <em>description</em>");
+    assert_eq!(channel.description_kind(), TextKind::Xhtml);
+
+    let item = &channel.items()[0];
+    assert_eq!(item.title(), Some("XHTML"));
+    assert_eq!(
+        item.description(),
+        Some("<p>Hello <b>world</b><br/> again</p>")
+    );
+    assert_eq!(item.description_kind(), TextKind::Xhtml);
+    assert_eq!(
+        item.content(),
+        Some(r#"<div class="body"><p>First</p><!-- note --><p>Second &amp; last</p></div>"#)
+    );
+    assert_eq!(item.content_kind(), TextKind::Xhtml);
+
+    let summary = &item.extensions()["ext"]["summary"][0];
+    assert_eq!(summary.value(), Some("Summary with <i>markup</i> <hr />"));
+    assert_eq!(summary.kind(), TextKind::Xhtml);
+    assert_eq!(summary.children()["i"][0].value(), Some("markup"));
+    assert_eq!(summary.children()["hr"][0].name(), "hr");
+
+    let owner = &item.extensions()["ext"]["owner"][0];
+    assert_eq!(owner.value(), None);
+    assert_eq!(owner.kind(), TextKind::Plain);
+    assert_eq!(owner.children()["name"][0].value(), Some("Owner"));
+
+    let item = &channel.items()[1];
+    assert_eq!(item.description(), Some("<p>Escaped</p>"));
+    assert_eq!(item.description_kind(), TextKind::Html);
+    assert_eq!(item.content(), Some("<p>CDATA</p>"));
+    assert_eq!(item.content_kind(), TextKind::Html);
+
+    let item = &channel.items()[2];
+    assert_eq!(item.description(), Some("Just text"));
+    assert_eq!(item.description_kind(), TextKind::Plain);
+}
+
+#[test]
+fn read_channel_ref_preserve_markup() {
+    let input = include_str!("data/markup.xml");
+    let expected = Channel::read_from_with_options(input.as_bytes(), preserve_markup())
+        .expect("failed to parse xml")
+        .channel;
+    let (channel, _) =
+        ChannelRef::parse_with_options(input, preserve_markup()).expect("failed to parse xml");
+
+    assert!(matches!(channel.description, Cow::Borrowed(_)));
+    assert!(matches!(
+        channel.items[0].description,
+        Some(Cow::Borrowed(_))
+    ));
+    assert_eq!(channel.into_owned(), expected);
+}
+
 #[test]
 fn read_cloud() {
     let input = include_str!("data/cloud.xml");
@@ -709,6 +1161,16 @@ fn read_itunes() {
             .duration(),
         Some("01:22:33")
     );
+    assert_eq!(
+        channel
+            .items()
+            .get(0)
+            .unwrap()
+            .itunes_ext()
+            .unwrap()
+            .duration_parsed(),
+        Some(Duration::from_secs(4953))
+    );
     assert_eq!(
         channel
             .items()
@@ -803,6 +1265,40 @@ fn read_itunes() {
     );
 }
 
+#[test]
+fn read_itunes_typed() {
+    use rss::extension::itunes::{EpisodeType, Explicit, ShowType};
+
+    let input = include_str!("data/itunes.xml");
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+
+    let itunes = channel.itunes_ext().unwrap();
+    assert_eq!(itunes.block_parsed(), Some(true));
+    assert_eq!(itunes.explicit_parsed(), Some(Explicit::No));
+    assert_eq!(itunes.complete_parsed(), Some(true));
+    assert_eq!(itunes.type_parsed(), Some(ShowType::Episodic));
+
+    let itunes = channel.items()[0].itunes_ext().unwrap();
+    assert_eq!(itunes.block_parsed(), Some(true));
+    assert_eq!(itunes.explicit_parsed(), Some(Explicit::Yes));
+    assert_eq!(itunes.closed_captioned_parsed(), Some(false));
+    assert_eq!(itunes.episode_parsed(), Some(2));
+    assert_eq!(itunes.season_parsed(), Some(3));
+    assert_eq!(itunes.episode_type_parsed(), Some(EpisodeType::Trailer));
+
+    let mut itunes = itunes.clone();
+    itunes.set_explicit("Explicit".to_string());
+    assert_eq!(itunes.explicit_parsed(), Some(Explicit::Yes));
+    itunes.set_explicit("maybe".to_string());
+    assert_eq!(itunes.explicit_parsed(), None);
+    itunes.set_episode(" 12 ".to_string());
+    assert_eq!(itunes.episode_parsed(), Some(12));
+    itunes.set_season("first".to_string());
+    assert_eq!(itunes.season_parsed(), None);
+    itunes.set_closed_captioned(None);
+    assert_eq!(itunes.closed_captioned_parsed(), None);
+}
+
 #[test]
 fn read_dublincore() {
     run_dublincore_test(include_str!("data/dublincore.xml"));
@@ -930,6 +1426,104 @@ fn read_syndication() {
     assert_eq!(syn.base(), "2000-01-01T12:00+00:00");
 }
 
+#[test]
+fn read_syndication_invalid() {
+    let input = r#"
+        <rss version="2.0" xmlns:sy="http://purl.org/rss/1.0/modules/syndication/">
+            <channel>
+                <sy:updatePeriod>Fortnightly</sy:updatePeriod>
+                <sy:updateFrequency></sy:updateFrequency>
+                <item><title>Item</title></item>
+            </channel>
+        </rss>
+    "#;
+    let messages = |warnings: &[rss::ParseWarning]| {
+        warnings
+            .iter()
+            .map(|warning| warning.message.clone())
+            .collect::<Vec<_>>()
+    };
+    let expected = vec![
+        "ignored invalid updatePeriod `Fortnightly`",
+        "ignored invalid updateFrequency ``",
+    ];
+
+    let parsed = Channel::read_from_with_options(input.as_bytes(), ParseOptions::default())
+        .expect("failed to parse xml");
+    let syn = parsed.channel.syndication_ext().unwrap();
+    assert_eq!(syn.period(), &syndication::UpdatePeriod::Daily);
+    assert_eq!(syn.frequency(), 1);
+    assert_eq!(messages(&parsed.warnings), expected);
+    let location = &parsed.warnings[0].location;
+    assert_eq!(location.path, "rss/channel/sy:updatePeriod");
+    assert_eq!(location.line, 4);
+    assert_eq!(parsed.warnings[1].location.line, 5);
+
+    // the values are checked as they are read, before the end of the channel
+    let mut reader = ChannelReader::new(input.as_bytes()).expect("failed to parse header");
+    assert_eq!(messages(reader.warnings()), expected);
+    assert!(reader.next().unwrap().is_ok());
+    assert!(reader.next().is_none());
+    assert_eq!(messages(reader.warnings()), expected);
+
+    let (_, warnings) = ChannelRef::parse_with_options(input, ParseOptions::default())
+        .expect("failed to parse xml");
+    assert_eq!(messages(&warnings), expected);
+
+    let input = input.replace("Fortnightly", " Weekly ");
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+    let syn = channel.syndication_ext().unwrap();
+    assert_eq!(syn.period(), &syndication::UpdatePeriod::Weekly);
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn read_syndication_schedule() {
+    use chrono::{TimeZone, Utc};
+
+    let input = include_str!("data/syndication.xml");
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+    let mut syn = channel.syndication_ext().unwrap().clone();
+
+    let base = Utc.with_ymd_and_hms(2000, 1, 1, 12, 0, 0).unwrap();
+    assert_eq!(syn.base_parsed(), Some(base.into()));
+    assert_eq!(
+        syn.next_update_after(&Utc.with_ymd_and_hms(1999, 1, 1, 0, 0, 0).unwrap()),
+        Some(base.into())
+    );
+    assert_eq!(
+        syn.next_update_after(&Utc.with_ymd_and_hms(2000, 1, 1, 12, 0, 0).unwrap()),
+        Some(Utc.with_ymd_and_hms(2000, 1, 1, 12, 30, 0).unwrap().into())
+    );
+
+    syn.set_period(syndication::UpdatePeriod::Monthly);
+    syn.set_frequency(1);
+    syn.set_base("2000-01-31T00:00+00:00");
+    assert_eq!(
+        syn.next_update_after(&Utc.with_ymd_and_hms(2001, 2, 10, 0, 0, 0).unwrap()),
+        Some(Utc.with_ymd_and_hms(2001, 2, 28, 0, 0, 0).unwrap().into())
+    );
+    assert_eq!(
+        syn.next_update_after(&Utc.with_ymd_and_hms(2001, 3, 1, 0, 0, 0).unwrap()),
+        Some(Utc.with_ymd_and_hms(2001, 3, 31, 0, 0, 0).unwrap().into())
+    );
+
+    syn.set_period(syndication::UpdatePeriod::Yearly);
+    syn.set_frequency(2);
+    syn.set_base("2000");
+    assert_eq!(
+        syn.next_update_after(&Utc.with_ymd_and_hms(2010, 3, 1, 0, 0, 0).unwrap()),
+        Some(Utc.with_ymd_and_hms(2010, 7, 2, 12, 0, 0).unwrap().into())
+    );
+    assert_eq!(
+        syn.next_update_after(&Utc.with_ymd_and_hms(2010, 8, 1, 0, 0, 0).unwrap()),
+        Some(Utc.with_ymd_and_hms(2011, 1, 1, 0, 0, 0).unwrap().into())
+    );
+
+    syn.set_frequency(0);
+    assert_eq!(syn.next_update_after(&base), None);
+}
+
 #[test]
 fn read_escaped() {
     let input = r#"
@@ -945,3 +1539,928 @@ fn read_escaped() {
     let parsed_channel = output.parse::<Channel>().unwrap();
     assert_eq!(channel, parsed_channel);
 }
+
+#[test]
+fn read_channel_reader() {
+    let input = include_str!("data/rss2sample.xml");
+    let expected = input.parse::<Channel>().expect("failed to parse xml");
+
+    let mut reader = ChannelReader::new(input.as_bytes()).expect("failed to parse header");
+    assert_eq!(reader.channel().title(), expected.title());
+    assert_eq!(reader.channel().items().len(), 0);
+
+    let items = reader
+        .by_ref()
+        .collect::<Result<Vec<_>, _>>()
+        .expect("failed to parse items");
+    assert_eq!(items, expected.items());
+
+    let mut channel = reader.into_channel();
+    channel.set_items(items);
+    assert_eq!(channel, expected);
+}
+
+#[test]
+fn read_channel_reader_rss1() {
+    let input = include_str!("data/rss1.xml");
+    let mut reader = ChannelReader::new(input.as_bytes()).expect("failed to parse header");
+
+    assert_eq!(reader.channel().title(), "XML.com");
+    assert_eq!(
+        reader.channel().image().map(|image| image.title()),
+        Some("XML.com")
+    );
+
+    let titles = reader
+        .by_ref()
+        .map(|item| item.unwrap().title.unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        titles,
+        vec!["Processing Inclusions with XSLT", "Putting RDF to Work"]
+    );
+
+    // the text input follows the items
+    assert_eq!(
+        reader
+            .channel()
+            .text_input()
+            .map(|text_input| text_input.name()),
+        Some("s")
+    );
+}
+
+#[test]
+fn read_channel_reader_extensions_around_items() {
+    let input = r#"
+        <rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd"
+                xmlns:dc="http://purl.org/dc/elements/1.1/">
+            <channel>
+                <itunes:author>Author</itunes:author>
+                <dc:creator>Creator</dc:creator>
+                <item><title>First</title></item>
+                <itunes:subtitle>Subtitle</itunes:subtitle>
+                <item><title>Second</title></item>
+                <dc:publisher>Publisher</dc:publisher>
+            </channel>
+        </rss>
+    "#;
+
+    let mut reader = ChannelReader::new(input.as_bytes()).unwrap();
+    let itunes = reader.channel().itunes_ext().unwrap();
+    assert_eq!(itunes.author(), Some("Author"));
+
+    assert_eq!(reader.by_ref().count(), 2);
+    let channel = reader.into_channel();
+    let itunes = channel.itunes_ext().unwrap();
+    assert_eq!(itunes.author(), Some("Author"));
+    assert_eq!(itunes.subtitle(), Some("Subtitle"));
+    let dublin_core = channel.dublin_core_ext().unwrap();
+    assert_eq!(dublin_core.creators(), ["Creator"]);
+    assert_eq!(dublin_core.publishers(), ["Publisher"]);
+    assert!(channel.extensions().is_empty());
+
+    let parsed = Channel::read_from(input.as_bytes()).unwrap();
+    assert_eq!(parsed.itunes_ext(), channel.itunes_ext());
+    assert_eq!(parsed.dublin_core_ext(), channel.dublin_core_ext());
+}
+
+#[test]
+fn read_channel_reader_stops_early() {
+    let input = r#"
+        <rss version="2.0">
+            <channel>
+                <title>Title</title>
+                <item><title>First</title></item>
+                <item><title>Second</title>
+    "#;
+
+    let mut reader = ChannelReader::new(input.as_bytes()).unwrap();
+    let item = reader.next().unwrap().unwrap();
+    assert_eq!(item.title(), Some("First"));
+    assert!(reader.next().unwrap().is_err());
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn read_channel_reader_invalid_start() {
+    let err = ChannelReader::new("<feed></feed>".as_bytes())
+        .err()
+        .unwrap();
+    assert!(matches!(err.error, rss::Error::InvalidStartTag));
+    let err = ChannelReader::new("<rss></rss>".as_bytes()).err().unwrap();
+    assert!(matches!(err.error, rss::Error::Eof));
+}
+
+#[test]
+fn read_namespaces() {
+    let input = include_str!("data/namespaces.xml");
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+
+    assert_eq!(channel.title(), "Title");
+    assert_eq!(
+        channel.namespaces().get("i").map(String::as_str),
+        Some("http://www.itunes.com/dtds/podcast-1.0.dtd")
+    );
+    assert_eq!(
+        channel.itunes_ext().and_then(|ext| ext.author()),
+        Some("Channel Author")
+    );
+
+    let item = channel.items().first().unwrap();
+    assert_eq!(item.title(), Some("Item"));
+    assert_eq!(item.content(), Some("Content"));
+    assert_eq!(item.author(), None);
+    assert_eq!(
+        item.itunes_ext().and_then(|ext| ext.author()),
+        Some("Item Author")
+    );
+    assert_eq!(
+        item.dublin_core_ext().map(|ext| ext.creators()),
+        Some(&["Creator".to_string()][..])
+    );
+    assert_eq!(
+        get_extension_values(item.extensions().get("x").unwrap(), "unknown"),
+        Some(vec!["Unknown"])
+    );
+}
+
+#[test]
+fn read_default_namespace_extension() {
+    let input = r#"
+        <rss version="2.0">
+            <channel>
+                <title>Title</title>
+                <link>http://example.com/</link>
+                <link xmlns="http://www.w3.org/2005/Atom" href="http://example.com/feed" rel="self"/>
+            </channel>
+        </rss>
+    "#;
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+
+    assert_eq!(channel.link(), "http://example.com/");
+    #[cfg(feature = "atom")]
+    assert_eq!(
+        channel.atom_ext().map(|ext| ext.links()[0].href()),
+        Some("http://example.com/feed")
+    );
+    #[cfg(not(feature = "atom"))]
+    assert_eq!(
+        channel.extensions()["atom"]["link"][0].attrs()["href"],
+        "http://example.com/feed"
+    );
+}
+
+#[test]
+fn read_unknown_default_namespace() {
+    let input = r#"
+        <rss version="2.0" xmlns="http://www.w3.org/1999/xhtml">
+            <channel>
+                <title>Title</title>
+                <item><title>Item</title></item>
+            </channel>
+        </rss>
+    "#;
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+
+    assert_eq!(channel.title(), "Title");
+    assert_eq!(channel.items().len(), 1);
+    assert_eq!(channel.items()[0].title(), Some("Item"));
+    assert!(channel.extensions().is_empty());
+}
+
+fn lenient() -> ParseOptions {
+    ParseOptions {
+        lenient: true,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn read_lenient_mismatched_end_tags() {
+    let input = r#"
+        <rss version="2.0">
+            <channel>
+                <title>Title</title>
+                <item><title>First</title></item>
+                <item><title>Second</titel><link>http://example.com/</link></item>
+                <item><title>Third</title><description>Unclosed</item>
+            </channel>
+        </rss>
+    "#;
+
+    assert!(Channel::read_from(input.as_bytes()).is_err());
+
+    let parsed =
+        Channel::read_from_with_options(input.as_bytes(), lenient()).expect("failed to parse xml");
+    let channel = parsed.channel;
+    assert_eq!(channel.title(), "Title");
+    assert_eq!(channel.items().len(), 3);
+    assert_eq!(channel.items()[1].title(), Some("Second"));
+    assert_eq!(channel.items()[1].link(), Some("http://example.com/"));
+    assert_eq!(channel.items()[2].title(), Some("Third"));
+    assert_eq!(channel.items()[2].description(), Some("Unclosed"));
+
+    let warnings = parsed.warnings;
+    assert_eq!(warnings.len(), 2);
+    assert_eq!(warnings[0].location.path, "rss/channel/item[2]/title");
+    assert!(warnings[0].message.contains("</titel>"));
+    assert_eq!(warnings[1].location.path, "rss/channel/item[3]/description");
+    assert_eq!(warnings[1].message, "element is not closed");
+    assert_eq!(
+        warnings[1].location.offset,
+        input.find("</item>\n            </channel>").unwrap() + "</item>".len()
+    );
+}
+
+#[test]
+fn read_lenient_truncated() {
+    let input = r#"
+        <rss version="2.0">
+            <channel>
+                <title>Title</title>
+                <item><title>First</title></item>
+                <item><title>Sec"#;
+
+    assert!(Channel::read_from(input.as_bytes()).is_err());
+
+    let parsed =
+        Channel::read_from_with_options(input.as_bytes(), lenient()).expect("failed to parse xml");
+    assert_eq!(parsed.channel.title(), "Title");
+    assert_eq!(parsed.channel.items().len(), 2);
+    assert_eq!(parsed.channel.items()[1].title(), Some("Sec"));
+    assert_eq!(parsed.warnings.len(), 1);
+    assert_eq!(parsed.warnings[0].message, "unexpected end of input");
+    assert_eq!(
+        parsed.warnings[0].location.path,
+        "rss/channel/item[2]/title"
+    );
+    assert_eq!(parsed.warnings[0].location.offset, input.len());
+}
+
+#[test]
+fn read_lenient_invalid_entities() {
+    let input = r#"
+        <rss version="2.0">
+            <channel>
+                <title>Fish &amp; Chips &nbsp; &</title>
+                <item>
+                    <enclosure url="http://example.com/?a=1&b=2" length="0" type="audio/mpeg"/>
+                </item>
+            </channel>
+        </rss>
+    "#;
+
+    assert!(Channel::read_from(input.as_bytes()).is_err());
+
+    let parsed =
+        Channel::read_from_with_options(input.as_bytes(), lenient()).expect("failed to parse xml");
+    assert_eq!(parsed.channel.title(), "Fish & Chips &nbsp; &");
+    assert_eq!(
+        parsed.channel.items()[0].enclosure().map(|e| e.url()),
+        Some("http://example.com/?a=1&b=2")
+    );
+    assert_eq!(parsed.warnings.len(), 2);
+    assert_eq!(parsed.warnings[0].location.path, "rss/channel/title");
+    assert_eq!(
+        parsed.warnings[1].location.path,
+        "rss/channel/item/enclosure"
+    );
+}
+
+#[test]
+fn read_lenient_stray_text() {
+    let input = r#"
+        <rss version="2.0">
+            <channel>
+                <title>Title</title>
+                stray
+                <item><title>Item</title>text</item>
+            </channel>
+        </rss>
+    "#;
+
+    let channel = Channel::read_from(input.as_bytes()).expect("failed to parse xml");
+    assert_eq!(channel.items()[0].title(), Some("Item"));
+
+    let parsed =
+        Channel::read_from_with_options(input.as_bytes(), lenient()).expect("failed to parse xml");
+    assert_eq!(parsed.channel, channel);
+    assert_eq!(parsed.warnings.len(), 2);
+    assert_eq!(parsed.warnings[0].location.path, "rss/channel");
+    assert_eq!(parsed.warnings[1].location.path, "rss/channel/item");
+}
+
+#[test]
+fn read_channel_reader_lenient() {
+    let input = r#"
+        <rss version="2.0">
+            <channel>
+                <title>Title</titl>
+                <item><title>First</title></item>
+                <item><title>Second</title></item>
+    "#;
+
+    let mut reader =
+        ChannelReader::with_options(input.as_bytes(), lenient()).expect("failed to parse xml");
+    assert_eq!(reader.channel().title(), "Title");
+    assert_eq!(reader.warnings().len(), 1);
+
+    let items = (&mut reader).collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(items.len(), 2);
+    assert_eq!(reader.warnings().len(), 2);
+    assert_eq!(reader.warnings()[1].location.path, "rss/channel");
+}
+
+fn read_with_limits(input: &str, limits: Limits) -> Result<Channel, ParseError> {
+    let options = ParseOptions {
+        limits,
+        ..Default::default()
+    };
+    Channel::read_from_with_options(input.as_bytes(), options).map(|parsed| parsed.channel)
+}
+
+fn assert_limit_exceeded(
+    result: Result<Channel, ParseError>,
+    expected_kind: LimitKind,
+    expected: usize,
+) {
+    match result.as_ref().map_err(|err| &err.error) {
+        Err(&Error::LimitExceeded { kind, limit }) => {
+            assert_eq!(kind, expected_kind);
+            assert_eq!(limit, expected);
+        }
+        other => panic!("expected LimitExceeded, got {:?}", other),
+    }
+}
+
+#[test]
+fn read_limit_depth() {
+    let nested = "<x:a>".repeat(10) + &"</x:a>".repeat(10);
+    let input = format!(
+        r#"<rss xmlns:x="http://example.com/"><channel>{}</channel></rss>"#,
+        nested
+    );
+
+    let limits = Limits {
+        max_depth: Some(12),
+        ..Default::default()
+    };
+    assert!(read_with_limits(&input, limits).is_ok());
+
+    let limits = Limits {
+        max_depth: Some(11),
+        ..Default::default()
+    };
+    assert_limit_exceeded(read_with_limits(&input, limits), LimitKind::Depth, 11);
+
+    // the default limit protects against unbounded recursion
+    let nested = "<x:a>".repeat(100_000) + &"</x:a>".repeat(100_000);
+    let input = format!(
+        r#"<rss xmlns:x="http://example.com/"><channel>{}</channel></rss>"#,
+        nested
+    );
+    assert_limit_exceeded(
+        read_with_limits(&input, Limits::default()),
+        LimitKind::Depth,
+        256,
+    );
+
+    // and so does the internal limit, even without a limit
+    let limits = Limits {
+        max_depth: None,
+        ..Default::default()
+    };
+    assert_limit_exceeded(read_with_limits(&input, limits), LimitKind::Depth, 256);
+    let limits = Limits {
+        max_depth: Some(100_000),
+        ..Default::default()
+    };
+    assert_limit_exceeded(read_with_limits(&input, limits), LimitKind::Depth, 256);
+}
+
+#[test]
+fn read_limit_items() {
+    let input = format!(
+        "<rss><channel>{}</channel></rss>",
+        "<item><title>Item</title></item>".repeat(3)
+    );
+
+    let limits = Limits {
+        max_items: Some(3),
+        ..Default::default()
+    };
+    assert_eq!(read_with_limits(&input, limits).unwrap().items().len(), 3);
+
+    let limits = Limits {
+        max_items: Some(2),
+        ..Default::default()
+    };
+    assert_limit_exceeded(read_with_limits(&input, limits), LimitKind::Items, 2);
+
+    let options = ParseOptions {
+        limits,
+        ..Default::default()
+    };
+    let mut reader = ChannelReader::with_options(input.as_bytes(), options).unwrap();
+    assert!(reader.next().unwrap().is_ok());
+    assert!(reader.next().unwrap().is_ok());
+    assert!(reader.next().unwrap().is_err());
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn read_limit_text_length() {
+    let input = r#"<rss><channel><title>0123456789</title></channel></rss>"#;
+
+    let limits = Limits {
+        max_text_len: Some(10),
+        ..Default::default()
+    };
+    assert_eq!(
+        read_with_limits(input, limits).unwrap().title(),
+        "0123456789"
+    );
+
+    let limits = Limits {
+        max_text_len: Some(9),
+        ..Default::default()
+    };
+    assert_limit_exceeded(read_with_limits(input, limits), LimitKind::TextLength, 9);
+
+    let input = r#"<rss><channel><cloud domain="0123456789"/></channel></rss>"#;
+    assert_limit_exceeded(read_with_limits(input, limits), LimitKind::TextLength, 9);
+
+    // the limit is checked while reading, so that endless text is not buffered
+    let options = ParseOptions {
+        limits: Limits {
+            max_text_len: Some(1000),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    for start in &[
+        "<rss><channel><title>",
+        "<rss><channel><!-- ",
+        "<rss><channel><cloud domain=\"",
+    ] {
+        let input = BufReader::new(start.as_bytes().chain(io::repeat(b'a')));
+        assert_limit_exceeded(
+            Channel::read_from_with_options(input, options.clone()).map(|parsed| parsed.channel),
+            LimitKind::TextLength,
+            1000,
+        );
+    }
+}
+
+#[test]
+fn read_limit_attributes() {
+    let input = r#"<rss><channel><cloud domain="a" port="1" path="/"/></channel></rss>"#;
+
+    let limits = Limits {
+        max_attributes: Some(3),
+        ..Default::default()
+    };
+    assert!(read_with_limits(input, limits).is_ok());
+
+    let limits = Limits {
+        max_attributes: Some(2),
+        ..Default::default()
+    };
+    assert_limit_exceeded(read_with_limits(input, limits), LimitKind::Attributes, 2);
+    assert!(matches!(
+        ChannelRef::parse_with_options(
+            input,
+            ParseOptions {
+                limits,
+                ..Default::default()
+            }
+        )
+        .as_ref()
+        .map_err(|err| &err.error),
+        Err(Error::LimitExceeded {
+            kind: LimitKind::Attributes,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn read_limit_extension_children() {
+    let input = r#"
+        <rss xmlns:x="http://example.com/">
+            <channel>
+                <x:a><x:b><x:c/></x:b><x:b/></x:a>
+                <x:a><x:b/></x:a>
+            </channel>
+        </rss>
+    "#;
+
+    let limits = Limits {
+        max_extension_children: Some(3),
+        ..Default::default()
+    };
+    assert!(read_with_limits(input, limits).is_ok());
+
+    let limits = Limits {
+        max_extension_children: Some(2),
+        ..Default::default()
+    };
+    assert_limit_exceeded(
+        read_with_limits(input, limits),
+        LimitKind::ExtensionChildren,
+        2,
+    );
+}
+
+#[test]
+fn read_limit_input_length() {
+    let input = include_str!("data/rss2sample.xml");
+
+    let limits = Limits {
+        max_input_len: Some(input.len()),
+        ..Default::default()
+    };
+    assert!(read_with_limits(input, limits).is_ok());
+
+    let limits = Limits {
+        max_input_len: Some(input.len() / 2),
+        ..Default::default()
+    };
+    assert_limit_exceeded(
+        read_with_limits(input, limits),
+        LimitKind::InputLength,
+        input.len() / 2,
+    );
+
+    // the limit also applies in lenient mode, which would otherwise treat the input as truncated
+    let options = ParseOptions {
+        lenient: true,
+        limits,
+        ..Default::default()
+    };
+    assert!(matches!(
+        Channel::read_from_with_options(input.as_bytes(), options)
+            .as_ref()
+            .map_err(|err| &err.error),
+        Err(Error::LimitExceeded { .. })
+    ));
+}
+
+#[test]
+fn read_error_location() {
+    let input = "<rss version=\"2.0\">
+  <channel>
+    <title>Title</title>
+    <item><title>First</title></item>
+    <item>
+      <title>Second</title>
+      <enclosure url=\"http://example.com/&bad;\" length=\"0\" type=\"audio/mpeg\"/>
+    </item>
+  </channel>
+</rss>";
+
+    let err = Channel::read_from(input.as_bytes()).unwrap_err();
+    assert!(matches!(err.error, Error::Xml(_)));
+    assert_eq!(input.parse::<Channel>().unwrap_err().location, err.location);
+    let with_options =
+        Channel::read_from_with_options(input.as_bytes(), ParseOptions::default()).unwrap_err();
+    assert_eq!(with_options.location, err.location);
+
+    let location = &err.location;
+    assert_eq!(location.path, "rss/channel/item[2]/enclosure");
+    assert_eq!(location.line, 7);
+    let line_start = input.find("      <enclosure").unwrap();
+    let offset = input.find("/>\n    </item>").unwrap() + 2;
+    assert_eq!(location.offset, offset);
+    assert_eq!(location.column, offset - line_start + 1);
+    assert!(err.to_string().ends_with(&format!(
+        " at rss/channel/item[2]/enclosure, line 7, column {}, byte {}",
+        location.column, offset
+    )));
+}
+
+#[test]
+fn read_error_location_eof() {
+    let input = "<rss>\n<channel>\n<title>Title</title>\n";
+
+    let err = Channel::read_from(input.as_bytes()).unwrap_err();
+    assert!(matches!(err.error, Error::Eof));
+
+    let location = &err.location;
+    assert_eq!(location.offset, input.len());
+    assert_eq!(location.line, 4);
+    assert_eq!(location.column, 1);
+
+    let err = ChannelReader::new("<feed>\n</feed>".as_bytes())
+        .err()
+        .unwrap();
+    assert!(matches!(err.error, Error::InvalidStartTag));
+    assert_eq!(err.location.path, "feed");
+}
+
+#[test]
+fn read_warning_location() {
+    let input = "<rss>\n  <channel>\n    <title>Title &nbsp;</title>\n  </channel>\n</rss>";
+
+    let parsed = Channel::read_from_with_options(input.as_bytes(), lenient()).unwrap();
+    let location = &parsed.warnings[0].location;
+    assert_eq!(location.path, "rss/channel/title");
+    assert_eq!(location.line, 3);
+    assert_eq!(location.column, "    <title>Title &nbsp;".len() + 1);
+}
+
+fn read_with_charset(input: &[u8], charset: Option<&str>) -> rss::ParsedChannel {
+    let options = ParseOptions {
+        charset: charset.map(String::from),
+        ..Default::default()
+    };
+    Channel::read_from_with_options(input, options).expect("failed to parse xml")
+}
+
+#[test]
+fn read_encoding_declaration() {
+    let input = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?>\n\
+        <rss><channel><title>Caf\xE9</title><item><title>\xA3 5</title></item></channel></rss>";
+
+    let parsed = read_with_charset(input, None);
+    assert_eq!(parsed.encoding, "windows-1252");
+    assert_eq!(parsed.channel.title(), "Café");
+    assert_eq!(parsed.channel.items()[0].title(), Some("£ 5"));
+    assert!(parsed.warnings.is_empty());
+
+    let input = b"<?xml version=\"1.0\" encoding=\"Shift_JIS\"?>\n\
+        <rss><channel><title>\x93\xfa\x96\x7b</title></channel></rss>";
+    let parsed = read_with_charset(input, None);
+    assert_eq!(parsed.encoding, "Shift_JIS");
+    assert_eq!(parsed.channel.title(), "日本");
+
+    let mut input = b"<?xml version=\"1.0\" encoding=\"windows-1252\"?><rss><channel>".to_vec();
+    for _ in 0..1000 {
+        input.extend_from_slice(b"<item><title>Caf\xE9</title></item>");
+    }
+    input.extend_from_slice(b"</channel></rss>");
+    let reader = BufReader::with_capacity(7, &input[..]);
+    let channel = Channel::read_from(reader).expect("failed to parse xml");
+    assert_eq!(channel.items().len(), 1000);
+    assert!(channel
+        .items()
+        .iter()
+        .all(|item| item.title() == Some("Café")));
+}
+
+#[test]
+fn read_encoding_charset_overrides_declaration() {
+    let input = b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+        <rss><channel><title>Caf\xE9</title></channel></rss>";
+
+    let parsed = read_with_charset(input, Some("windows-1252"));
+    assert_eq!(parsed.encoding, "windows-1252");
+    assert_eq!(parsed.channel.title(), "Café");
+    assert_eq!(parsed.warnings.len(), 1);
+    assert_eq!(parsed.warnings[0].location.offset, 0);
+
+    let parsed = read_with_charset(input, Some("x-unknown"));
+    assert_eq!(parsed.encoding, "UTF-8");
+    assert_eq!(parsed.channel.title(), "Caf\u{FFFD}");
+    assert_eq!(parsed.warnings.len(), 1);
+}
+
+#[test]
+fn read_encoding_bom() {
+    let mut input = vec![0xEF, 0xBB, 0xBF];
+    input.extend_from_slice(
+        "<?xml version=\"1.0\" encoding=\"windows-1252\"?><rss><channel><title>Café</title></channel></rss>"
+            .as_bytes(),
+    );
+    let parsed = read_with_charset(&input, Some("Shift_JIS"));
+    assert_eq!(parsed.encoding, "UTF-8");
+    assert_eq!(parsed.channel.title(), "Café");
+
+    let mut input = vec![0xFF, 0xFE];
+    for unit in
+        "<?xml version=\"1.0\"?><rss><channel><title>Café</title></channel></rss>".encode_utf16()
+    {
+        input.extend_from_slice(&unit.to_le_bytes());
+    }
+    let parsed = read_with_charset(&input, None);
+    assert_eq!(parsed.encoding, "UTF-16LE");
+    assert_eq!(parsed.channel.title(), "Café");
+}
+
+#[test]
+fn read_encoding_sniffing() {
+    let input = "<rss><channel><title>Café</title></channel></rss>";
+    let parsed = read_with_charset(input.as_bytes(), None);
+    assert_eq!(parsed.encoding, "UTF-8");
+    assert_eq!(parsed.channel.title(), "Café");
+
+    let input = b"<rss><channel><title>Caf\xE9</title></channel></rss>";
+    let parsed = read_with_charset(input, None);
+    assert_eq!(parsed.encoding, "windows-1252");
+    assert_eq!(parsed.channel.title(), "Café");
+
+    let reader = ChannelReader::new(&input[..]).expect("failed to parse xml");
+    assert_eq!(reader.encoding(), "windows-1252");
+}
+
+#[test]
+fn read_channel_ref() {
+    let inputs = [
+        include_str!("data/attributes.xml"),
+        include_str!("data/category.xml"),
+        include_str!("data/channel.xml"),
+        include_str!("data/cloud.xml"),
+        include_str!("data/content.xml"),
+        include_str!("data/dublincore.xml"),
+        include_str!("data/dublincore_altprefix.xml"),
+        include_str!("data/enclosure.xml"),
+        include_str!("data/extension.xml"),
+        include_str!("data/guid.xml"),
+        include_str!("data/image.xml"),
+        include_str!("data/item.xml"),
+        include_str!("data/itunes.xml"),
+        include_str!("data/markup.xml"),
+        include_str!("data/mixed_content.xml"),
+        include_str!("data/namespaces.xml"),
+        include_str!("data/rss090.xml"),
+        include_str!("data/rss091.xml"),
+        include_str!("data/rss092.xml"),
+        include_str!("data/rss1.xml"),
+        include_str!("data/rss2_with_atom.xml"),
+        include_str!("data/rss2sample.xml"),
+        include_str!("data/source.xml"),
+        include_str!("data/syndication.xml"),
+        include_str!("data/textinput.xml"),
+        include_str!("data/unknown.xml"),
+        include_str!("data/verify_write_format.xml"),
+    ];
+
+    for input in inputs.iter() {
+        let expected = input.parse::<Channel>().expect("failed to parse xml");
+        let channel = ChannelRef::parse(input).expect("failed to parse xml");
+        assert_eq!(channel.into_owned(), expected);
+    }
+}
+
+#[test]
+fn read_channel_ref_borrows() {
+    let input = r#"
+        <rss version="2.0" xmlns:ext="http://example.com/">
+            <channel>
+                <title>Title</title>
+                <description><![CDATA[<p>Description</p>]]></description>
+                <item>
+                    <title>Fish &amp; Chips</title>
+                    <enclosure url="http://example.com/a?b=1&amp;c=2" length="10" type="audio/mpeg"/>
+                    <category domain="http://example.com/">Category</category>
+                    <ext:element attr="value">Text</ext:element>
+                </item>
+            </channel>
+        </rss>
+    "#;
+
+    let channel = ChannelRef::parse(input).expect("failed to parse xml");
+    assert!(matches!(channel.title, Cow::Borrowed("Title")));
+    assert!(matches!(
+        channel.description,
+        Cow::Borrowed("<p>Description</p>")
+    ));
+
+    let item = &channel.items[0];
+    assert!(matches!(item.title, Some(Cow::Owned(ref title)) if title == "Fish & Chips"));
+
+    let enclosure = item.enclosure.as_ref().unwrap();
+    assert!(matches!(enclosure.url, Cow::Owned(ref url) if url == "http://example.com/a?b=1&c=2"));
+    assert!(matches!(enclosure.length, Cow::Borrowed("10")));
+    assert!(matches!(enclosure.mime_type, Cow::Borrowed("audio/mpeg")));
+
+    let category = &item.categories[0];
+    assert!(matches!(category.name, Cow::Borrowed("Category")));
+    assert!(matches!(
+        category.domain,
+        Some(Cow::Borrowed("http://example.com/"))
+    ));
+
+    let extension = &item.extensions["ext"]["element"][0];
+    assert!(matches!(extension.name, Cow::Borrowed("ext:element")));
+    assert!(matches!(extension.value, Some(Cow::Borrowed("Text"))));
+    assert!(matches!(extension.attrs["attr"], Cow::Borrowed("value")));
+
+    let expected = input.parse::<Channel>().expect("failed to parse xml");
+    assert_eq!(channel.into_owned(), expected);
+}
+
+#[test]
+fn read_channel_ref_with_options() {
+    let input =
+        "<rss>\n<channel>\n<title>Title &nbsp;</title>\n<item><title>Item</item>\n</channel>";
+
+    let err = ChannelRef::parse(input).unwrap_err();
+    assert_eq!(err.location.line, 3);
+
+    let (channel, warnings) =
+        ChannelRef::parse_with_options(input, lenient()).expect("failed to parse xml");
+    let expected =
+        Channel::read_from_with_options(input.as_bytes(), lenient()).expect("failed to parse xml");
+    assert_eq!(warnings, expected.warnings);
+    assert_eq!(channel.into_owned(), expected.channel);
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn read_async_items_before_end_of_input() {
+    use rss::AsyncChannelReader;
+    use tokio::io::{duplex, AsyncWriteExt, BufReader};
+    use tokio::time::timeout;
+
+    let (input, mut output) = duplex(4096);
+    output
+        .write_all(b"<rss><channel><title>Title</title><item><title>First</title></item>")
+        .await
+        .unwrap();
+
+    // the input stays open, so reading would never finish if it waited for the end of input
+    let wait = Duration::from_secs(5);
+    let mut reader = timeout(wait, AsyncChannelReader::new(BufReader::new(input)))
+        .await
+        .expect("waited for the end of input")
+        .expect("failed to parse header");
+    assert_eq!(reader.channel().title(), "Title");
+    let item = timeout(wait, reader.next_item()).await.unwrap();
+    assert_eq!(item.unwrap().unwrap().title(), Some("First"));
+
+    output
+        .write_all(b"<item><title>Second</title></item>")
+        .await
+        .unwrap();
+    let item = timeout(wait, reader.next_item()).await.unwrap();
+    assert_eq!(item.unwrap().unwrap().title(), Some("Second"));
+
+    output.write_all(b"</channel></rss>").await.unwrap();
+    drop(output);
+    assert!(reader.next_item().await.is_none());
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn read_async() {
+    use rss::AsyncChannelReader;
+    use tokio::io::BufReader;
+
+    let inputs = [
+        include_str!("data/attributes.xml"),
+        include_str!("data/category.xml"),
+        include_str!("data/channel.xml"),
+        include_str!("data/cloud.xml"),
+        include_str!("data/content.xml"),
+        include_str!("data/dublincore.xml"),
+        include_str!("data/dublincore_altprefix.xml"),
+        include_str!("data/enclosure.xml"),
+        include_str!("data/extension.xml"),
+        include_str!("data/guid.xml"),
+        include_str!("data/image.xml"),
+        include_str!("data/item.xml"),
+        include_str!("data/itunes.xml"),
+        include_str!("data/markup.xml"),
+        include_str!("data/mixed_content.xml"),
+        include_str!("data/namespaces.xml"),
+        include_str!("data/rss090.xml"),
+        include_str!("data/rss091.xml"),
+        include_str!("data/rss092.xml"),
+        include_str!("data/rss1.xml"),
+        include_str!("data/rss2_with_atom.xml"),
+        include_str!("data/rss2sample.xml"),
+        include_str!("data/source.xml"),
+        include_str!("data/syndication.xml"),
+        include_str!("data/textinput.xml"),
+        include_str!("data/unknown.xml"),
+        include_str!("data/verify_write_format.xml"),
+    ];
+
+    for input in inputs.iter() {
+        let expected = input.parse::<Channel>().expect("failed to parse xml");
+
+        let channel = Channel::read_from_async(input.as_bytes())
+            .await
+            .expect("failed to parse xml");
+        assert_eq!(channel, expected);
+
+        let items = ChannelReader::new(input.as_bytes())
+            .expect("failed to parse header")
+            .collect::<Result<Vec<_>, _>>()
+            .expect("failed to parse items");
+        assert_eq!(items, expected.items());
+
+        // a small buffer splits the input into many reads
+        let input = BufReader::with_capacity(16, input.as_bytes());
+        let mut reader = AsyncChannelReader::new(input)
+            .await
+            .expect("failed to parse header");
+        let mut items = Vec::new();
+        while let Some(item) = reader.next_item().await {
+            items.push(item.expect("failed to parse item"));
+        }
+        assert_eq!(items, expected.items());
+    }
+}
@@ -1,8 +1,9 @@
 extern crate rss;
 
 use rss::{
-    extension, CategoryBuilder, Channel, ChannelBuilder, CloudBuilder, EnclosureBuilder,
-    GuidBuilder, ImageBuilder, ItemBuilder, SourceBuilder, TextInputBuilder,
+    extension, CategoryBuilder, Channel, ChannelBuilder, ChannelWriter, CloudBuilder,
+    EmptyElements, EnclosureBuilder, Error, GuidBuilder, ImageBuilder, ItemBuilder, ParseOptions,
+    RssVersion, SourceBuilder, Stylesheet, TextInputBuilder, TextStyle, WriteOptions, WriteWarning,
 };
 use std::collections::BTreeMap;
 
@@ -21,6 +22,85 @@ fn write_channel() {
     test_write!(channel);
 }
 
+#[test]
+fn write_rss1() {
+    let input = include_str!("data/rss1.xml");
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+    let output = String::from_utf8(channel.write_rdf_to(Vec::new()).unwrap()).unwrap();
+    assert!(
+        output.contains(r#"<rdf:li rdf:resource="http://xml.com/pub/2000/08/09/xslt/xslt.html"/>"#)
+    );
+    let parsed = output.parse::<Channel>().expect("failed to parse xml");
+    assert_eq!(channel, parsed);
+}
+
+#[test]
+fn write_rss1_defaults() {
+    let channel = ChannelBuilder::default()
+        .title("Title")
+        .link("http://example.com/")
+        .items(vec![ItemBuilder::default()
+            .title("Item".to_string())
+            .link("http://example.com/item".to_string())
+            .build()])
+        .build();
+    let output = String::from_utf8(channel.write_rdf_to(Vec::new()).unwrap()).unwrap();
+    let parsed = output.parse::<Channel>().expect("failed to parse xml");
+    assert_eq!(parsed.about(), Some("http://example.com/"));
+    assert_eq!(parsed.items()[0].about(), Some("http://example.com/item"));
+    assert_eq!(parsed.items()[0].title(), Some("Item"));
+}
+
+#[test]
+fn write_unknown_elements() {
+    let input = include_str!("data/unknown.xml");
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+    test_write!(channel);
+
+    let output = channel.to_string();
+    let position = |name: &str| output.find(name).unwrap();
+    assert!(position("<feedId>") < position("<title>Title"));
+    assert!(position("<title>Title") < position("<publisher kind=\"person\">"));
+    assert!(position("<publisher") < position("<link>http://example.com/</link>"));
+    assert!(position("<title>Item") < position("<rank>"));
+    assert!(position("<rank>") < position("<link>http://example.com/item"));
+    assert!(position("</item>") < position("<status>"));
+}
+
+#[test]
+fn write_extra_attributes() {
+    let input = include_str!("data/attributes.xml");
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+    test_write!(channel);
+
+    let output = channel.to_string();
+    assert!(output.contains("<channel xml:base=\"http://example.com/\" xml:lang=\"en\">"));
+    assert!(output.contains("<item vendor:id=\"4\" xml:lang=\"fr\">"));
+    assert!(output.contains("<guid isPermaLink=\"false\" vendor:id=\"6\">"));
+}
+
+#[test]
+fn write_markup() {
+    let input = include_str!("data/markup.xml");
+    let options = ParseOptions {
+        preserve_markup: true,
+        ..Default::default()
+    };
+    let channel = Channel::read_from_with_options(input.as_bytes(), options.clone())
+        .expect("failed to parse xml")
+        .channel;
+
+    let output = channel.to_string();
+    assert!(output.contains("<description><p>Hello <b>world</b><br/> again</p></description>"));
+    assert!(output.contains("<ext:summary>Summary with <i>markup</i> <hr /></ext:summary>"));
+    assert!(output.contains("<![CDATA[<p>CDATA</p>]]>"));
+
+    let parsed = Channel::read_from_with_options(output.as_bytes(), options)
+        .expect("failed to parse xml")
+        .channel;
+    assert_eq!(channel, parsed);
+}
+
 #[test]
 fn write_item() {
     let input = include_str!("data/item.xml");
@@ -105,6 +185,119 @@ fn write_itunes() {
     test_write!(channel);
 }
 
+#[test]
+fn write_itunes_typed() {
+    use rss::extension::itunes::{
+        EpisodeType, Explicit, ITunesChannelExtension, ITunesItemExtension, ShowType,
+    };
+
+    let mut channel_ext = ITunesChannelExtension::default();
+    channel_ext.set_block_from(false);
+    channel_ext.set_explicit_from(Explicit::Clean);
+    channel_ext.set_complete_from(true);
+    channel_ext.set_type_from(ShowType::Serial);
+
+    let mut item_ext = ITunesItemExtension::default();
+    item_ext.set_explicit_from(Explicit::Yes);
+    item_ext.set_closed_captioned_from(true);
+    item_ext.set_episode_from(4);
+    item_ext.set_season_from(1);
+    item_ext.set_episode_type_from(EpisodeType::Full);
+
+    let channel = ChannelBuilder::default()
+        .itunes_ext(channel_ext)
+        .item(ItemBuilder::default().itunes_ext(item_ext).build())
+        .build();
+    let xml = channel.to_string();
+
+    assert!(xml.contains("<itunes:block>No</itunes:block>"));
+    assert!(xml.contains("<itunes:explicit>clean</itunes:explicit>"));
+    assert!(xml.contains("<itunes:complete>Yes</itunes:complete>"));
+    assert!(xml.contains("<itunes:type>serial</itunes:type>"));
+    assert!(xml.contains("<itunes:explicit>true</itunes:explicit>"));
+    assert!(xml.contains("<itunes:isClosedCaptioned>Yes</itunes:isClosedCaptioned>"));
+    assert!(xml.contains("<itunes:episode>4</itunes:episode>"));
+    assert!(xml.contains("<itunes:season>1</itunes:season>"));
+    assert!(xml.contains("<itunes:episodeType>full</itunes:episodeType>"));
+
+    let channel = xml.parse::<Channel>().expect("failed to parse xml");
+    let itunes = channel.items()[0].itunes_ext().unwrap();
+    assert_eq!(itunes.explicit_parsed(), Some(Explicit::Yes));
+    assert_eq!(itunes.episode_parsed(), Some(4));
+}
+
+#[test]
+fn write_itunes_namespace() {
+    use rss::extension::itunes::ITunesChannelExtension;
+
+    let mut itunes = ITunesChannelExtension::default();
+    itunes.set_author("Author".to_string());
+    let channel = ChannelBuilder::default().itunes_ext(itunes).build();
+
+    let output = channel.to_string();
+    assert!(output.contains(r#"xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd""#));
+    assert!(output.contains("<itunes:author>Author</itunes:author>"));
+    let parsed = output.parse::<Channel>().expect("failed to parse xml");
+    assert_eq!(parsed.itunes_ext(), channel.itunes_ext());
+
+    // the extension is written with the itunes prefix, which is declared along with the one it
+    // was read with
+    let input = r#"<rss version="2.0" xmlns:i="http://www.itunes.com/dtds/podcast-1.0.dtd">
+        <channel><i:author>Author</i:author></channel>
+    </rss>"#;
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+    assert_eq!(channel.itunes_ext().unwrap().author(), Some("Author"));
+
+    let output = channel.to_string();
+    assert!(output.contains(r#"xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd""#));
+    assert!(output.contains("<itunes:author>Author</itunes:author>"));
+    let parsed = output.parse::<Channel>().expect("failed to parse xml");
+    assert_eq!(parsed.itunes_ext(), channel.itunes_ext());
+}
+
+#[test]
+fn write_rss2_with_atom() {
+    let input = include_str!("data/rss2_with_atom.xml");
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+    test_write!(channel);
+
+    let output = channel.to_string();
+    assert!(output.contains(r#"xmlns:atom="http://www.w3.org/2005/Atom""#));
+    assert!(output.contains(r#"<atom:link href="http://liftoff.msfc.nasa.gov/rss" rel="self""#));
+    assert!(!output.contains("<link href="));
+}
+
+#[cfg(feature = "atom")]
+#[test]
+fn write_atom_prefix() {
+    use rss::extension::atom::{AtomExtension, Link};
+
+    let mut atom = AtomExtension::default();
+    atom.set_links(vec![Link {
+        href: "http://example.com/feed".to_string(),
+        rel: "self".to_string(),
+        ..Default::default()
+    }]);
+    let item = ItemBuilder::default().atom_ext(atom.clone()).build();
+    let mut channel = ChannelBuilder::default().atom_ext(atom).item(item).build();
+
+    let output = channel.to_string();
+    assert!(output.contains(r#"xmlns:atom="http://www.w3.org/2005/Atom""#));
+    assert_eq!(output.matches("<atom:link ").count(), 2);
+    let parsed = output.parse::<Channel>().expect("failed to parse xml");
+    assert_eq!(parsed.atom_ext(), channel.atom_ext());
+    assert_eq!(parsed.items()[0].atom_ext(), channel.items()[0].atom_ext());
+
+    let mut namespaces = BTreeMap::new();
+    namespaces.insert("a".to_string(), "http://www.w3.org/2005/Atom".to_string());
+    channel.set_namespaces(namespaces);
+    let output = channel.to_string();
+    assert!(output.contains(r#"xmlns:a="http://www.w3.org/2005/Atom""#));
+    assert!(!output.contains("xmlns:atom="));
+    assert_eq!(output.matches("<a:link ").count(), 2);
+    test_write!(channel);
+}
+
 #[test]
 fn write_dublincore() {
     let input = include_str!("data/dublincore.xml");
@@ -119,6 +312,120 @@ fn write_syndication() {
     test_write!(channel);
 }
 
+#[test]
+fn write_syndication_namespace() {
+    let mut syndication = extension::syndication::SyndicationExtension::default();
+    syndication.set_frequency(2);
+    let channel = ChannelBuilder::default()
+        .syndication_ext(syndication)
+        .build();
+
+    let output = channel.to_string();
+    assert!(output.contains(r#"xmlns:sy="http://purl.org/rss/1.0/modules/syndication/""#));
+    assert!(output.contains("<sy:updateFrequency>2</sy:updateFrequency>"));
+    let parsed = output.parse::<Channel>().expect("failed to parse xml");
+    assert_eq!(parsed.syndication_ext(), channel.syndication_ext());
+}
+
+#[test]
+fn write_undeclared_namespaces() {
+    let mut thing = BTreeMap::new();
+    thing.insert(
+        "thing".to_owned(),
+        vec![extension::ExtensionBuilder::default()
+            .name("dc:thing")
+            .value("Thing".to_owned())
+            .build()],
+    );
+    let mut tag = BTreeMap::new();
+    tag.insert(
+        "tag".to_owned(),
+        vec![extension::ExtensionBuilder::default()
+            .name("ext:tag")
+            .build()],
+    );
+
+    let mut namespaces = BTreeMap::new();
+    namespaces.insert("dc".to_owned(), "http://example.com/dc".to_owned());
+
+    let mut item = ItemBuilder::default().title("Item".to_owned()).build();
+    item.extensions.insert("ext".to_owned(), tag);
+    let mut channel = ChannelBuilder::default()
+        .dublin_core_ext(
+            extension::dublincore::DublinCoreExtensionBuilder::default()
+                .creators(vec!["Creator".to_owned()])
+                .build(),
+        )
+        .namespaces(namespaces)
+        .item(item)
+        .build();
+    channel.extensions.insert("dc".to_owned(), thing);
+
+    let output = channel.to_string();
+    assert!(output.contains(r#"xmlns:dc="http://purl.org/dc/elements/1.1/""#));
+    assert!(output.contains(r#"xmlns:dc1="http://example.com/dc""#));
+    assert!(output.contains(r#"xmlns:ext="urn:x-rss:undeclared:ext""#));
+    assert!(output.contains("<dc1:thing>Thing</dc1:thing>"));
+    assert!(output.contains("<dc:creator>Creator</dc:creator>"));
+
+    let parsed = output.parse::<Channel>().expect("failed to parse xml");
+    assert_eq!(parsed.dublin_core_ext(), channel.dublin_core_ext());
+    assert_eq!(
+        parsed.extensions()["dc1"]["thing"][0].value(),
+        Some("Thing")
+    );
+    assert_eq!(parsed.items()[0].extensions()["ext"]["tag"].len(), 1);
+
+    // the placeholder namespace is reported
+    let (_, warnings) = channel
+        .write_with_options(Vec::new(), &WriteOptions::default())
+        .unwrap();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].path, "channel/item/ext:tag");
+}
+
+#[test]
+fn write_scoped_namespaces() {
+    let input = r#"
+        <rss version="2.0" xmlns:foo="http://example.com/channel">
+            <channel>
+                <foo:thing>Channel</foo:thing>
+                <item xmlns:foo="http://example.com/foo" xmlns:bar="http://example.com/bar"
+                        bar:attr="value">
+                    <foo:thing>Item</foo:thing>
+                    <bar:tag><bar:child>Child</bar:child></bar:tag>
+                    <enclosure url="http://example.com/a.mp3" length="1" type="audio/mpeg"
+                        bar:attr="value"/>
+                    <baz:tag xmlns:baz="http://example.com/baz">Element</baz:tag>
+                </item>
+            </channel>
+        </rss>
+    "#;
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+
+    let (output, warnings) = channel
+        .write_with_options(Vec::new(), &WriteOptions::default())
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(warnings.is_empty());
+    assert!(!output.contains("urn:x-rss:undeclared"));
+    assert!(output.contains(r#"<foo:thing xmlns:foo="http://example.com/foo">Item</foo:thing>"#));
+    assert!(output.contains(r#"<baz:tag xmlns:baz="http://example.com/baz">"#));
+
+    // every element is read back in the namespace it was read in first
+    let parsed = output.parse::<Channel>().expect("failed to parse xml");
+    assert_eq!(parsed, channel);
+    let item = &parsed.items()[0];
+    assert_eq!(
+        item.extensions()["foo"]["thing"][0].attrs()["xmlns:foo"],
+        "http://example.com/foo"
+    );
+    assert_eq!(
+        item.extra_attrs().get("xmlns:bar").map(String::as_str),
+        Some("http://example.com/bar")
+    );
+}
+
 #[test]
 fn verify_write_format() {
     let item = ItemBuilder::default()
@@ -296,3 +603,593 @@ fn test_escape() {
         "<title>"
     );
 }
+
+#[test]
+fn write_version_2_0() {
+    let input = include_str!("data/rss2sample.xml");
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+    let (output, warnings) = channel
+        .write_with_options(Vec::new(), &WriteOptions::default())
+        .unwrap();
+    assert!(warnings.is_empty());
+    assert_eq!(String::from_utf8(output).unwrap(), channel.to_string());
+}
+
+#[test]
+fn write_version_0_91() {
+    let mut dublin_core = extension::dublincore::DublinCoreExtension::default();
+    dublin_core.set_creators(vec!["Creator".to_string()]);
+    let items = (0..20)
+        .map(|i| {
+            ItemBuilder::default()
+                .title(format!("Item {}", i))
+                .guid(
+                    GuidBuilder::default()
+                        .value(format!("http://example.com/{}", i))
+                        .permalink(true)
+                        .build(),
+                )
+                .categories(vec![CategoryBuilder::default().name("Category").build()])
+                .pub_date("Sat, 07 Sep 2002 00:00:01 GMT".to_string())
+                .build()
+        })
+        .collect::<Vec<_>>();
+    let channel = ChannelBuilder::default()
+        .title("Title")
+        .link("http://example.com/")
+        .description("Description")
+        .language("en-us".to_string())
+        .generator("Generator".to_string())
+        .dublin_core_ext(dublin_core)
+        .items(items)
+        .build();
+
+    let options = WriteOptions {
+        version: RssVersion::V0_91,
+        ..Default::default()
+    };
+    let (output, warnings) = channel.write_with_options(Vec::new(), &options).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains(r#"<rss version="0.91">"#));
+    assert!(!output.contains("xmlns:dc"));
+
+    let paths = warnings.iter().map(|w| w.path.as_str()).collect::<Vec<_>>();
+    assert_eq!(
+        paths[..4],
+        [
+            "channel/generator",
+            "channel/dc:*",
+            "channel/item[16]",
+            "channel/item/category"
+        ]
+    );
+    assert_eq!(paths[4], "channel/item/pubDate");
+    assert_eq!(warnings.len(), 3 + 2 * 15);
+    assert_eq!(
+        warnings[2].message,
+        "RSS 0.91 allows at most 15 items, 5 items were left out"
+    );
+
+    let parsed = output.parse::<Channel>().expect("failed to parse xml");
+    assert_eq!(parsed.generator(), None);
+    assert_eq!(parsed.dublin_core_ext(), None);
+    assert_eq!(parsed.items().len(), 15);
+    assert_eq!(parsed.items()[3].link(), Some("http://example.com/3"));
+    assert_eq!(parsed.items()[3].guid(), None);
+    assert!(parsed.items()[3].categories().is_empty());
+}
+
+#[test]
+fn write_version_0_91_requires_language() {
+    let channel = ChannelBuilder::default().title("Title").build();
+    let options = WriteOptions {
+        version: RssVersion::V0_91,
+        ..Default::default()
+    };
+    match channel.write_with_options(Vec::new(), &options) {
+        Err(Error::Version { version, .. }) => assert_eq!(version, RssVersion::V0_91),
+        other => panic!(
+            "unexpected result: {:?}",
+            other.map(|(_, warnings)| warnings)
+        ),
+    }
+}
+
+#[test]
+fn write_version_0_92() {
+    let input = include_str!("data/item.xml");
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+    let options = WriteOptions {
+        version: RssVersion::V0_92,
+        ..Default::default()
+    };
+    let (output, warnings) = channel.write_with_options(Vec::new(), &options).unwrap();
+    let parsed = Channel::read_from(&output[..]).expect("failed to parse xml");
+
+    let item = &channel.items()[0];
+    let written = &parsed.items()[0];
+    assert_eq!(written.enclosure(), item.enclosure());
+    assert_eq!(written.source(), item.source());
+    assert_eq!(written.categories(), item.categories());
+    assert_eq!(written.author(), None);
+    assert_eq!(written.comments(), None);
+    assert_eq!(written.pub_date(), None);
+    assert!(warnings
+        .iter()
+        .any(|warning| warning.path == "channel/item/author"));
+}
+
+#[test]
+fn write_version_0_92_extra_attributes() {
+    let input = include_str!("data/attributes.xml");
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+    let options = WriteOptions {
+        version: RssVersion::V0_92,
+        ..Default::default()
+    };
+    let (output, warnings) = channel.write_with_options(Vec::new(), &options).unwrap();
+    let parsed = Channel::read_from(&output[..]).expect("failed to parse xml");
+
+    assert_eq!(parsed.extra_attrs(), channel.extra_attrs());
+    let item = &parsed.items()[0];
+    assert_eq!(item.extra_attrs().len(), 1);
+    assert_eq!(item.extra_attrs().get("xml:lang").unwrap(), "fr");
+    assert!(item.enclosure().unwrap().extra_attrs().is_empty());
+    assert!(warnings
+        .iter()
+        .any(|warning| warning.path == "channel/item/enclosure/@vendor:id"));
+}
+
+#[test]
+fn write_version_1_0() {
+    let channel = ChannelBuilder::default()
+        .title("Title")
+        .link("http://example.com/")
+        .description("Description")
+        .language("en-us".to_string())
+        .ttl("60".to_string())
+        .items(vec![ItemBuilder::default()
+            .title("Item".to_string())
+            .link("http://example.com/item".to_string())
+            .author("Author".to_string())
+            .comments("http://example.com/comments".to_string())
+            .build()])
+        .build();
+    let options = WriteOptions {
+        version: RssVersion::V1_0,
+        ..Default::default()
+    };
+    let (output, warnings) = channel.write_with_options(Vec::new(), &options).unwrap();
+    let paths = warnings.iter().map(|w| w.path.as_str()).collect::<Vec<_>>();
+    assert_eq!(paths, ["channel/ttl", "channel/item/comments"]);
+
+    let parsed = Channel::read_from(&output[..]).expect("failed to parse xml");
+    let dublin_core = parsed.dublin_core_ext().unwrap();
+    assert_eq!(dublin_core.languages(), ["en-us"]);
+    let dublin_core = parsed.items()[0].dublin_core_ext().unwrap();
+    assert_eq!(dublin_core.creators(), ["Author"]);
+    assert_eq!(parsed.items()[0].title(), Some("Item"));
+}
+
+#[test]
+fn write_text_styles() {
+    let channel = ChannelBuilder::default()
+        .description("Channel & description")
+        .items(vec![ItemBuilder::default()
+            .description("Item & description".to_string())
+            .content("<p>Content with ]]> inside</p>".to_string())
+            .build()])
+        .build();
+
+    let output = channel.to_string();
+    assert!(output.contains("<description>Channel &amp; description</description>"));
+    assert!(output.contains("<description><![CDATA[Item & description]]></description>"));
+    assert!(output.contains("<![CDATA[<p>Content with ]]]]><![CDATA[> inside</p>]]>"));
+    let parsed = output.parse::<Channel>().unwrap();
+    assert_eq!(parsed.items(), channel.items());
+
+    let options = WriteOptions {
+        channel_description: TextStyle::CData,
+        item_description: TextStyle::Escaped,
+        content: TextStyle::Escaped,
+        ..Default::default()
+    };
+    let (output, _) = channel.write_with_options(Vec::new(), &options).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("<description><![CDATA[Channel & description]]></description>"));
+    assert!(output.contains("<description>Item &amp; description</description>"));
+    assert!(output.contains("&lt;p&gt;Content with ]]&gt; inside&lt;/p&gt;"));
+    let parsed = output.parse::<Channel>().unwrap();
+    assert_eq!(parsed.description(), channel.description());
+    assert_eq!(parsed.items(), channel.items());
+}
+
+#[test]
+fn write_declaration_and_stylesheets() {
+    let channel = ChannelBuilder::default().title("Title").build();
+    let options = WriteOptions {
+        xml_declaration: false,
+        stylesheets: vec![
+            Stylesheet::xsl("/feed.xsl?a=1&b=2"),
+            Stylesheet::css("/feed.css"),
+        ],
+        ..Default::default()
+    };
+    let (output, _) = channel.write_with_options(Vec::new(), &options).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.starts_with(concat!(
+        r#"<?xml-stylesheet type="text/xsl" href="/feed.xsl?a=1&amp;b=2"?>"#,
+        r#"<?xml-stylesheet type="text/css" href="/feed.css"?>"#,
+        "<rss "
+    )));
+    assert_eq!(output.parse::<Channel>().unwrap(), channel);
+}
+
+#[test]
+fn write_encoding() {
+    let channel = ChannelBuilder::default().title("Café à 5 €").build();
+    let options = WriteOptions {
+        encoding: Some("ISO-8859-15".to_string()),
+        ..Default::default()
+    };
+    let (output, _) = channel.write_with_options(Vec::new(), &options).unwrap();
+    assert!(output.starts_with(br#"<?xml version="1.0" encoding="iso-8859-15"?>"#));
+    let title = b"<title>Caf\xe9 \xe0 5 \xa4</title>";
+    assert!(output.windows(title.len()).any(|bytes| bytes == title));
+    assert_eq!(Channel::read_from(&output[..]).unwrap(), channel);
+
+    // characters that the encoding cannot represent are written as character references
+    let options = WriteOptions {
+        encoding: Some("iso-8859-2".to_string()),
+        ..Default::default()
+    };
+    let (output, _) = channel.write_with_options(Vec::new(), &options).unwrap();
+    let title = b"<title>Caf\xe9 &#224; 5 &#8364;</title>";
+    assert!(output.windows(title.len()).any(|bytes| bytes == title));
+    assert_eq!(Channel::read_from(&output[..]).unwrap(), channel);
+
+    let options = WriteOptions {
+        encoding: Some("utf-16".to_string()),
+        ..Default::default()
+    };
+    match channel.write_with_options(Vec::new(), &options) {
+        Err(Error::Encoding(label)) => assert_eq!(label, "utf-16"),
+        other => panic!(
+            "unexpected result: {:?}",
+            other.map(|(_, warnings)| warnings)
+        ),
+    }
+}
+
+#[test]
+fn write_empty_elements() {
+    let mut tag = BTreeMap::new();
+    tag.insert(
+        "tag".to_owned(),
+        vec![extension::ExtensionBuilder::default()
+            .name("ext:tag")
+            .build()],
+    );
+    let mut item = ItemBuilder::default()
+        .title(String::new())
+        .enclosure(
+            EnclosureBuilder::default()
+                .url("http://example.com/1.mp3")
+                .build(),
+        )
+        .build();
+    item.extensions.insert("ext".to_owned(), tag);
+    let channel = ChannelBuilder::default().title("Title").item(item).build();
+
+    let output = channel.to_string();
+    assert!(output.contains("<title></title>"));
+    assert!(output.contains("<ext:tag></ext:tag>"));
+    assert!(output.contains(r#"<enclosure url="http://example.com/1.mp3" length="" type=""/>"#));
+
+    let options = WriteOptions {
+        empty_elements: Some(EmptyElements::SelfClosing),
+        ..Default::default()
+    };
+    let (output, _) = channel.write_with_options(Vec::new(), &options).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("<title>Title</title>"));
+    assert!(output.contains("<item><title/>"));
+    assert!(output.contains("<ext:tag/>"));
+    let parsed = output.parse::<Channel>().unwrap();
+    assert_eq!(
+        parsed.items()[0].extensions(),
+        channel.items()[0].extensions()
+    );
+
+    let options = WriteOptions {
+        indent: Some((b' ', 2)),
+        empty_elements: Some(EmptyElements::SelfClosing),
+        ..Default::default()
+    };
+    let (output, _) = channel.write_with_options(Vec::new(), &options).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("\n      <ext:tag/>\n"));
+
+    let options = WriteOptions {
+        empty_elements: Some(EmptyElements::Expanded),
+        ..Default::default()
+    };
+    let (output, _) = channel.write_with_options(Vec::new(), &options).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output
+        .contains(r#"<enclosure url="http://example.com/1.mp3" length="" type=""></enclosure>"#));
+    let parsed = output.parse::<Channel>().unwrap();
+    assert_eq!(
+        parsed.items()[0].enclosure(),
+        channel.items()[0].enclosure()
+    );
+}
+
+/// Writes the items of `channel` one at a time with a `ChannelWriter`.
+fn write_streaming(channel: &Channel, options: &WriteOptions) -> (Vec<u8>, Vec<WriteWarning>) {
+    let mut writer = ChannelWriter::with_options(Vec::new(), channel, options).unwrap();
+    for item in channel.items() {
+        writer.write_item(item).unwrap();
+    }
+    let warnings = writer.warnings().to_vec();
+    (writer.finish().unwrap(), warnings)
+}
+
+#[test]
+fn write_channel_writer() {
+    for input in &[
+        include_str!("data/rss2sample.xml"),
+        include_str!("data/unknown.xml"),
+        include_str!("data/itunes.xml"),
+        include_str!("data/extension.xml"),
+    ] {
+        let channel = input.parse::<Channel>().expect("failed to parse xml");
+        let (output, warnings) = write_streaming(&channel, &WriteOptions::default());
+        assert!(warnings.is_empty());
+        assert_eq!(String::from_utf8(output).unwrap(), channel.to_string());
+
+        let options = WriteOptions {
+            indent: Some((b' ', 2)),
+            encoding: Some("iso-8859-1".to_string()),
+            empty_elements: Some(EmptyElements::SelfClosing),
+            ..Default::default()
+        };
+        let (output, _) = write_streaming(&channel, &options);
+        let (expected, _) = channel.write_with_options(Vec::new(), &options).unwrap();
+        assert_eq!(output, expected);
+    }
+}
+
+#[test]
+fn write_channel_writer_namespaces() {
+    let mut tag = BTreeMap::new();
+    tag.insert(
+        "tag".to_owned(),
+        vec![extension::ExtensionBuilder::default()
+            .name("ext:tag")
+            .value("Tag".to_owned())
+            .build()],
+    );
+    let mut item = ItemBuilder::default()
+        .title("Item".to_owned())
+        .content("<p>Content</p>".to_owned())
+        .dublin_core_ext(
+            extension::dublincore::DublinCoreExtensionBuilder::default()
+                .creators(vec!["Creator".to_owned()])
+                .build(),
+        )
+        .build();
+    item.extensions.insert("ext".to_owned(), tag);
+
+    let channel = ChannelBuilder::default().title("Title").build();
+    let mut writer = ChannelWriter::new(Vec::new(), &channel);
+    writer
+        .register_namespace("ext", "http://example.com/ext")
+        .unwrap()
+        .register_item(&ItemBuilder::default().content(String::new()).build())
+        .unwrap()
+        .register_item(
+            &ItemBuilder::default()
+                .dublin_core_ext(extension::dublincore::DublinCoreExtension::default())
+                .build(),
+        )
+        .unwrap();
+    writer.write_item(&item).unwrap();
+    writer.write_item(&item).unwrap();
+    let output = String::from_utf8(writer.finish().unwrap()).unwrap();
+
+    assert!(output.contains(r#"xmlns:ext="http://example.com/ext""#));
+    assert!(output.contains(r#"xmlns:content="http://purl.org/rss/1.0/modules/content/""#));
+    assert!(output.contains(r#"xmlns:dc="http://purl.org/dc/elements/1.1/""#));
+    let parsed = output.parse::<Channel>().expect("failed to parse xml");
+    assert_eq!(parsed.items(), &[item.clone(), item]);
+}
+
+#[test]
+fn write_channel_writer_unregistered_namespaces() {
+    let item = ItemBuilder::default()
+        .title("Item".to_owned())
+        .itunes_ext(
+            extension::itunes::ITunesItemExtensionBuilder::default()
+                .author("Author".to_owned())
+                .build(),
+        )
+        .build();
+
+    let mut writer = ChannelWriter::new(Vec::new(), &Channel::default());
+    writer.write_item(&item).unwrap();
+    writer.write_item(&item).unwrap();
+    assert!(writer.warnings().is_empty());
+    let output = String::from_utf8(writer.finish().unwrap()).unwrap();
+
+    assert!(output.contains(r#"<item xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd">"#));
+    let parsed = output.parse::<Channel>().expect("failed to parse xml");
+    assert_eq!(parsed.items().len(), 2);
+    for parsed in parsed.items() {
+        assert_eq!(parsed.itunes_ext(), item.itunes_ext());
+    }
+}
+
+#[test]
+fn write_channel_writer_register_late() {
+    let mut writer = ChannelWriter::new(Vec::new(), &Channel::default());
+    writer.write_item(&Default::default()).unwrap();
+    let result = writer.register_namespace("ext", "http://example.com/ext");
+    assert!(matches!(result, Err(Error::HeaderWritten)));
+    let result = writer.register_item(&Default::default());
+    assert!(matches!(result, Err(Error::HeaderWritten)));
+}
+
+#[test]
+fn write_channel_writer_versions() {
+    let channel = ChannelBuilder::default()
+        .title("Title")
+        .language("en-us".to_string())
+        .build();
+    let item = ItemBuilder::default()
+        .title("Item".to_string())
+        .comments("http://example.com/comments".to_string())
+        .build();
+
+    let options = WriteOptions {
+        version: RssVersion::V0_91,
+        ..Default::default()
+    };
+    let mut writer = ChannelWriter::with_options(Vec::new(), &channel, &options).unwrap();
+    for _ in 0..20 {
+        writer.write_item(&item).unwrap();
+    }
+    let paths = writer
+        .warnings()
+        .iter()
+        .map(|warning| warning.path.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(paths.len(), 15);
+    assert_eq!(paths[0], "channel/item/comments");
+    assert_eq!(paths[14], "channel/item[15]/comments");
+
+    let output = String::from_utf8(writer.finish().unwrap()).unwrap();
+    assert!(output.contains(r#"<rss version="0.91">"#));
+    let parsed = output.parse::<Channel>().expect("failed to parse xml");
+    assert_eq!(parsed.items().len(), 15);
+    assert_eq!(parsed.items()[0].comments(), None);
+
+    let options = WriteOptions {
+        version: RssVersion::V1_0,
+        ..Default::default()
+    };
+    match ChannelWriter::with_options(Vec::new(), &channel, &options) {
+        Err(Error::Version { version, .. }) => assert_eq!(version, RssVersion::V1_0),
+        Err(err) => panic!("unexpected error: {}", err),
+        Ok(_) => panic!("RSS 1.0 cannot be streamed"),
+    }
+}
+
+fn canonical(channel: &Channel) -> String {
+    String::from_utf8(channel.write_canonical(Vec::new()).unwrap()).unwrap()
+}
+
+fn read_markup(input: &str) -> Channel {
+    let options = ParseOptions {
+        preserve_markup: true,
+        ..Default::default()
+    };
+    Channel::read_from_with_options(input.as_bytes(), options)
+        .expect("failed to parse xml")
+        .channel
+}
+
+#[test]
+fn write_canonical() {
+    let input = include_str!("data/rss2sample.xml");
+    let channel = input.parse::<Channel>().expect("failed to parse xml");
+
+    let output = canonical(&channel);
+    assert!(output.starts_with(r#"<?xml version="1.0" encoding="utf-8"?><rss version="2.0">"#));
+    assert!(!output.contains('\n'));
+    assert!(!output.contains("CDATA"));
+
+    let parsed = output.parse::<Channel>().expect("failed to parse xml");
+    assert_eq!(parsed.items().len(), channel.items().len());
+    assert_eq!(canonical(&parsed), output);
+    assert_eq!(
+        parsed.content_hash().unwrap(),
+        channel.content_hash().unwrap()
+    );
+}
+
+#[test]
+fn write_canonical_equivalent() {
+    let first = read_markup(
+        r#"<rss version="2.0"><channel><title>Title</title>
+        <description><p class="a" id="b">A &amp; B</p></description>
+        <item><title>Item</title><description><![CDATA[<b>bold</b>]]></description>
+        <enclosure url="http://example.com/1.mp3" length="1" type="audio/mpeg"/></item>
+        </channel></rss>"#,
+    );
+    let second = read_markup(
+        r#"<rss version="2.0">
+          <channel>
+            <title>  Title  </title>
+            <description><p id='b' class='a'>A &#38; B</p></description>
+            <item>
+              <title>Item</title>
+              <description>&lt;b&gt;bold&lt;/b&gt;</description>
+              <enclosure type="audio/mpeg" length="1" url="http://example.com/1.mp3"></enclosure>
+            </item>
+          </channel>
+        </rss>"#,
+    );
+    assert_ne!(first, second);
+
+    let output = canonical(&first);
+    assert!(output.contains(r#"<description><p class="a" id="b">A &amp; B</p></description>"#));
+    assert!(output
+        .contains(r#"<enclosure length="1" type="audio/mpeg" url="http://example.com/1.mp3"/>"#));
+    assert_eq!(canonical(&second), output);
+    assert_eq!(
+        first.content_hash().unwrap(),
+        second.content_hash().unwrap()
+    );
+}
+
+#[test]
+fn write_canonical_content_hash() {
+    let item = ItemBuilder::default().title("Item".to_string()).build();
+    let mut channel = ChannelBuilder::default()
+        .title("Title")
+        .items(vec![item])
+        .build();
+    let hash = channel.content_hash().unwrap();
+    assert_eq!(channel.clone().content_hash().unwrap(), hash);
+
+    channel.items_mut()[0].set_title("Another item".to_string());
+    assert_ne!(channel.content_hash().unwrap(), hash);
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn write_canonical_dates() {
+    let mut dublin_core = extension::dublincore::DublinCoreExtension::default();
+    dublin_core.set_dates(vec!["2017-01-01T13:00:00+01:00".to_string()]);
+    let first = ChannelBuilder::default()
+        .pub_date("Sun, 01 Jan 2017 12:00:00 GMT".to_string())
+        .last_build_date("sometime".to_string())
+        .dublin_core_ext(dublin_core)
+        .build();
+
+    let mut dublin_core = extension::dublincore::DublinCoreExtension::default();
+    dublin_core.set_dates(vec!["2017-01-01T12:00:00Z".to_string()]);
+    let second = ChannelBuilder::default()
+        .pub_date("2017-01-01T07:00:00-05:00".to_string())
+        .last_build_date("sometime".to_string())
+        .dublin_core_ext(dublin_core)
+        .build();
+
+    let output = canonical(&first);
+    assert!(output.contains("<pubDate>Sun, 1 Jan 2017 12:00:00 +0000</pubDate>"));
+    assert!(output.contains("<lastBuildDate>sometime</lastBuildDate>"));
+    assert!(output.contains("<dc:date>2017-01-01T12:00:00Z</dc:date>"));
+    assert_eq!(canonical(&second), output);
+    assert_eq!(first.pub_date(), Some("Sun, 01 Jan 2017 12:00:00 GMT"));
+}